@@ -0,0 +1,320 @@
+use crate::matrices::Matrix4;
+use crate::point::Point;
+use crate::ray::Ray;
+
+/// Axis aligned box around a primitive. Primitives without an end, like a plane, are
+/// bounded by an infinite box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+pub(crate) fn axis(point: &Point, axis: usize) -> f64 {
+    match axis {
+        0 => point.x,
+        1 => point.y,
+        _ => point.z,
+    }
+}
+
+impl BoundingBox {
+    pub fn new(min: Point, max: Point) -> Self {
+        Self { min, max }
+    }
+
+    /// Box that contains nothing, merging anything into it gives the merged box back.
+    pub fn empty() -> Self {
+        Self::new(
+            Point::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        )
+    }
+
+    pub fn infinite() -> Self {
+        Self::new(
+            Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+            Point::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    pub fn is_infinite(&self) -> bool {
+        !self.is_empty()
+            && [self.min, self.max]
+                .iter()
+                .any(|p| !(p.x.is_finite() && p.y.is_finite() && p.z.is_finite()))
+    }
+
+    pub fn add_point(self, point: &Point) -> Self {
+        Self::new(
+            Point::new(
+                self.min.x.min(point.x),
+                self.min.y.min(point.y),
+                self.min.z.min(point.z),
+            ),
+            Point::new(
+                self.max.x.max(point.x),
+                self.max.y.max(point.y),
+                self.max.z.max(point.z),
+            ),
+        )
+    }
+
+    pub fn merge(self, other: &BoundingBox) -> Self {
+        self.add_point(&other.min).add_point(&other.max)
+    }
+
+    pub fn centroid(&self) -> Point {
+        Point::new(
+            (self.min.x + self.max.x) / 2.0,
+            (self.min.y + self.max.y) / 2.0,
+            (self.min.z + self.max.z) / 2.0,
+        )
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+
+        let (dx, dy, dz) = (
+            self.max.x - self.min.x,
+            self.max.y - self.min.y,
+            self.max.z - self.min.z,
+        );
+
+        2.0 * (dx * dy + dy * dz + dz * dx)
+    }
+
+    /// Grow the box on every side, covers the tolerance primitives use when intersecting.
+    pub fn padded(self, padding: f64) -> Self {
+        if self.is_empty() || self.is_infinite() {
+            return self;
+        }
+
+        Self::new(
+            Point::new(
+                self.min.x - padding,
+                self.min.y - padding,
+                self.min.z - padding,
+            ),
+            Point::new(
+                self.max.x + padding,
+                self.max.y + padding,
+                self.max.z + padding,
+            ),
+        )
+    }
+
+    /// Box around all eight transformed corners.
+    pub fn transform(&self, transformation: &Matrix4) -> Self {
+        if self.is_empty() || self.is_infinite() {
+            return *self;
+        }
+
+        let (min, max) = (self.min, self.max);
+
+        [
+            Point::new(min.x, min.y, min.z),
+            Point::new(min.x, min.y, max.z),
+            Point::new(min.x, max.y, min.z),
+            Point::new(min.x, max.y, max.z),
+            Point::new(max.x, min.y, min.z),
+            Point::new(max.x, min.y, max.z),
+            Point::new(max.x, max.y, min.z),
+            Point::new(max.x, max.y, max.z),
+        ]
+        .iter()
+        .fold(Self::empty(), |bounds, corner| {
+            bounds.add_point(&(*transformation * *corner))
+        })
+    }
+
+    /// Slab test, true when the ray passes the box anywhere between `t_min` and `t_max`.
+    pub fn intersects(&self, ray: &Ray, t_min: f64, t_max: f64) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+
+        if self.is_infinite() {
+            return true;
+        }
+
+        let origin = [ray.origin.x, ray.origin.y, ray.origin.z];
+        let direction = [ray.direction.x, ray.direction.y, ray.direction.z];
+        let (mut t_min, mut t_max) = (t_min, t_max);
+
+        for index in 0..3 {
+            let (low, high) = (axis(&self.min, index), axis(&self.max, index));
+
+            // a ray parallel to the slab gets infinite times, so it misses unless it starts
+            // between the planes; one starting on a plane gets NaN, which min/max skip
+            let inverse = 1.0 / direction[index];
+            let t0 = (low - origin[index]) * inverse;
+            let t1 = (high - origin[index]) * inverse;
+
+            t_min = t_min.max(t0.min(t1));
+            t_max = t_max.min(t0.max(t1));
+
+            if t_min > t_max {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+#[cfg(test)]
+mod bounding_box_tests {
+    use crate::acceleration::BoundingBox;
+    use crate::point::Point;
+    use crate::ray::Ray;
+    use crate::transformations::{Over, Transform};
+    use crate::vector::Vector3;
+    use std::f64::consts::{FRAC_1_SQRT_2, PI, SQRT_2};
+
+    #[test]
+    fn bounding_box_empty() {
+        let bounds = BoundingBox::empty();
+
+        assert!(bounds.is_empty());
+        assert!(!bounds.is_infinite());
+        assert_eq!(0.0, bounds.surface_area());
+    }
+
+    #[test]
+    fn bounding_box_add_points() {
+        let bounds = BoundingBox::empty()
+            .add_point(&Point::new(-5.0, 2.0, 0.0))
+            .add_point(&Point::new(7.0, 0.0, -3.0));
+        let expected_bounds =
+            BoundingBox::new(Point::new(-5.0, 0.0, -3.0), Point::new(7.0, 2.0, 0.0));
+
+        assert_eq!(expected_bounds, bounds);
+    }
+
+    #[test]
+    fn bounding_box_merge() {
+        let bounds_a = BoundingBox::new(Point::new(-5.0, -2.0, 0.0), Point::new(7.0, 4.0, 4.0));
+        let bounds_b = BoundingBox::new(Point::new(8.0, -7.0, -2.0), Point::new(14.0, 2.0, 8.0));
+        let expected_bounds =
+            BoundingBox::new(Point::new(-5.0, -7.0, -2.0), Point::new(14.0, 4.0, 8.0));
+
+        assert_eq!(expected_bounds, bounds_a.merge(&bounds_b));
+    }
+
+    #[test]
+    fn bounding_box_transform() {
+        let bounds = BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0));
+        let transformation = Transform::Rotate(Over::Y, PI / 4.0).transformation()
+            * Transform::Rotate(Over::X, PI / 4.0).transformation();
+        let expected_bounds = BoundingBox::new(
+            Point::new(-1.0 - FRAC_1_SQRT_2, -SQRT_2, -1.0 - FRAC_1_SQRT_2),
+            Point::new(1.0 + FRAC_1_SQRT_2, SQRT_2, 1.0 + FRAC_1_SQRT_2),
+        );
+
+        assert_eq!(expected_bounds, bounds.transform(&transformation));
+    }
+
+    #[test]
+    fn bounding_box_infinite_stays_infinite() {
+        let bounds = BoundingBox::infinite()
+            .transform(&Transform::Translate(1.0, 2.0, 3.0).transformation());
+
+        assert!(bounds.is_infinite());
+    }
+
+    #[test]
+    fn bounding_box_ray_intersection() {
+        let bounds = BoundingBox::new(Point::new(5.0, -2.0, 0.0), Point::new(11.0, 4.0, 7.0));
+
+        let cases = vec![
+            (
+                Point::new(15.0, 1.0, 2.0),
+                Vector3::new(-1.0, 0.0, 0.0),
+                true,
+            ),
+            (
+                Point::new(-5.0, -1.0, 4.0),
+                Vector3::new(1.0, 0.0, 0.0),
+                true,
+            ),
+            (
+                Point::new(7.0, 6.0, 5.0),
+                Vector3::new(0.0, -1.0, 0.0),
+                true,
+            ),
+            (
+                Point::new(9.0, -5.0, 6.0),
+                Vector3::new(0.0, 1.0, 0.0),
+                true,
+            ),
+            (
+                Point::new(8.0, 2.0, 12.0),
+                Vector3::new(0.0, 0.0, -1.0),
+                true,
+            ),
+            (Point::new(8.0, 1.0, 3.5), Vector3::new(0.0, 0.0, 1.0), true),
+            (
+                Point::new(9.0, -1.0, -8.0),
+                Vector3::new(2.0, 4.0, 6.0),
+                false,
+            ),
+            (
+                Point::new(8.0, 3.0, -4.0),
+                Vector3::new(6.0, 2.0, 4.0),
+                false,
+            ),
+            (
+                Point::new(9.0, -1.0, -2.0),
+                Vector3::new(4.0, 6.0, 2.0),
+                false,
+            ),
+            (
+                Point::new(4.0, 0.0, 9.0),
+                Vector3::new(0.0, 0.0, -1.0),
+                false,
+            ),
+            (
+                Point::new(8.0, 6.0, -1.0),
+                Vector3::new(0.0, -1.0, 0.0),
+                false,
+            ),
+            (
+                Point::new(12.0, 5.0, 4.0),
+                Vector3::new(-1.0, 0.0, 0.0),
+                false,
+            ),
+        ];
+
+        for (origin, direction, expected) in cases {
+            let ray = Ray::new(origin, direction.normalize());
+
+            assert_eq!(
+                expected,
+                bounds.intersects(&ray, f64::NEG_INFINITY, f64::INFINITY)
+            );
+        }
+    }
+
+    #[test]
+    fn bounding_box_ray_intersection_limited() {
+        let bounds = BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0));
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert!(bounds.intersects(&ray, 0.0, 10.0));
+        assert!(!bounds.intersects(&ray, 0.0, 3.0));
+        assert!(!bounds.intersects(&ray, 7.0, 10.0));
+    }
+}
@@ -0,0 +1,376 @@
+use crate::acceleration::bounding_box::axis;
+use crate::acceleration::BoundingBox;
+use crate::ray::Ray;
+
+const BINS: usize = 12;
+const MAX_LEAF_SIZE: usize = 4;
+const TRAVERSAL_COST: f64 = 1.0;
+
+/// Chooses how `World` finds the primitives a ray may hit. Brute force tests every
+/// primitive and is kept to check the hierarchy against.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Acceleration {
+    #[default]
+    Bvh,
+    BruteForce,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Leaf {
+        bounds: BoundingBox,
+        start: usize,
+        end: usize,
+    },
+    Branch {
+        bounds: BoundingBox,
+        left: usize,
+        right: usize,
+    },
+}
+
+/// Bounding volume hierarchy over indices of bounded items, built with binned surface
+/// area heuristic. Items with infinite bounds are kept aside and visited for every ray.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bvh {
+    bounds: Vec<BoundingBox>,
+    nodes: Vec<Node>,
+    indices: Vec<usize>,
+    unbounded: Vec<usize>,
+}
+
+#[derive(Clone, Copy)]
+struct Bin {
+    bounds: BoundingBox,
+    count: usize,
+}
+
+impl Bvh {
+    pub fn build(bounds: &[BoundingBox]) -> Self {
+        let (unbounded, mut indices): (Vec<usize>, Vec<usize>) =
+            (0..bounds.len()).partition(|&index| bounds[index].is_infinite());
+
+        indices.retain(|&index| !bounds[index].is_empty());
+
+        let mut bvh = Self {
+            bounds: bounds.to_vec(),
+            nodes: Vec::with_capacity(indices.len() * 2),
+            indices,
+            unbounded,
+        };
+
+        if !bvh.indices.is_empty() {
+            bvh.split(bounds, 0, bvh.indices.len());
+        }
+
+        bvh
+    }
+
+    fn split(&mut self, bounds: &[BoundingBox], start: usize, end: usize) -> usize {
+        let node_bounds = self.indices[start..end]
+            .iter()
+            .fold(BoundingBox::empty(), |acc, &index| {
+                acc.merge(&bounds[index])
+            });
+        let leaf = Node::Leaf {
+            bounds: node_bounds,
+            start,
+            end,
+        };
+
+        let count = end - start;
+        let node = self.nodes.len();
+        self.nodes.push(leaf);
+
+        if count <= 1 {
+            return node;
+        }
+
+        let (split_axis, split_position, split_cost) = match self.best_split(bounds, start, end) {
+            Some(split) => split,
+            None => return node,
+        };
+
+        let leaf_cost = count as f64;
+        let split_cost = TRAVERSAL_COST + split_cost / node_bounds.surface_area().max(f64::EPSILON);
+
+        if split_cost >= leaf_cost && count <= MAX_LEAF_SIZE {
+            return node;
+        }
+
+        let middle = self.partition(bounds, start, end, split_axis, split_position);
+
+        if middle == start || middle == end {
+            return node;
+        }
+
+        let left = self.split(bounds, start, middle);
+        let right = self.split(bounds, middle, end);
+
+        self.nodes[node] = Node::Branch {
+            bounds: node_bounds,
+            left,
+            right,
+        };
+
+        node
+    }
+
+    /// Bin centroids along the longest axis and return the split with the lowest cost
+    /// as (axis, position, cost) where the cost is not yet divided by the parent area.
+    fn best_split(
+        &self,
+        bounds: &[BoundingBox],
+        start: usize,
+        end: usize,
+    ) -> Option<(usize, f64, f64)> {
+        let centroids = self.indices[start..end]
+            .iter()
+            .fold(BoundingBox::empty(), |acc, &index| {
+                acc.add_point(&bounds[index].centroid())
+            });
+
+        let split_axis = (0..3)
+            .max_by(|&a, &b| {
+                let extent_a = axis(&centroids.max, a) - axis(&centroids.min, a);
+                let extent_b = axis(&centroids.max, b) - axis(&centroids.min, b);
+                extent_a.total_cmp(&extent_b)
+            })
+            .unwrap_or(0);
+
+        let low = axis(&centroids.min, split_axis);
+        let extent = axis(&centroids.max, split_axis) - low;
+
+        if extent <= 0.0 {
+            return None;
+        }
+
+        let bin_of = |index: usize| {
+            let centroid = axis(&bounds[index].centroid(), split_axis);
+            (((centroid - low) / extent * BINS as f64) as usize).min(BINS - 1)
+        };
+
+        let mut bins = [Bin {
+            bounds: BoundingBox::empty(),
+            count: 0,
+        }; BINS];
+
+        for &index in &self.indices[start..end] {
+            let bin = &mut bins[bin_of(index)];
+            bin.bounds = bin.bounds.merge(&bounds[index]);
+            bin.count += 1;
+        }
+
+        let cost = |bins: &[Bin]| {
+            let (bounds, count) = bins
+                .iter()
+                .fold((BoundingBox::empty(), 0), |(bounds, count), bin| {
+                    (bounds.merge(&bin.bounds), count + bin.count)
+                });
+            (bounds.surface_area() * count as f64, count)
+        };
+
+        (1..BINS)
+            .filter_map(|split| {
+                let (left_cost, left_count) = cost(&bins[..split]);
+                let (right_cost, right_count) = cost(&bins[split..]);
+
+                if left_count == 0 || right_count == 0 {
+                    None
+                } else {
+                    let position = low + extent * split as f64 / BINS as f64;
+                    Some((split_axis, position, left_cost + right_cost))
+                }
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+    }
+
+    fn partition(
+        &mut self,
+        bounds: &[BoundingBox],
+        start: usize,
+        end: usize,
+        split_axis: usize,
+        position: f64,
+    ) -> usize {
+        let mut middle = start;
+
+        for current in start..end {
+            if axis(&bounds[self.indices[current]].centroid(), split_axis) < position {
+                self.indices.swap(middle, current);
+                middle += 1;
+            }
+        }
+
+        middle
+    }
+
+    /// Call `visit` with the index of every item whose bounds the ray passes between
+    /// `t_min` and `t_max`, unbounded items come first. Returning `true` from `visit`
+    /// stops the traversal.
+    pub fn traverse<F: FnMut(usize) -> bool>(
+        &self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        mut visit: F,
+    ) -> bool {
+        for &index in &self.unbounded {
+            if visit(index) {
+                return true;
+            }
+        }
+
+        let mut stack = Vec::with_capacity(32);
+
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(node) = stack.pop() {
+            match &self.nodes[node] {
+                Node::Leaf { bounds, start, end } => {
+                    if bounds.intersects(ray, t_min, t_max) {
+                        for &index in &self.indices[*start..*end] {
+                            if self.bounds[index].intersects(ray, t_min, t_max) && visit(index) {
+                                return true;
+                            }
+                        }
+                    }
+                }
+                Node::Branch {
+                    bounds,
+                    left,
+                    right,
+                } => {
+                    if bounds.intersects(ray, t_min, t_max) {
+                        stack.push(*right);
+                        stack.push(*left);
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Indices of every item the whole line of the ray may hit, in ascending order.
+    pub fn candidates(&self, ray: &Ray) -> Vec<usize> {
+        let mut candidates = vec![];
+
+        self.traverse(ray, f64::NEG_INFINITY, f64::INFINITY, |index| {
+            candidates.push(index);
+            false
+        });
+
+        candidates.sort_unstable();
+        candidates
+    }
+
+    pub fn depth(&self) -> usize {
+        fn depth(nodes: &[Node], node: usize) -> usize {
+            match &nodes[node] {
+                Node::Leaf { .. } => 1,
+                Node::Branch { left, right, .. } => {
+                    1 + depth(nodes, *left).max(depth(nodes, *right))
+                }
+            }
+        }
+
+        if self.nodes.is_empty() {
+            0
+        } else {
+            depth(&self.nodes, 0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod bvh_tests {
+    use crate::acceleration::{BoundingBox, Bvh};
+    use crate::point::Point;
+    use crate::ray::Ray;
+    use crate::vector::Vector3;
+
+    fn unit_box(x: f64, y: f64, z: f64) -> BoundingBox {
+        BoundingBox::new(
+            Point::new(x - 0.5, y - 0.5, z - 0.5),
+            Point::new(x + 0.5, y + 0.5, z + 0.5),
+        )
+    }
+
+    #[test]
+    fn bvh_empty() {
+        let bvh = Bvh::build(&[]);
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert_eq!(0, bvh.depth());
+        assert!(bvh.candidates(&ray).is_empty());
+    }
+
+    #[test]
+    fn bvh_splits_row_of_boxes() {
+        let bounds = (0..64)
+            .map(|i| unit_box(i as f64 * 2.0, 0.0, 0.0))
+            .collect::<Vec<_>>();
+
+        let bvh = Bvh::build(&bounds);
+        let ray = Ray::new(Point::new(10.0, 5.0, 0.0), Vector3::new(0.0, -1.0, 0.0));
+
+        assert!(bvh.depth() > 1);
+        assert_eq!(vec![5], bvh.candidates(&ray));
+    }
+
+    #[test]
+    fn bvh_visits_unbounded_items() {
+        let bounds = vec![
+            unit_box(0.0, 0.0, 0.0),
+            BoundingBox::infinite(),
+            unit_box(10.0, 0.0, 0.0),
+        ];
+
+        let bvh = Bvh::build(&bounds);
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert_eq!(vec![0, 1], bvh.candidates(&ray));
+    }
+
+    #[test]
+    fn bvh_candidates_match_every_box() {
+        let bounds = (0..8)
+            .flat_map(|x| (0..8).map(move |y| unit_box(x as f64 * 1.5, y as f64 * 1.5, 0.0)))
+            .collect::<Vec<_>>();
+
+        let bvh = Bvh::build(&bounds);
+        let ray = Ray::new(
+            Point::new(-2.0, -1.0, -5.0),
+            Vector3::new(1.0, 0.8, 0.5).normalize(),
+        );
+
+        let expected_candidates = (0..bounds.len())
+            .filter(|&index| bounds[index].intersects(&ray, f64::NEG_INFINITY, f64::INFINITY))
+            .collect::<Vec<_>>();
+
+        assert!(!expected_candidates.is_empty());
+        assert_eq!(expected_candidates, bvh.candidates(&ray));
+    }
+
+    #[test]
+    fn bvh_traverse_stops_early() {
+        let bounds = (0..16)
+            .map(|i| unit_box(0.0, 0.0, i as f64 * 2.0))
+            .collect::<Vec<_>>();
+
+        let bvh = Bvh::build(&bounds);
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let mut visited = 0;
+
+        let stopped = bvh.traverse(&ray, 0.0, f64::INFINITY, |_| {
+            visited += 1;
+            true
+        });
+
+        assert!(stopped);
+        assert_eq!(1, visited);
+    }
+}
@@ -0,0 +1,429 @@
+use crate::acceleration::Acceleration;
+use crate::preset::Preset;
+use crate::progressive::Progressive;
+use crate::render::Rendering;
+use crate::sampling::Adaptive;
+use crate::tiling::{TileOrder, Tiling};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+Usage: warp render <scene.yaml> [options]
+
+Options:
+    -o, --output <file>    image to write, `image.png` by default
+    --width <pixels>       horizontal size, overrides the scene camera
+    --height <pixels>      vertical size, overrides the scene camera
+    --depth <n>            reflection and refraction depth
+    --samples <n>          samples per pixel, overrides the scene camera
+    --passes <n>           render progressively, adding up to n passes of samples
+    --time-limit <s>       render progressively until s seconds have passed
+    --noise <threshold>    render progressively until the noise estimate drops this low
+    --snapshots            render progressively, writing the image after every pass
+    --adaptive <contrast>  refine pixels differing this much from a neighbor, up to --samples
+    --threads <n>          number of render threads
+    --tile-size <pixels>   side of the tiles a parallel render is split into, 32 by default
+    --tile-order <order>   order tiles are rendered in: rows, spiral (default) or hilbert
+    --sequential           render on a single thread instead of in parallel tiles
+    --brute-force          test every primitive instead of using the BVH
+    -fast, -slow           quality preset, explicit options take precedence
+    -h, --help             print this message";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Render(Box<RenderOptions>),
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RenderOptions {
+    pub scene: String,
+    pub output: String,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub depth: Option<usize>,
+    pub samples: Option<usize>,
+    pub passes: Option<usize>,
+    pub time_limit: Option<f64>,
+    pub noise: Option<f64>,
+    pub snapshots: bool,
+    pub adaptive: Option<f64>,
+    pub threads: Option<usize>,
+    pub tiling: Tiling,
+    pub render_mode: Rendering,
+    pub acceleration: Acceleration,
+    pub preset: Option<Preset>,
+}
+
+impl RenderOptions {
+    pub fn new(scene: &str) -> Self {
+        Self {
+            scene: scene.to_string(),
+            output: "image.png".to_string(),
+            width: None,
+            height: None,
+            depth: None,
+            samples: None,
+            passes: None,
+            time_limit: None,
+            noise: None,
+            snapshots: false,
+            adaptive: None,
+            threads: None,
+            tiling: Tiling::default(),
+            render_mode: Rendering::Parallel,
+            acceleration: Acceleration::Bvh,
+            preset: None,
+        }
+    }
+
+    /// Depth requested explicitly, or the one of the selected preset.
+    pub fn depth(&self) -> Option<usize> {
+        self.depth
+            .or_else(|| self.preset.map(|preset| preset.depth()))
+    }
+
+    /// Samples per pixel requested explicitly, or the ones of the selected preset.
+    pub fn samples(&self) -> Option<usize> {
+        self.samples
+            .or_else(|| self.preset.map(|preset| preset.samples_per_pixel()))
+    }
+
+    /// Progressive settings when any progressive option is given. A time limit alone keeps
+    /// adding passes until it is spent.
+    pub fn progressive(&self) -> Option<Progressive> {
+        if self.passes.is_none()
+            && self.time_limit.is_none()
+            && self.noise.is_none()
+            && !self.snapshots
+        {
+            return None;
+        }
+
+        let max_passes = match (self.passes, self.time_limit) {
+            (Some(passes), _) => passes,
+            (None, Some(_)) => usize::MAX,
+            (None, None) => Progressive::default().max_passes,
+        };
+        let mut progressive = Progressive::new(max_passes);
+        if let Some(seconds) = self.time_limit {
+            progressive = progressive.with_time_budget(Duration::from_secs_f64(seconds));
+        }
+        if let Some(noise) = self.noise {
+            progressive = progressive.with_noise_threshold(noise);
+        }
+
+        Some(progressive)
+    }
+
+    /// Adaptive settings when a contrast threshold is given, the samples requested bound
+    /// the refinement of a pixel.
+    pub fn adaptive(&self) -> Option<Adaptive> {
+        self.adaptive.map(|threshold| match self.samples() {
+            Some(samples) => Adaptive::new(threshold, samples),
+            None => Adaptive {
+                threshold,
+                ..Adaptive::default()
+            },
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ArgumentError {
+    MissingCommand,
+    UnknownCommand(String),
+    MissingScene,
+    UnknownOption(String),
+    UnexpectedArgument(String),
+    MissingValue(String),
+    InvalidValue(String, String),
+}
+
+impl fmt::Display for ArgumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgumentError::MissingCommand => write!(f, "no command given"),
+            ArgumentError::UnknownCommand(command) => write!(f, "unknown command '{}'", command),
+            ArgumentError::MissingScene => write!(f, "no scene file given"),
+            ArgumentError::UnknownOption(option) => write!(f, "unknown option '{}'", option),
+            ArgumentError::UnexpectedArgument(argument) => {
+                write!(f, "unexpected argument '{}'", argument)
+            }
+            ArgumentError::MissingValue(option) => write!(f, "{} requires a value", option),
+            ArgumentError::InvalidValue(option, value) => {
+                write!(f, "invalid value '{}' for {}", value, option)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArgumentError {}
+
+/// Parse command line arguments, the program name is expected to be skipped already.
+pub fn parse_arguments<I: IntoIterator<Item = String>>(
+    arguments: I,
+) -> Result<Command, ArgumentError> {
+    let mut arguments = arguments.into_iter();
+
+    match arguments.next().as_deref() {
+        Some("render") => parse_render(arguments).map(|options| Command::Render(Box::new(options))),
+        Some("help") | Some("-h") | Some("--help") => Ok(Command::Help),
+        Some(command) => Err(ArgumentError::UnknownCommand(command.to_string())),
+        None => Err(ArgumentError::MissingCommand),
+    }
+}
+
+fn parse_render<I: Iterator<Item = String>>(
+    mut arguments: I,
+) -> Result<RenderOptions, ArgumentError> {
+    let mut scene = None;
+    let mut options = RenderOptions::new("");
+
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "-o" | "--output" => options.output = value(&argument, arguments.next())?,
+            "--width" => options.width = Some(number(&argument, arguments.next())?),
+            "--height" => options.height = Some(number(&argument, arguments.next())?),
+            "--depth" => options.depth = Some(number(&argument, arguments.next())?),
+            "--samples" => options.samples = Some(number(&argument, arguments.next())?),
+            "--passes" => options.passes = Some(number(&argument, arguments.next())?),
+            "--time-limit" => options.time_limit = Some(positive(&argument, arguments.next())?),
+            "--noise" => options.noise = Some(positive(&argument, arguments.next())?),
+            "--snapshots" => options.snapshots = true,
+            "--adaptive" => options.adaptive = Some(positive(&argument, arguments.next())?),
+            "--threads" => options.threads = Some(number(&argument, arguments.next())?),
+            "--tile-size" => options.tiling.tile_size = number(&argument, arguments.next())?,
+            "--tile-order" => {
+                let order = value(&argument, arguments.next())?;
+                options.tiling.order = TileOrder::from_name(&order)
+                    .ok_or(ArgumentError::InvalidValue(argument, order))?;
+            }
+            "--sequential" => options.render_mode = Rendering::Sequential,
+            "--brute-force" => options.acceleration = Acceleration::BruteForce,
+            "-fast" | "-slow" => options.preset = Preset::from_flag(&argument),
+            _ if argument.starts_with('-') => {
+                return Err(ArgumentError::UnknownOption(argument));
+            }
+            _ if scene.is_none() => scene = Some(argument),
+            _ => return Err(ArgumentError::UnexpectedArgument(argument)),
+        }
+    }
+
+    options.scene = scene.ok_or(ArgumentError::MissingScene)?;
+
+    Ok(options)
+}
+
+fn value(option: &str, value: Option<String>) -> Result<String, ArgumentError> {
+    value.ok_or_else(|| ArgumentError::MissingValue(option.to_string()))
+}
+
+/// Sizes, depth, samples, passes and thread count are all positive numbers.
+fn number<T: FromStr + Default + PartialEq>(
+    option: &str,
+    value: Option<String>,
+) -> Result<T, ArgumentError> {
+    let value = self::value(option, value)?;
+
+    match value.parse::<T>() {
+        Ok(number) if number != T::default() => Ok(number),
+        _ => Err(ArgumentError::InvalidValue(option.to_string(), value)),
+    }
+}
+
+/// Time limit, noise threshold and adaptive contrast are positive and finite.
+fn positive(option: &str, value: Option<String>) -> Result<f64, ArgumentError> {
+    let value = self::value(option, value)?;
+
+    match value.parse::<f64>() {
+        Ok(number) if number > 0.0 && number.is_finite() => Ok(number),
+        _ => Err(ArgumentError::InvalidValue(option.to_string(), value)),
+    }
+}
+
+#[cfg(test)]
+mod arguments_tests {
+    use crate::acceleration::Acceleration;
+    use crate::arguments::{parse_arguments, ArgumentError, Command, RenderOptions};
+    use crate::preset::Preset;
+    use crate::progressive::Progressive;
+    use crate::render::Rendering;
+    use crate::sampling::Adaptive;
+    use crate::tiling::{TileOrder, Tiling};
+    use std::time::Duration;
+
+    fn parse(line: &str) -> Result<Command, ArgumentError> {
+        parse_arguments(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn arguments_render_defaults() {
+        let expected_command = Command::Render(Box::new(RenderOptions::new("scene.yaml")));
+
+        assert_eq!(Ok(expected_command), parse("render scene.yaml"));
+    }
+
+    #[test]
+    fn arguments_render_all_options() {
+        let command = parse(
+            "render -o out.png scene.yaml --width 640 --height 480 --depth 5 --samples 8 --threads 4 --tile-size 8 --tile-order hilbert --sequential --brute-force -slow",
+        );
+
+        let expected_command = Command::Render(Box::new(RenderOptions {
+            scene: "scene.yaml".to_string(),
+            output: "out.png".to_string(),
+            width: Some(640),
+            height: Some(480),
+            depth: Some(5),
+            samples: Some(8),
+            passes: None,
+            time_limit: None,
+            noise: None,
+            snapshots: false,
+            adaptive: None,
+            threads: Some(4),
+            tiling: Tiling::new(8, TileOrder::Hilbert),
+            render_mode: Rendering::Sequential,
+            acceleration: Acceleration::BruteForce,
+            preset: Preset::from_flag("-slow"),
+        }));
+
+        assert_eq!(Ok(expected_command), command);
+    }
+
+    #[test]
+    fn arguments_preset_depth() {
+        let depth = |line| match parse(line) {
+            Ok(Command::Render(options)) => options.depth(),
+            _ => None,
+        };
+
+        assert_eq!(None, depth("render scene.yaml"));
+        assert_eq!(Some(50), depth("render scene.yaml -slow"));
+        assert_eq!(Some(3), depth("render scene.yaml -slow --depth 3"));
+    }
+
+    #[test]
+    fn arguments_preset_samples() {
+        let samples = |line| match parse(line) {
+            Ok(Command::Render(options)) => options.samples(),
+            _ => None,
+        };
+
+        assert_eq!(None, samples("render scene.yaml"));
+        assert_eq!(Some(10), samples("render scene.yaml -fast"));
+        assert_eq!(Some(4), samples("render scene.yaml -fast --samples 4"));
+    }
+
+    #[test]
+    fn arguments_progressive() {
+        let progressive = |line| match parse(line) {
+            Ok(Command::Render(options)) => options.progressive(),
+            _ => None,
+        };
+        let expected_timed = Progressive::new(usize::MAX)
+            .with_time_budget(Duration::from_secs_f64(2.5))
+            .with_noise_threshold(0.01);
+
+        assert_eq!(None, progressive("render scene.yaml --samples 4"));
+        assert_eq!(
+            Some(Progressive::default()),
+            progressive("render scene.yaml --snapshots")
+        );
+        assert_eq!(
+            Some(Progressive::new(6)),
+            progressive("render scene.yaml --passes 6")
+        );
+        assert_eq!(
+            Some(expected_timed),
+            progressive("render scene.yaml --time-limit 2.5 --noise 0.01")
+        );
+        assert_eq!(
+            Err(ArgumentError::InvalidValue(
+                "--time-limit".to_string(),
+                "-1".to_string()
+            )),
+            parse("render scene.yaml --time-limit -1")
+        );
+    }
+
+    #[test]
+    fn arguments_adaptive() {
+        let adaptive = |line| match parse(line) {
+            Ok(Command::Render(options)) => options.adaptive(),
+            _ => None,
+        };
+
+        assert_eq!(None, adaptive("render scene.yaml --samples 4"));
+        assert_eq!(
+            Some(Adaptive::new(0.05, 17)),
+            adaptive("render scene.yaml --adaptive 0.05")
+        );
+        assert_eq!(
+            Some(Adaptive::new(0.2, 10)),
+            adaptive("render scene.yaml --adaptive 0.2 -fast")
+        );
+        assert_eq!(
+            Some(Adaptive::new(0.2, 33)),
+            adaptive("render scene.yaml --samples 33 --adaptive 0.2")
+        );
+        assert_eq!(
+            Err(ArgumentError::InvalidValue(
+                "--adaptive".to_string(),
+                "0".to_string()
+            )),
+            parse("render scene.yaml --adaptive 0")
+        );
+    }
+
+    #[test]
+    fn arguments_help() {
+        assert_eq!(Ok(Command::Help), parse("--help"));
+        assert_eq!(Ok(Command::Help), parse("help"));
+    }
+
+    #[test]
+    fn arguments_errors() {
+        assert_eq!(Err(ArgumentError::MissingCommand), parse(""));
+        assert_eq!(
+            Err(ArgumentError::UnknownCommand("draw".to_string())),
+            parse("draw scene.yaml")
+        );
+        assert_eq!(Err(ArgumentError::MissingScene), parse("render -fast"));
+        assert_eq!(
+            Err(ArgumentError::MissingValue("--output".to_string())),
+            parse("render scene.yaml --output")
+        );
+        assert_eq!(
+            Err(ArgumentError::InvalidValue(
+                "--width".to_string(),
+                "wide".to_string()
+            )),
+            parse("render scene.yaml --width wide")
+        );
+        assert_eq!(
+            Err(ArgumentError::InvalidValue(
+                "--threads".to_string(),
+                "0".to_string()
+            )),
+            parse("render scene.yaml --threads 0")
+        );
+        assert_eq!(
+            Err(ArgumentError::InvalidValue(
+                "--tile-order".to_string(),
+                "random".to_string()
+            )),
+            parse("render scene.yaml --tile-order random")
+        );
+        assert_eq!(
+            Err(ArgumentError::UnknownOption("--fast".to_string())),
+            parse("render scene.yaml --fast")
+        );
+        assert_eq!(
+            Err(ArgumentError::UnexpectedArgument("other.yaml".to_string())),
+            parse("render scene.yaml other.yaml")
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use crate::color::Color;
+use crate::patterns::{TextureFilter, TextureImage, TexturePattern, TextureWrap, UvMapping};
+use crate::point::Point;
+use crate::vector::Vector3;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+/// Directions averaged for the ambient light of a background.
+const AMBIENT_SAMPLES: usize = 1024;
+
+/// What rays that miss every primitive see, looked up by the direction of the ray. The y axis
+/// points up.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Background {
+    Solid(Color),
+    /// From `bottom` straight down to `top` straight up.
+    Gradient {
+        bottom: Color,
+        top: Color,
+    },
+    /// Latitude and longitude map of the whole sphere, the center of the image looks along z.
+    Equirectangular(TexturePattern),
+    /// Six faces in the cross layout of `UvMapping::Cube`.
+    CubeMap(TexturePattern),
+}
+
+impl Background {
+    pub fn equirectangular(image: Arc<TextureImage>) -> Self {
+        Background::Equirectangular(
+            TexturePattern::new(image, UvMapping::Spherical).with_wrap(TextureWrap::Clamp),
+        )
+    }
+
+    pub fn cube_map(image: Arc<TextureImage>) -> Self {
+        // blending across the edge of a face would pick up the empty cells of the cross
+        Background::CubeMap(
+            TexturePattern::new(image, UvMapping::Cube)
+                .with_filter(TextureFilter::Nearest)
+                .with_wrap(TextureWrap::Clamp),
+        )
+    }
+
+    pub fn color_at(&self, direction: &Vector3) -> Color {
+        let direction = direction.normalize();
+
+        match self {
+            Background::Solid(color) => *color,
+            Background::Gradient { bottom, top } => {
+                let t = (direction.y + 1.0) / 2.0;
+                *bottom * (1.0 - t) + *top * t
+            }
+            Background::Equirectangular(texture) => {
+                texture.texture_at(&Point::new(direction.x, direction.y, direction.z))
+            }
+            Background::CubeMap(texture) => {
+                // onto the surface of the cube between -1 and 1
+                let side = direction
+                    .x
+                    .abs()
+                    .max(direction.y.abs())
+                    .max(direction.z.abs());
+                let on_cube = direction / side;
+                texture.texture_at(&Point::new(on_cube.x, on_cube.y, on_cube.z))
+            }
+        }
+    }
+
+    /// Average color over every direction, what a surface open to the whole background
+    /// receives. Directions are spread evenly along a Fibonacci spiral.
+    pub fn average(&self) -> Color {
+        if let Background::Solid(color) = self {
+            return *color;
+        }
+
+        let golden_angle = PI * (3.0 - 5.0_f64.sqrt());
+        let total = (0..AMBIENT_SAMPLES).fold(Color::black(), |acc, i| {
+            let y = 1.0 - (2.0 * i as f64 + 1.0) / AMBIENT_SAMPLES as f64;
+            let radius = (1.0 - y * y).sqrt();
+            let angle = golden_angle * i as f64;
+            let direction = Vector3::new(radius * angle.cos(), y, radius * angle.sin());
+
+            acc + self.color_at(&direction)
+        });
+
+        total / AMBIENT_SAMPLES as f64
+    }
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Solid(Color::black())
+    }
+}
+
+#[cfg(test)]
+mod background_tests {
+    use crate::background::Background;
+    use crate::color::Color;
+    use crate::float_eq::ApproxEq;
+    use crate::patterns::TextureImage;
+    use crate::vector::Vector3;
+    use std::sync::Arc;
+
+    fn image(width: usize, height: usize, colors: &[(usize, usize, Color)]) -> Arc<TextureImage> {
+        let mut pixels = vec![Color::black(); width * height];
+        for (x, y, color) in colors {
+            pixels[y * width + x] = *color;
+        }
+
+        Arc::new(TextureImage::new(width, height, pixels))
+    }
+
+    #[test]
+    fn background_solid_and_gradient() {
+        let solid = Background::Solid(Color::new(0.2, 0.4, 0.6));
+        let gradient = Background::Gradient {
+            bottom: Color::black(),
+            top: Color::new(0.0, 0.0, 1.0),
+        };
+
+        assert_eq!(
+            Color::new(0.2, 0.4, 0.6),
+            solid.color_at(&Vector3::new(1.0, 2.0, 3.0))
+        );
+        assert_eq!(
+            Color::new(0.0, 0.0, 1.0),
+            gradient.color_at(&Vector3::new(0.0, 5.0, 0.0))
+        );
+        assert_eq!(
+            Color::new(0.0, 0.0, 0.5),
+            gradient.color_at(&Vector3::new(0.0, 0.0, -2.0))
+        );
+        assert_eq!(
+            Color::black(),
+            gradient.color_at(&Vector3::new(0.0, -1.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn background_equirectangular() {
+        // top row white, bottom row red, the middle rows blue looking along +z
+        let red = Color::new(1.0, 0.0, 0.0);
+        let blue = Color::new(0.0, 0.0, 1.0);
+        let background = Background::equirectangular(image(
+            8,
+            4,
+            &[
+                (3, 0, Color::white()),
+                (4, 0, Color::white()),
+                (3, 1, blue),
+                (4, 1, blue),
+                (3, 2, blue),
+                (4, 2, blue),
+                (3, 3, red),
+                (4, 3, red),
+            ],
+        ));
+
+        assert_eq!(blue, background.color_at(&Vector3::new(0.0, 0.0, 1.0)));
+        assert_eq!(
+            Color::black(),
+            background.color_at(&Vector3::new(0.0, 0.0, -1.0))
+        );
+        assert_eq!(
+            Color::white(),
+            background.color_at(&Vector3::new(0.0, 1.0, 0.0))
+        );
+        assert_eq!(red, background.color_at(&Vector3::new(0.0, -1.0, 0.0)));
+    }
+
+    #[test]
+    fn background_cube_map() {
+        // one pixel per face of the cross, front green and up white
+        let green = Color::new(0.0, 1.0, 0.0);
+        let background =
+            Background::cube_map(image(4, 3, &[(1, 1, green), (1, 0, Color::white())]));
+
+        assert_eq!(green, background.color_at(&Vector3::new(0.2, -0.3, 1.0)));
+        assert_eq!(
+            Color::white(),
+            background.color_at(&Vector3::new(0.1, 2.0, 0.5))
+        );
+        assert_eq!(
+            Color::black(),
+            background.color_at(&Vector3::new(-3.0, 0.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn background_average() {
+        let gradient = Background::Gradient {
+            bottom: Color::black(),
+            top: Color::white(),
+        };
+        let average = gradient.average();
+
+        assert!(average.approx_eq_low(&Color::new(0.5, 0.5, 0.5)));
+        assert_eq!(Color::black(), Background::default().average());
+    }
+}
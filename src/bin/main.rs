@@ -1,160 +1,132 @@
-use image::codecs::png::PngEncoder;
-use image::ImageEncoder;
-use rand::thread_rng;
-use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
 use std::env;
-use std::fs::File;
-use warp::camera::Camera;
-use warp::hit::HittableList;
-use warp::material::Material;
-use warp::objects::sphere::Sphere;
-use warp::objects::triangle::Triangle;
-use warp::preset::{parse_preset, Preset};
-use warp::vec3::{Color, Vec3};
-
-// TODO:remove later or move to separate file
-
-fn write_image(pixels: &[u8], image_width: usize, image_height: usize) {
-    let file = File::create("../../image.png").expect("Unable to create image.png file!");
-    let encoder = PngEncoder::new(file);
-
-    encoder
-        .write_image(
-            pixels,
-            image_width as u32,
-            image_height as u32,
-            image::ColorType::Rgb8,
-        )
-        .expect("Unable to write to image.png file!");
-}
+use std::process;
+use std::sync::atomic::AtomicBool;
+use std::time::Instant;
+use warp::arguments::{parse_arguments, Command, RenderOptions, USAGE};
+use warp::builder::Terraform;
+use warp::render::{Render, Rendering};
 
 fn main() {
-    let image_width = 512;
-
-    let parsed = parse_preset(env::args());
-
-    let mut world = HittableList::new();
+    let command = parse_arguments(env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("error: {}\n\n{}", error, USAGE);
+        process::exit(2);
+    });
 
-    let material_ground = Material::Lambertian {
-        albedo: Color::new(0.5, 0.5, 0.5),
-    };
+    match command {
+        Command::Help => println!("{}", USAGE),
+        Command::Render(options) => render(*options),
+    }
+}
 
-    let ground = Box::from(Sphere::from(
-        Vec3::new(0.0, -1000.0, 0.0),
-        1000.0,
-        material_ground,
-    ));
+fn render(options: RenderOptions) {
+    let start = Instant::now();
 
-    world.attach(ground);
+    let mut terraform = Terraform::load(&options.scene)
+        .unwrap_or_else(|error| {
+            eprintln!("{}", error);
+            process::exit(1);
+        })
+        .with_size(options.width, options.height)
+        .with_acceleration(options.acceleration);
 
-    let mut rng = thread_rng();
+    if let Some(depth) = options.depth() {
+        terraform = terraform.with_depth(depth);
+    }
 
-    // for i in -11..11 {
-    //     for j in -11..11 {
-    //         let choose: f32 = rng.gen();
-    //         let center = Point3::new(
-    //             i as f32 + 0.9 * rng.gen::<f32>(),
-    //             0.2,
-    //             j as f32 + rng.gen::<f32>(),
-    //         );
-    //
-    //         if (center - Vec3::new(4.0, 0.2, 0.0)).length() > 0.9 {
-    //             let mut sphere_material = Material::new();
-    //
-    //             if choose < 0.8 {
-    //                 let albedo = Color::random_vector() * Color::random_vector();
-    //                 sphere_material = Material::Lambertian { albedo };
-    //                 world.attach(Box::from(Sphere::from(center, 0.2, sphere_material)));
-    //             } else if choose < 0.95 {
-    //                 let albedo = Color::random_vector_mm(0.5, 1.0);
-    //                 let fuzz = rng.gen_range(0.0..=0.5);
-    //                 sphere_material = Material::Metal { albedo, fuzz };
-    //                 world.attach(Box::from(Sphere::from(center, 0.2, sphere_material)));
-    //             } else {
-    //                 sphere_material = Material::Dielectric {
-    //                     index_of_refraction: 1.5,
-    //                 };
-    //                 world.attach(Box::from(Sphere::from(center, 0.2, sphere_material)));
-    //             }
-    //         }
-    //     }
-    // }
+    println!(
+        "Scene {} loaded in {}ms",
+        options.scene,
+        start.elapsed().as_millis()
+    );
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(options.threads.unwrap_or(0))
+        .build()
+        .unwrap_or_else(|error| {
+            eprintln!("unable to start render threads: {}", error);
+            process::exit(1);
+        });
+
+    let mut sampling = terraform.sampling();
+    if let Some(samples) = options.samples() {
+        sampling.samples = samples;
+    }
 
-    let dielectric_material = Material::Dielectric {
-        index_of_refraction: 1.5,
-    };
-    let lambertian_material = Material::Lambertian {
-        albedo: Color::new(0.4, 0.2, 0.1),
-    };
-    let metal_material = Material::Metal {
-        albedo: Color::new(0.7, 0.6, 0.5),
-        fuzz: 0.0,
+    let render_mode = options
+        .adaptive()
+        .map_or(options.render_mode, Rendering::Adaptive);
+
+    let start = Instant::now();
+    let image = match options.progressive() {
+        Some(progressive) => pool.install(|| {
+            terraform.camera().progressive_render(
+                terraform.world(),
+                &sampling,
+                &progressive,
+                |pass, image| {
+                    let noise = pass
+                        .noise
+                        .map_or_else(|| "unknown".to_string(), |noise| format!("{:.5}", noise));
+                    println!(
+                        "Pass {} done after {}ms, {} samples per pixel, noise {}",
+                        pass.number,
+                        pass.elapsed.as_millis(),
+                        pass.samples_per_pixel,
+                        noise
+                    );
+
+                    if options.snapshots {
+                        if let Err(error) = image.export(&options.output) {
+                            eprintln!("unable to write {}: {}", options.output, error);
+                        }
+                    }
+                },
+            )
+        }),
+        None if render_mode == Rendering::Parallel => pool.install(|| {
+            let image = terraform.camera().tiled_render(
+                terraform.world(),
+                &sampling,
+                &options.tiling,
+                &AtomicBool::new(false),
+                |progress| {
+                    let eta = progress
+                        .eta()
+                        .map_or_else(String::new, |eta| format!(", {}s left", eta.as_secs()));
+                    eprint!(
+                        "\rRendered {}/{} tiles ({:.0}%){}    ",
+                        progress.tiles_done,
+                        progress.tiles_total,
+                        progress.fraction() * 100.0,
+                        eta
+                    );
+                },
+            );
+            eprintln!();
+            image
+        }),
+        None => {
+            let setting = Render {
+                render_mode,
+                sampling,
+                tiling: options.tiling,
+            };
+            pool.install(|| terraform.render_with(setting))
+        }
     };
 
-    // world.attach(Box::from(Sphere::from(
-    //     Vec3::new(0.0, 1.0, 0.0),
-    //     1.0,
-    //     dielectric_material,
-    // )));
-
-    // world.attach(Box::from(Plane::from(
-    //     Point3::new(0.0, -100.0, 0.0),
-    //     Vec3::new(0.0, 1.0, 0.0),
-    //     material_ground,
-    // )));
-
-    world.attach(Box::from(Sphere::from(
-        Vec3::new(0.0, 0.0, 0.0),
-        1.0,
-        metal_material,
-    )));
-
-    world.attach(Box::from(Triangle::from(
-        Vec3::new(-1.0, 3.0, 0.0),
-        Vec3::new(4.0, 0.0, -1.0),
-        Vec3::new(0.0, 1.0, -1.0),
-        lambertian_material,
-    )));
+    println!(
+        "Frame {}x{} rendered in {}ms",
+        terraform.camera().horizontal_size(),
+        terraform.camera().vertical_size(),
+        start.elapsed().as_millis()
+    );
 
-    let mut camera = Camera::new(image_width);
-
-    if parsed.is_some() {
-        match parsed.unwrap() {
-            Preset::Fast {
-                samples_per_pixel,
-                depth,
-            } => {
-                camera.set_samples_per_pixel(samples_per_pixel);
-                camera.set_depth(depth);
-            }
-            Preset::Slow {
-                samples_per_pixel,
-                depth,
-            } => {
-                camera.set_samples_per_pixel(samples_per_pixel);
-                camera.set_depth(depth);
-            }
-        }
+    if let Err(error) = image.export(&options.output) {
+        eprintln!("unable to write {}: {}", options.output, error);
+        process::exit(1);
     }
 
-    let image_width = image_width;
-
-    let aspect_ratio = 16.0 / 9.0;
-    let image_height = (image_width as f32 / aspect_ratio) as i32;
-
-    let mut pixels = vec![0; (image_width * image_height * 3) as usize];
-    let bands: Vec<(usize, &mut [u8])> = pixels
-        .chunks_mut((image_width * 3) as usize)
-        .enumerate()
-        .collect();
-
-    let start = std::time::Instant::now();
-
-    bands.into_par_iter().rev().for_each(|(i, band)| {
-        camera.render_parallel(&world, band, i as i32);
-    });
-
-    println!("Frame time: {}ms", start.elapsed().as_millis());
-
-    write_image(&pixels, image_width as usize, image_height as usize);
+    println!("Image saved to {}", options.output);
 }
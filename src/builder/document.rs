@@ -0,0 +1,1033 @@
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::{Deserialize, Deserializer};
+use std::cell::RefCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::str::FromStr;
+use yaml_rust::parser::{MarkedEventReceiver, Parser};
+use yaml_rust::scanner::{Marker, ScanError, TScalarStyle};
+use yaml_rust::Event;
+
+/// Position of a node inside of the scene file, both line and column start from 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mark {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Default for Mark {
+    fn default() -> Self {
+        Self { line: 1, column: 1 }
+    }
+}
+
+impl From<Marker> for Mark {
+    fn from(marker: Marker) -> Self {
+        Self {
+            line: marker.line(),
+            column: marker.col() + 1,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Scalar(String),
+    Sequence(Vec<Node>),
+    Mapping(Vec<(Node, Node)>),
+}
+
+/// YAML node that remembers where it was written, so that every deserialization error
+/// can point back to the scene file.
+#[derive(Debug, PartialEq)]
+pub struct Node {
+    pub value: Value,
+    pub mark: Mark,
+}
+
+impl Node {
+    pub fn new(value: Value, mark: Mark) -> Self {
+        Self { value, mark }
+    }
+
+    pub fn load(raw: &str) -> Result<Node, Error> {
+        let mut loader = Loader::default();
+        let mut parser = Parser::new(raw.chars());
+
+        parser.load(&mut loader, false)?;
+
+        if let Some(error) = loader.error {
+            return Err(error);
+        }
+
+        Ok(loader
+            .root
+            .unwrap_or_else(|| Node::new(Value::Null, Mark::default())))
+    }
+
+    fn unexpected(&self) -> String {
+        match &self.value {
+            Value::Null => "nothing".to_string(),
+            Value::Scalar(scalar) => format!("'{}'", scalar),
+            Value::Sequence(_) => "a list".to_string(),
+            Value::Mapping(_) => "a mapping".to_string(),
+        }
+    }
+
+    fn invalid(&self, expected: &str) -> Error {
+        Error::new(format!("expected {}, got {}", expected, self.unexpected())).at(self.mark)
+    }
+
+    fn parse<T: FromStr>(&self, expected: &str) -> Result<T, Error> {
+        match &self.value {
+            Value::Scalar(scalar) => scalar.parse::<T>().map_err(|_| self.invalid(expected)),
+            _ => Err(self.invalid(expected)),
+        }
+    }
+}
+
+/// Single problem found in the scene file: where it is, which key it belongs to and what
+/// was wrong with the value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub mark: Option<Mark>,
+    pub path: String,
+    pub message: String,
+}
+
+impl From<Error> for Diagnostic {
+    fn from(error: Error) -> Self {
+        Self {
+            mark: error.mark,
+            path: error.path.unwrap_or_default(),
+            message: error.message,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(mark) = self.mark {
+            write!(f, "{}:{}: ", mark.line, mark.column)?;
+        }
+
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Struct `Marked` asks the deserializer for, the node deserializer answers with the
+/// position and the key path of the node next to its value.
+const MARKED: &str = "$warp::Marked";
+const MARKED_FIELDS: &[&str] = &["$line", "$column", "$path", "$value"];
+
+/// Value of the scene model that remembers where it was written, so that problems found
+/// after deserialization can still point back to the scene file. Values built in code have
+/// no mark and no key path, comparisons only look at the value.
+#[derive(Debug, Clone)]
+pub struct Marked<T> {
+    pub value: T,
+    pub mark: Option<Mark>,
+    /// Key path leading to the value, named so it doesn't hide the `path` of scene blocks.
+    pub key: String,
+}
+
+impl<T> Marked<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            mark: None,
+            key: String::new(),
+        }
+    }
+
+    /// Problem with the value, reported where it was written.
+    pub fn diagnostic<S: Into<String>>(&self, message: S) -> Diagnostic {
+        Diagnostic {
+            mark: self.mark,
+            path: self.key.clone(),
+            message: message.into(),
+        }
+    }
+
+    /// Problem with the `field` of the value, reported where the value was written. Used for
+    /// fields that are missing and so have no position of their own.
+    pub fn field_diagnostic<S: Into<String>>(&self, field: &str, message: S) -> Diagnostic {
+        Diagnostic {
+            mark: self.mark,
+            path: join(&self.key, field),
+            message: message.into(),
+        }
+    }
+}
+
+impl<T> Deref for Marked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: PartialEq> PartialEq for Marked<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Default> Default for Marked<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for Marked<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Marked<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MarkedVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for MarkedVisitor<T> {
+            type Value = Marked<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("marked value")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let (mut line, mut column, mut path, mut value) = (None, None, None, None);
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "$line" => line = Some(map.next_value()?),
+                        "$column" => column = Some(map.next_value()?),
+                        "$path" => path = Some(map.next_value()?),
+                        _ => value = Some(map.next_value()?),
+                    }
+                }
+
+                Ok(Marked {
+                    value: value.ok_or_else(|| de::Error::missing_field("$value"))?,
+                    mark: line.zip(column).map(|(line, column)| Mark { line, column }),
+                    key: path.unwrap_or_default(),
+                })
+            }
+        }
+
+        deserializer.deserialize_struct(MARKED, MARKED_FIELDS, MarkedVisitor(PhantomData))
+    }
+}
+
+/// Every problem found while reading a scene, collected in a single pass over the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneError {
+    pub file: Option<String>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl SceneError {
+    pub fn new(diagnostics: Vec<Diagnostic>) -> Self {
+        Self {
+            file: None,
+            diagnostics,
+        }
+    }
+
+    pub fn with_file<S: Into<String>>(mut self, file: S) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, diagnostic) in self.diagnostics.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+
+            match &self.file {
+                Some(file) if diagnostic.mark.is_some() => write!(f, "{}:{}", file, diagnostic)?,
+                Some(file) => write!(f, "{}: {}", file, diagnostic)?,
+                None => write!(f, "{}", diagnostic)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+/// Deserialize any scene model type straight from raw YAML.
+///
+/// Invalid values are reported and replaced with defaults, so that one pass over the file
+/// finds as many problems as possible instead of stopping at the first one.
+pub fn from_str<T: DeserializeOwned>(raw: &str) -> Result<T, SceneError> {
+    let root = Node::load(raw).map_err(|error| SceneError::new(vec![error.into()]))?;
+    let context = Context::default();
+    let deserializer = NodeDeserializer::new(&root, String::new(), &context, false);
+
+    let result = T::deserialize(deserializer.clone()).map_err(|error| deserializer.record(error));
+    let diagnostics = context.diagnostics.into_inner();
+
+    match result {
+        Ok(value) if diagnostics.is_empty() => Ok(value),
+        _ => Err(SceneError::new(diagnostics)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    pub message: String,
+    pub mark: Option<Mark>,
+    pub path: Option<String>,
+    recorded: bool,
+}
+
+impl Error {
+    pub fn new(message: String) -> Self {
+        Self {
+            message,
+            mark: None,
+            path: None,
+            recorded: false,
+        }
+    }
+
+    /// Attach position to the error, innermost position always wins.
+    pub fn at(mut self, mark: Mark) -> Self {
+        if self.mark.is_none() {
+            self.mark = Some(mark);
+        }
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Diagnostic::from(self.clone()))
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        Self::new(message.to_string())
+    }
+}
+
+impl From<ScanError> for Error {
+    #[allow(deprecated)]
+    fn from(error: ScanError) -> Self {
+        use std::error::Error as _;
+
+        Self::new(error.description().to_string()).at((*error.marker()).into())
+    }
+}
+
+enum Frame {
+    Sequence(Mark, Vec<Node>),
+    Mapping(Mark, Vec<(Node, Node)>, Option<Node>),
+}
+
+#[derive(Default)]
+struct Loader {
+    stack: Vec<Frame>,
+    root: Option<Node>,
+    error: Option<Error>,
+}
+
+impl Loader {
+    fn push(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            None => self.root = Some(node),
+            Some(Frame::Sequence(_, items)) => items.push(node),
+            Some(Frame::Mapping(_, entries, key)) => match key.take() {
+                Some(key) => entries.push((key, node)),
+                None => *key = Some(node),
+            },
+        }
+    }
+
+    fn scalar(value: String, style: TScalarStyle) -> Value {
+        let is_null = matches!(value.as_str(), "" | "~" | "null" | "Null" | "NULL");
+
+        if style == TScalarStyle::Plain && is_null {
+            Value::Null
+        } else {
+            Value::Scalar(value)
+        }
+    }
+}
+
+impl MarkedEventReceiver for Loader {
+    fn on_event(&mut self, event: Event, marker: Marker) {
+        let mark = Mark::from(marker);
+
+        match event {
+            Event::Scalar(value, style, _, _) => {
+                self.push(Node::new(Self::scalar(value, style), mark))
+            }
+            Event::SequenceStart(_) => self.stack.push(Frame::Sequence(mark, vec![])),
+            Event::MappingStart(_) => self.stack.push(Frame::Mapping(mark, vec![], None)),
+            Event::SequenceEnd | Event::MappingEnd => {
+                let node = match self.stack.pop() {
+                    Some(Frame::Sequence(mark, items)) => Node::new(Value::Sequence(items), mark),
+                    // block mappings are marked after their first key, point at the key instead
+                    Some(Frame::Mapping(mark, entries, _)) => {
+                        let mark = entries.first().map_or(mark, |(key, _)| key.mark);
+                        Node::new(Value::Mapping(entries), mark)
+                    }
+                    None => return,
+                };
+                self.push(node);
+            }
+            Event::Alias(_) => {
+                if self.error.is_none() {
+                    self.error = Some(Error::new("aliases are not supported".to_string()).at(mark));
+                }
+                self.push(Node::new(Value::Null, mark));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Diagnostics shared by every deserializer of a single document.
+#[derive(Default)]
+struct Context {
+    diagnostics: RefCell<Vec<Diagnostic>>,
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn one_of(names: &[&str]) -> String {
+    let names = names
+        .iter()
+        .map(|name| format!("`{}`", name))
+        .collect::<Vec<_>>();
+
+    format!("one of {}", names.join(", "))
+}
+
+/// Deserializer of a single node, it knows the key path leading to the node and reports
+/// problems into the shared context instead of giving up on the whole document.
+///
+/// Quiet deserializers work on values that were already reported as invalid, so everything
+/// that goes wrong inside of them is a consequence of the reported problem.
+#[derive(Clone)]
+struct NodeDeserializer<'a> {
+    node: &'a Node,
+    path: String,
+    context: &'a Context,
+    quiet: bool,
+}
+
+impl<'a> NodeDeserializer<'a> {
+    fn new(node: &'a Node, path: String, context: &'a Context, quiet: bool) -> Self {
+        Self {
+            node,
+            path,
+            context,
+            quiet,
+        }
+    }
+
+    fn child(&self, node: &'a Node, path: String) -> Self {
+        Self::new(node, path, self.context, self.quiet)
+    }
+
+    /// Report the error once, with the position and the key path of the innermost node.
+    fn record(&self, mut error: Error) -> Error {
+        if !error.recorded {
+            error.recorded = true;
+            error.mark.get_or_insert(self.node.mark);
+            error.path.get_or_insert_with(|| self.path.clone());
+
+            if !self.quiet {
+                self.context
+                    .diagnostics
+                    .borrow_mut()
+                    .push(error.clone().into());
+            }
+        }
+        error
+    }
+
+    fn invalid(&self, expected: &str) -> Error {
+        self.record(self.node.invalid(expected))
+    }
+
+    fn parse<T: FromStr + Default>(&self, expected: &str) -> T {
+        self.node.parse::<T>(expected).unwrap_or_else(|error| {
+            self.record(error);
+            T::default()
+        })
+    }
+
+    fn sequence(&self, items: &'a [Node]) -> SequenceNodes<'a> {
+        SequenceNodes {
+            parent: self.clone(),
+            items: items.iter().enumerate(),
+        }
+    }
+
+    fn mapping(
+        &self,
+        entries: &'a [(Node, Node)],
+        fields: Option<&'static [&'static str]>,
+    ) -> MappingNodes<'a> {
+        MappingNodes {
+            parent: self.clone(),
+            entries: entries.iter(),
+            fields,
+            value: None,
+        }
+    }
+
+    /// Continue with an empty mapping in place of the invalid value.
+    fn recover_mapping<'de, V: Visitor<'de>>(&self, visitor: V) -> Result<V::Value, Error> {
+        let mut quiet = self.clone();
+        quiet.quiet = true;
+
+        visitor
+            .visit_map(quiet.mapping(&[], None))
+            .map_err(|error| quiet.record(error))
+    }
+
+    /// Continue with the first known variant in place of the invalid one.
+    fn recover_enum<'de, V: Visitor<'de>>(
+        &self,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let name = match variants.first() {
+            Some(name) => name,
+            None => return Err(self.node.invalid("nothing")),
+        };
+        let variant = Node::new(Value::Scalar(name.to_string()), self.node.mark);
+        let value = Node::new(Value::Null, self.node.mark);
+        let quiet = NodeDeserializer::new(&value, self.path.clone(), self.context, true);
+
+        visitor
+            .visit_enum(EnumNode {
+                variant: quiet.child(&variant, self.path.clone()),
+                value: Some(quiet.clone()),
+            })
+            .map_err(|error| quiet.record(error))
+    }
+}
+
+macro_rules! deserialize_number {
+    ($($method:ident => $visit:ident: $ty:ty, $expected:expr;)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                visitor.$visit(self.parse::<$ty>($expected))
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for NodeDeserializer<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match &self.node.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Scalar(scalar) => visitor.visit_str(scalar),
+            Value::Sequence(_) => self.deserialize_seq(visitor),
+            Value::Mapping(_) => self.deserialize_map(visitor),
+        }
+    }
+
+    deserialize_number! {
+        deserialize_i8 => visit_i8: i8, "integer";
+        deserialize_i16 => visit_i16: i16, "integer";
+        deserialize_i32 => visit_i32: i32, "integer";
+        deserialize_i64 => visit_i64: i64, "integer";
+        deserialize_u8 => visit_u8: u8, "positive integer";
+        deserialize_u16 => visit_u16: u16, "positive integer";
+        deserialize_u32 => visit_u32: u32, "positive integer";
+        deserialize_u64 => visit_u64: u64, "positive integer";
+        deserialize_f32 => visit_f32: f32, "number";
+        deserialize_f64 => visit_f64: f64, "number";
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_bool(self.parse::<bool>("boolean"))
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_char(self.parse::<char>("character"))
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match &self.node.value {
+            Value::Scalar(scalar) => visitor.visit_str(scalar),
+            _ => {
+                self.invalid("string");
+                visitor.visit_str("")
+            }
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match &self.node.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.node.value != Value::Null {
+            self.invalid("nothing");
+        }
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match &self.node.value {
+            Value::Null => visitor.visit_seq(self.sequence(&[])),
+            Value::Sequence(items) => visitor
+                .visit_seq(self.sequence(items))
+                .map_err(|error| self.record(error)),
+            _ => {
+                self.invalid("list");
+                visitor.visit_seq(self.sequence(&[]))
+            }
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match &self.node.value {
+            Value::Null => visitor.visit_map(self.mapping(&[], None)),
+            Value::Mapping(entries) => visitor
+                .visit_map(self.mapping(entries, None))
+                .map_err(|error| self.record(error)),
+            _ => {
+                self.invalid("mapping");
+                self.recover_mapping(visitor)
+            }
+        }
+    }
+
+    /// Structs are read from mappings, keys that are not among the struct fields are
+    /// reported and skipped. Lists are handed over as they are, so that a struct can also
+    /// be written as a sequence of its parts. `Marked` gets the node itself with its mark.
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if name == MARKED {
+            return visitor.visit_map(MarkedNode {
+                node: self,
+                field: 0,
+            });
+        }
+
+        match &self.node.value {
+            Value::Null => visitor.visit_map(self.mapping(&[], Some(fields))),
+            Value::Mapping(entries) => visitor
+                .visit_map(self.mapping(entries, Some(fields)))
+                .map_err(|error| self.record(error)),
+            Value::Sequence(items) => visitor
+                .visit_seq(self.sequence(items))
+                .map_err(|error| self.record(error)),
+            Value::Scalar(_) => {
+                self.invalid("mapping");
+                self.recover_mapping(visitor)
+            }
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let (variant, value) = match &self.node.value {
+            Value::Scalar(_) => (self.node, None),
+            Value::Mapping(entries) if entries.len() == 1 => (&entries[0].0, Some(&entries[0].1)),
+            _ => {
+                self.invalid(&one_of(variants));
+                return self.recover_enum(variants, visitor);
+            }
+        };
+
+        let variant = self.child(variant, self.path.clone());
+
+        match &variant.node.value {
+            Value::Scalar(name) if variants.contains(&name.as_str()) => {
+                let value = value.map(|value| self.child(value, join(&self.path, name)));
+
+                visitor
+                    .visit_enum(EnumNode { variant, value })
+                    .map_err(|error| self.record(error))
+            }
+            _ => {
+                variant.invalid(&one_of(variants));
+                self.recover_enum(variants, visitor)
+            }
+        }
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+}
+
+struct SequenceNodes<'a> {
+    parent: NodeDeserializer<'a>,
+    items: std::iter::Enumerate<std::slice::Iter<'a, Node>>,
+}
+
+impl<'de, 'a> SeqAccess<'de> for SequenceNodes<'a> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        match self.items.next() {
+            Some((index, item)) => {
+                let item = self
+                    .parent
+                    .child(item, format!("{}[{}]", self.parent.path, index));
+
+                seed.deserialize(item.clone())
+                    .map(Some)
+                    .map_err(|error| item.record(error))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+struct MappingNodes<'a> {
+    parent: NodeDeserializer<'a>,
+    entries: std::slice::Iter<'a, (Node, Node)>,
+    fields: Option<&'static [&'static str]>,
+    value: Option<NodeDeserializer<'a>>,
+}
+
+impl<'de, 'a> MapAccess<'de> for MappingNodes<'a> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        for (key, value) in self.entries.by_ref() {
+            let path = match &key.value {
+                Value::Scalar(name) => join(&self.parent.path, name),
+                _ => self.parent.path.clone(),
+            };
+            let key = self.parent.child(key, path.clone());
+
+            if let (Some(fields), Value::Scalar(name)) = (self.fields, &key.node.value) {
+                if !fields.contains(&name.as_str()) {
+                    key.record(Error::new(format!(
+                        "unknown key, expected {}",
+                        one_of(fields)
+                    )));
+                    continue;
+                }
+            }
+
+            self.value = Some(self.parent.child(value, path));
+
+            return seed
+                .deserialize(key.clone())
+                .map(Some)
+                .map_err(|error| key.record(error));
+        }
+
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        match self.value.take() {
+            Some(value) => seed
+                .deserialize(value.clone())
+                .map_err(|error| value.record(error)),
+            None => Err(Error::new("value is missing for the key".to_string())),
+        }
+    }
+}
+
+/// Fields of a `Marked` value, the position and key path of the node and then the node.
+struct MarkedNode<'a> {
+    node: NodeDeserializer<'a>,
+    field: usize,
+}
+
+impl<'de, 'a> MapAccess<'de> for MarkedNode<'a> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match MARKED_FIELDS.get(self.field) {
+            Some(field) => seed.deserialize(field.into_deserializer()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let mark = self.node.node.mark;
+        self.field += 1;
+
+        match self.field {
+            1 => seed.deserialize(mark.line.into_deserializer()),
+            2 => seed.deserialize(mark.column.into_deserializer()),
+            3 => seed.deserialize(self.node.path.clone().into_deserializer()),
+            _ => seed
+                .deserialize(self.node.clone())
+                .map_err(|error| self.node.record(error)),
+        }
+    }
+}
+
+struct EnumNode<'a> {
+    variant: NodeDeserializer<'a>,
+    value: Option<NodeDeserializer<'a>>,
+}
+
+impl<'de, 'a> EnumAccess<'de> for EnumNode<'a> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let variant = seed
+            .deserialize(self.variant.clone())
+            .map_err(|error| self.variant.record(error))?;
+
+        Ok((variant, self))
+    }
+}
+
+impl<'a> EnumNode<'a> {
+    /// Variants written without a value are read as if the value was empty.
+    fn value<T, F>(self, deserialize: F) -> Result<T, Error>
+    where
+        F: FnOnce(NodeDeserializer) -> Result<T, Error>,
+    {
+        match self.value {
+            Some(value) => deserialize(value.clone()).map_err(|error| value.record(error)),
+            None => {
+                let empty = Node::new(Value::Null, self.variant.node.mark);
+                let value = NodeDeserializer::new(
+                    &empty,
+                    self.variant.path.clone(),
+                    self.variant.context,
+                    self.variant.quiet,
+                );
+
+                deserialize(value.clone()).map_err(|error| value.record(error))
+            }
+        }
+    }
+}
+
+impl<'de, 'a> VariantAccess<'de> for EnumNode<'a> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        self.value(|value| de::Deserializer::deserialize_unit(value, de::IgnoredAny))
+            .map(|_| ())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        self.value(|value| seed.deserialize(value))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.value(|value| de::Deserializer::deserialize_seq(value, visitor))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.value(|value| de::Deserializer::deserialize_struct(value, "", fields, visitor))
+    }
+}
+
+#[cfg(test)]
+mod document_tests {
+    use crate::builder::document::{from_str, Diagnostic, Mark, Marked, Node, Value};
+    use std::collections::HashMap;
+
+    #[test]
+    fn document_empty() {
+        let node = Node::load("").unwrap();
+
+        assert_eq!(Value::Null, node.value);
+    }
+
+    #[test]
+    fn document_keeps_repeated_keys_in_order() {
+        let node = Node::load("a: 1\nb: 2\na: 3\n").unwrap();
+
+        let keys = match node.value {
+            Value::Mapping(entries) => entries
+                .into_iter()
+                .map(|(key, value)| (key.value, value.mark))
+                .collect::<Vec<_>>(),
+            _ => vec![],
+        };
+
+        let expected_keys = vec![
+            (Value::Scalar("a".to_string()), Mark { line: 1, column: 4 }),
+            (Value::Scalar("b".to_string()), Mark { line: 2, column: 4 }),
+            (Value::Scalar("a".to_string()), Mark { line: 3, column: 4 }),
+        ];
+
+        assert_eq!(expected_keys, keys);
+    }
+
+    #[test]
+    fn document_deserialize_map() {
+        let raw = r#"
+        first: 1.5
+        second: -2
+        "#;
+
+        let map: HashMap<String, f64> = from_str(raw).unwrap();
+
+        assert_eq!(Some(&1.5), map.get("first"));
+        assert_eq!(Some(&-2.0), map.get("second"));
+    }
+
+    #[test]
+    fn document_invalid_number_position() {
+        let raw = "first: 1.5\nsecond: abc\n";
+
+        let error = from_str::<HashMap<String, f64>>(raw).unwrap_err();
+        let expected_diagnostics = vec![Diagnostic {
+            mark: Some(Mark { line: 2, column: 9 }),
+            path: "second".to_string(),
+            message: "expected number, got 'abc'".to_string(),
+        }];
+
+        assert_eq!(expected_diagnostics, error.diagnostics);
+    }
+
+    #[test]
+    fn document_reports_every_invalid_value() {
+        let raw = "first: abc\nsecond: [1, x]\nthird: [3]\n";
+
+        let error = from_str::<HashMap<String, Vec<f64>>>(raw).unwrap_err();
+        let expected_error = "1:8: first: expected list, got 'abc'\n\
+                              2:13: second[1]: expected number, got 'x'";
+
+        assert_eq!(expected_error, error.to_string());
+    }
+
+    #[test]
+    fn document_marked_values() {
+        let raw = "first: [1.5]\nsecond: [1, 2]\n";
+
+        let map: HashMap<String, Marked<Vec<f64>>> = from_str(raw).unwrap();
+        let second = &map["second"];
+
+        assert_eq!(vec![1.0, 2.0], second.value);
+        assert_eq!(Some(Mark { line: 2, column: 9 }), second.mark);
+        assert_eq!("second", second.key);
+        assert_eq!(Marked::new(vec![1.5]), map["first"]);
+    }
+
+    #[test]
+    fn document_marked_value_errors() {
+        let error = from_str::<HashMap<String, Marked<f64>>>("first: abc\n").unwrap_err();
+        let expected_diagnostics = vec![Diagnostic {
+            mark: Some(Mark { line: 1, column: 8 }),
+            path: "first".to_string(),
+            message: "expected number, got 'abc'".to_string(),
+        }];
+
+        assert_eq!(expected_diagnostics, error.diagnostics);
+    }
+
+    #[test]
+    fn document_syntax_error() {
+        let error = from_str::<HashMap<String, f64>>("first: [1, 2\n").unwrap_err();
+
+        assert_eq!(1, error.diagnostics.len());
+        assert!(error.diagnostics[0].mark.is_some());
+    }
+}
@@ -0,0 +1,385 @@
+use crate::point::Point;
+use crate::primitives::{Face, MeshData};
+use crate::vector::Vector3;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Problem in a Wavefront OBJ file, lines start from 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ObjError {
+    fn new(line: usize, message: String) -> Self {
+        Self { line, message }
+    }
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ObjError {}
+
+pub fn load_obj<P: AsRef<Path>>(path: P) -> Result<MeshData, ObjError> {
+    let raw = fs::read_to_string(path).map_err(|error| ObjError::new(0, error.to_string()))?;
+
+    parse_obj(&raw)
+}
+
+/// Read vertices, normals, texture coordinates and faces of an OBJ file. Polygons are
+/// split into triangles around their first vertex, statements the mesh has no use for,
+/// like `s` or `mtllib`, are skipped.
+pub fn parse_obj(raw: &str) -> Result<MeshData, ObjError> {
+    let mut vertices = vec![];
+    let mut normals = vec![];
+    let mut texture_coordinates = vec![];
+    let mut faces = vec![];
+    let mut groups: Vec<String> = vec![];
+    let mut materials: Vec<String> = vec![];
+    let mut group = None;
+    let mut material = None;
+
+    for (number, line) in raw.lines().enumerate() {
+        let number = number + 1;
+        let line = line.split('#').next().unwrap_or_default();
+        let mut tokens = line.split_whitespace();
+
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let arguments = tokens.collect::<Vec<_>>();
+
+        match keyword {
+            "v" => {
+                let [x, y, z] = numbers::<3>(number, &arguments)?;
+                vertices.push(Point::new(x, y, z));
+            }
+            "vn" => {
+                let [x, y, z] = numbers::<3>(number, &arguments)?;
+                normals.push(Vector3::new(x, y, z));
+            }
+            "vt" => {
+                // `v` is optional and 0 when missing, as in 1D textures
+                let [u] = numbers::<1>(number, &arguments)?;
+                let v = match arguments.get(1) {
+                    Some(v) => parse(number, v, "number")?,
+                    None => 0.0,
+                };
+                texture_coordinates.push((u, v));
+            }
+            "f" => {
+                if arguments.len() < 3 {
+                    return Err(ObjError::new(
+                        number,
+                        "face needs at least 3 vertices".to_string(),
+                    ));
+                }
+
+                let corners = arguments
+                    .iter()
+                    .map(|corner| {
+                        Corner::parse(
+                            number,
+                            corner,
+                            vertices.len(),
+                            texture_coordinates.len(),
+                            normals.len(),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                for index in 1..corners.len() - 1 {
+                    let triangle = [corners[0], corners[index], corners[index + 1]];
+
+                    faces.push(Face {
+                        vertices: triangle.map(|corner| corner.vertex),
+                        normals: all(triangle.map(|corner| corner.normal)),
+                        texture: all(triangle.map(|corner| corner.texture)),
+                        group,
+                        material,
+                    });
+                }
+            }
+            "g" | "o" => group = Some(slot(&mut groups, &arguments.join(" "))),
+            "usemtl" => material = Some(slot(&mut materials, &arguments.join(" "))),
+            _ => {}
+        }
+    }
+
+    Ok(MeshData::new(
+        vertices,
+        normals,
+        texture_coordinates,
+        faces,
+        groups,
+        materials,
+    ))
+}
+
+/// Index of the name, the name is added when it was not seen before.
+fn slot(names: &mut Vec<String>, name: &str) -> usize {
+    match names.iter().position(|known| known == name) {
+        Some(index) => index,
+        None => {
+            names.push(name.to_string());
+            names.len() - 1
+        }
+    }
+}
+
+fn all(indices: [Option<usize>; 3]) -> Option<[usize; 3]> {
+    match indices {
+        [Some(a), Some(b), Some(c)] => Some([a, b, c]),
+        _ => None,
+    }
+}
+
+/// Leading numbers of a statement, optional trailing ones like `w` are ignored.
+fn numbers<const N: usize>(line: usize, arguments: &[&str]) -> Result<[f64; N], ObjError> {
+    let mut values = [0.0; N];
+
+    if arguments.len() < N {
+        return Err(ObjError::new(
+            line,
+            format!("expected {} numbers, got {}", N, arguments.len()),
+        ));
+    }
+
+    for (value, argument) in values.iter_mut().zip(arguments) {
+        *value = parse(line, argument, "number")?;
+    }
+
+    Ok(values)
+}
+
+fn parse<T: FromStr>(line: usize, raw: &str, expected: &str) -> Result<T, ObjError> {
+    raw.parse::<T>()
+        .map_err(|_| ObjError::new(line, format!("expected {}, got '{}'", expected, raw)))
+}
+
+#[derive(Clone, Copy)]
+struct Corner {
+    vertex: usize,
+    texture: Option<usize>,
+    normal: Option<usize>,
+}
+
+impl Corner {
+    /// Parse `v`, `v/vt`, `v//vn` or `v/vt/vn`, indices start from 1 and negative ones
+    /// count back from the last element read so far.
+    fn parse(
+        line: usize,
+        raw: &str,
+        vertices: usize,
+        textures: usize,
+        normals: usize,
+    ) -> Result<Self, ObjError> {
+        let mut parts = raw.split('/');
+
+        let index = |part: Option<&str>, count: usize| -> Result<Option<usize>, ObjError> {
+            let part = match part {
+                Some(part) if !part.is_empty() => part,
+                _ => return Ok(None),
+            };
+
+            let index = parse::<i64>(line, part, "index")?;
+            let resolved = if index > 0 {
+                index - 1
+            } else {
+                count as i64 + index
+            };
+
+            if index == 0 || resolved < 0 || resolved >= count as i64 {
+                Err(ObjError::new(
+                    line,
+                    format!("index {} is out of range of {} elements", index, count),
+                ))
+            } else {
+                Ok(Some(resolved as usize))
+            }
+        };
+
+        let vertex = index(parts.next(), vertices)?
+            .ok_or_else(|| ObjError::new(line, format!("expected vertex index, got '{}'", raw)))?;
+        let texture = index(parts.next(), textures)?;
+        let normal = index(parts.next(), normals)?;
+
+        Ok(Self {
+            vertex,
+            texture,
+            normal,
+        })
+    }
+}
+
+#[cfg(test)]
+mod obj_tests {
+    use crate::builder::obj::{parse_obj, ObjError};
+    use crate::point::Point;
+    use crate::primitives::Face;
+    use crate::vector::Vector3;
+
+    #[test]
+    fn obj_ignores_unrecognized_lines() {
+        let raw = "There was a young lady named Bright\nwho traveled much faster than light.\n";
+
+        let mesh = parse_obj(raw).unwrap();
+
+        assert!(mesh.vertices.is_empty());
+        assert!(mesh.faces.is_empty());
+    }
+
+    #[test]
+    fn obj_vertex_records() {
+        let raw = "v -1 1 0\nv -1.0000 0.5000 0.0000\nv 1 0 0\nv 1 1 0 1.0\n";
+
+        let mesh = parse_obj(raw).unwrap();
+        let expected_vertices = vec![
+            Point::new(-1.0, 1.0, 0.0),
+            Point::new(-1.0, 0.5, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+        ];
+
+        assert_eq!(expected_vertices, mesh.vertices);
+    }
+
+    #[test]
+    fn obj_triangle_faces() {
+        let raw = "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\n\nf 1 2 3\nf 1 3 4\n";
+
+        let mesh = parse_obj(raw).unwrap();
+        let expected_faces = vec![
+            Face {
+                vertices: [0, 1, 2],
+                ..Face::default()
+            },
+            Face {
+                vertices: [0, 2, 3],
+                ..Face::default()
+            },
+        ];
+
+        assert_eq!(expected_faces, mesh.faces);
+    }
+
+    #[test]
+    fn obj_polygons_are_fan_triangulated() {
+        let raw = "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\nv 0 2 0\n\nf 1 2 3 4 5\n";
+
+        let mesh = parse_obj(raw).unwrap();
+        let expected_vertices = vec![[0, 1, 2], [0, 2, 3], [0, 3, 4]];
+
+        assert_eq!(
+            expected_vertices,
+            mesh.faces.iter().map(|f| f.vertices).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn obj_negative_indices() {
+        let raw = "v 0 1 0\nv -1 0 0\nv 1 0 0\nf -3 -2 -1\nv 0 0 1\nf -4 -1 -2\n";
+
+        let mesh = parse_obj(raw).unwrap();
+        let expected_vertices = vec![[0, 1, 2], [0, 3, 2]];
+
+        assert_eq!(
+            expected_vertices,
+            mesh.faces.iter().map(|f| f.vertices).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn obj_normals_and_texture_coordinates() {
+        let raw = "\
+v 0 1 0
+v -1 0 0
+v 1 0 0
+vn -1 0 0
+vn 1 0 0
+vn 0 1 0
+vt 0.5 1
+vt 0 0
+vt 1 0 0
+vt 0.25
+f 1//3 2//1 3//2
+f 1/1/3 2/2/1 3/3/2
+f 1/1 2/2 3/3
+";
+
+        let mesh = parse_obj(raw).unwrap();
+
+        assert_eq!(Vector3::new(0.0, 1.0, 0.0), mesh.normals[2]);
+        assert_eq!((1.0, 0.0), mesh.texture_coordinates[2]);
+        assert_eq!((0.25, 0.0), mesh.texture_coordinates[3]);
+        assert_eq!(Some([2, 0, 1]), mesh.faces[0].normals);
+        assert_eq!(None, mesh.faces[0].texture);
+        assert_eq!(Some([2, 0, 1]), mesh.faces[1].normals);
+        assert_eq!(Some([0, 1, 2]), mesh.faces[1].texture);
+        assert_eq!(None, mesh.faces[2].normals);
+    }
+
+    #[test]
+    fn obj_groups_and_materials() {
+        let raw = "\
+v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+g FirstGroup
+usemtl red
+f 1 2 3
+o SecondGroup
+usemtl blue
+f 1 3 4
+g FirstGroup
+usemtl red
+f 2 3 4
+";
+
+        let mesh = parse_obj(raw).unwrap();
+
+        assert_eq!(vec!["FirstGroup", "SecondGroup"], mesh.groups);
+        assert_eq!(vec!["red", "blue"], mesh.materials);
+        assert_eq!(
+            vec![(Some(0), Some(0)), (Some(1), Some(1)), (Some(0), Some(0))],
+            mesh.faces
+                .iter()
+                .map(|f| (f.group, f.material))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn obj_errors() {
+        let bad_number = parse_obj("v 1 x 0\n").unwrap_err();
+        let out_of_range = parse_obj("v 1 0 0\nv 0 1 0\n\nf 1 2 3\n").unwrap_err();
+        let short_face = parse_obj("v 1 0 0\nv 0 1 0\nf 1 2\n").unwrap_err();
+
+        assert_eq!(
+            ObjError {
+                line: 1,
+                message: "expected number, got 'x'".to_string()
+            },
+            bad_number
+        );
+        assert_eq!(4, out_of_range.line);
+        assert_eq!(
+            "line 4: index 3 is out of range of 2 elements",
+            out_of_range.to_string()
+        );
+        assert_eq!(3, short_face.line);
+    }
+}
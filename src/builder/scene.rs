@@ -0,0 +1,1415 @@
+use crate::background::Background;
+use crate::builder::document::{Marked, SceneError};
+use crate::builder::obj::load_obj;
+use crate::camera::{Camera, Projection};
+use crate::color::Color;
+use crate::lights::{DirectionalLight, Light, PointLight, SpotLight};
+use crate::material::Material;
+use crate::matrices::{Matrix, Matrix4};
+use crate::patterns::{
+    BlendMode, BlendedPattern, Bump, MarblePattern, NoiseKind, NoisePattern, Pattern, PatternSlot,
+    PerturbedPattern, TextureFilter, TextureImage, TexturePattern, TextureWrap, UvMapping,
+    WoodPattern,
+};
+use crate::point::Point;
+use crate::primitives::PrimitiveShape::{
+    ConeShape, CsgShape, CubeShape, CylinderShape, GroupShape, MeshShape, MovingShape, PlaneShape,
+    SphereShape,
+};
+use crate::primitives::{
+    Cone, Csg, CsgOperation, Cube, Cylinder, Group, Mesh, MeshData, Moving, Plane, PrimitiveShape,
+    Sphere,
+};
+use crate::sampling::{Filter, Sampler, Sampling};
+use crate::transformations::Transform::{Orientation, Rotate, Scale, Shear, Translate};
+use crate::transformations::{Over, Transformable};
+use crate::utils::degrees_to_radians;
+use crate::vector::Vector3;
+use crate::world::World;
+use serde::de::{Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Scene model that a scene file is deserialized into, before it is turned into a `World`
+/// and a `Camera`. Every block is optional and may appear in any order.
+#[derive(Debug, Default, PartialEq)]
+pub struct SceneDescription {
+    pub primitives: Vec<Marked<PrimitiveDescription>>,
+    pub lights: Vec<LightDescription>,
+    pub camera: Option<CameraDescription>,
+    pub background: Option<Marked<BackgroundDescription>>,
+}
+
+/// Mesh data of mesh primitives, keyed by the `path` as it is written in the scene.
+pub type MeshLibrary = HashMap<String, Arc<MeshData>>;
+
+/// Images of texture patterns, keyed by the `path` as it is written in the scene.
+pub type TextureLibrary = HashMap<String, Arc<TextureImage>>;
+
+impl SceneDescription {
+    pub fn world(&self, meshes: &MeshLibrary, textures: &TextureLibrary) -> World {
+        let world = World::default()
+            .with_objects(
+                self.primitives
+                    .iter()
+                    .map(|p| p.primitive(meshes, textures))
+                    .collect(),
+            )
+            .with_light_sources(self.lights.iter().map(|l| l.light()).collect());
+
+        match &self.background {
+            Some(background) => world
+                .with_background(background.background(textures))
+                .with_background_ambient(background.ambient),
+            None => world,
+        }
+    }
+
+    /// Every primitive of the scene including the children of csg primitives.
+    fn all_primitives(&self) -> Vec<&Marked<PrimitiveDescription>> {
+        let mut primitives = vec![];
+        for primitive in &self.primitives {
+            primitive.walk(&mut primitives);
+        }
+        primitives
+    }
+
+    /// Check what the scene model can't express on its own, csg primitives need an
+    /// `operation` and both children, perturbed patterns the `Pattern` they perturb, spot
+    /// and directional lights a `Direction` that isn't zero and the camera a positive
+    /// `focal_distance`.
+    pub fn validate(&self) -> Result<(), SceneError> {
+        let mut diagnostics = vec![];
+
+        if let Some(camera) = &self.camera {
+            if *camera.focal_distance <= 0.0 || camera.focal_distance.is_nan() {
+                diagnostics.push(
+                    camera
+                        .focal_distance
+                        .diagnostic("camera needs a positive focal distance"),
+                );
+            }
+        }
+
+        for light in &self.lights {
+            let (direction, kind) = match light {
+                LightDescription::Point(_) => continue,
+                LightDescription::Spot(spot) => (&spot.direction, "spot"),
+                LightDescription::Directional(directional) => {
+                    (&directional.direction, "directional")
+                }
+            };
+
+            if direction.vector().near_zero() {
+                diagnostics.push(
+                    direction
+                        .diagnostic(format!("{} light needs a direction that isn't zero", kind)),
+                );
+            }
+        }
+
+        for primitive in self.all_primitives() {
+            for pattern in primitive.patterns() {
+                if pattern.pattern_type == PatternKind::Perturbed && pattern.pattern.is_none() {
+                    diagnostics.push(pattern.diagnostic("perturbed pattern needs `Pattern`"));
+                }
+            }
+
+            if primitive.primitive_type != PrimitiveKind::Csg {
+                continue;
+            }
+
+            let missing = [
+                ("operation", primitive.operation.is_none()),
+                ("Left", primitive.left.is_none()),
+                ("Right", primitive.right.is_none()),
+            ];
+
+            for (field, _) in missing.iter().filter(|(_, missing)| *missing) {
+                diagnostics.push(primitive.diagnostic(format!("csg primitive needs `{}`", field)));
+            }
+        }
+
+        if diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(SceneError::new(diagnostics))
+        }
+    }
+
+    /// Load every model referenced by mesh primitives, relative paths start in `directory`.
+    /// A model used by several primitives is read once and shared between them.
+    pub fn load_meshes(&self, directory: &Path) -> Result<MeshLibrary, SceneError> {
+        let mut meshes = MeshLibrary::new();
+        let mut diagnostics = vec![];
+
+        for primitive in self.all_primitives() {
+            if primitive.primitive_type != PrimitiveKind::Mesh {
+                continue;
+            }
+
+            let path = match &primitive.path {
+                Some(path) => path,
+                None => {
+                    diagnostics.push(
+                        primitive
+                            .field_diagnostic("path", "mesh primitive needs a path to an OBJ file"),
+                    );
+                    continue;
+                }
+            };
+
+            if meshes.contains_key(path.as_str()) {
+                continue;
+            }
+
+            match load_obj(directory.join(path.as_str())) {
+                Ok(mesh) => {
+                    meshes.insert(path.to_string(), Arc::new(mesh));
+                }
+                Err(error) => diagnostics.push(path.diagnostic(format!(
+                    "unable to load '{}': {}",
+                    path.as_str(),
+                    error
+                ))),
+            }
+        }
+
+        if diagnostics.is_empty() {
+            Ok(meshes)
+        } else {
+            Err(SceneError::new(diagnostics))
+        }
+    }
+
+    /// Load every image referenced by texture patterns, relative paths start in `directory`.
+    /// An image used by several materials is read once and shared between them.
+    pub fn load_textures(&self, directory: &Path) -> Result<TextureLibrary, SceneError> {
+        let mut textures = TextureLibrary::new();
+        let mut diagnostics = vec![];
+
+        // diagnostic for a missing path and the path of every image the scene refers to
+        let mut images = vec![];
+        for primitive in self.all_primitives() {
+            for pattern in primitive.patterns() {
+                if pattern.pattern_type == PatternKind::Texture {
+                    let missing = pattern
+                        .field_diagnostic("path", "texture pattern needs a path to an image");
+                    images.push((missing, &pattern.path));
+                }
+            }
+        }
+        if let Some(background) = &self.background {
+            if background.needs_image() {
+                let missing =
+                    background.field_diagnostic("path", "background needs a path to an image");
+                images.push((missing, &background.path));
+            }
+        }
+
+        for (missing, path) in images {
+            let path = match path {
+                Some(path) => path,
+                None => {
+                    diagnostics.push(missing);
+                    continue;
+                }
+            };
+
+            if textures.contains_key(path.as_str()) {
+                continue;
+            }
+
+            match TextureImage::load(directory.join(path.as_str())) {
+                Ok(image) => {
+                    textures.insert(path.to_string(), Arc::new(image));
+                }
+                Err(error) => diagnostics.push(path.diagnostic(format!(
+                    "unable to load '{}': {}",
+                    path.as_str(),
+                    error
+                ))),
+            }
+        }
+
+        if diagnostics.is_empty() {
+            Ok(textures)
+        } else {
+            Err(SceneError::new(diagnostics))
+        }
+    }
+
+    pub fn camera(&self) -> Camera {
+        match &self.camera {
+            Some(camera) => camera.camera(),
+            None => CameraDescription::default().camera(),
+        }
+    }
+
+    pub fn sampling(&self) -> Sampling {
+        match &self.camera {
+            Some(camera) => camera.sampling(),
+            None => Sampling::default(),
+        }
+    }
+}
+
+const SCENE_KEYS: &[&str] = &[
+    "Primitive",
+    "Primitives",
+    "PointLight",
+    "PointLights",
+    "SpotLight",
+    "SpotLights",
+    "DirectionalLight",
+    "DirectionalLights",
+    "Camera",
+    "Background",
+];
+
+#[derive(Deserialize)]
+enum SceneKey {
+    Primitive,
+    Primitives,
+    PointLight,
+    PointLights,
+    SpotLight,
+    SpotLights,
+    DirectionalLight,
+    DirectionalLights,
+    Camera,
+    Background,
+}
+
+impl<'de> Deserialize<'de> for SceneDescription {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SceneVisitor;
+
+        impl<'de> Visitor<'de> for SceneVisitor {
+            type Value = SceneDescription;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("scene description")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut scene = SceneDescription::default();
+                let mut failure = None;
+
+                // `Primitive` and the light blocks are allowed to repeat on the top level
+                while let Some(key) = map.next_key::<SceneKey>()? {
+                    let block = match key {
+                        SceneKey::Primitive => map.next_value().map(|p| scene.primitives.push(p)),
+                        SceneKey::Primitives => map
+                            .next_value::<Vec<_>>()
+                            .map(|p| scene.primitives.extend(p)),
+                        SceneKey::PointLight => map
+                            .next_value()
+                            .map(|l| scene.lights.push(LightDescription::Point(l))),
+                        SceneKey::PointLights => map.next_value::<Vec<_>>().map(|l| {
+                            scene
+                                .lights
+                                .extend(l.into_iter().map(LightDescription::Point))
+                        }),
+                        SceneKey::SpotLight => map
+                            .next_value()
+                            .map(|l| scene.lights.push(LightDescription::Spot(l))),
+                        SceneKey::SpotLights => map.next_value::<Vec<_>>().map(|l| {
+                            scene
+                                .lights
+                                .extend(l.into_iter().map(LightDescription::Spot))
+                        }),
+                        SceneKey::DirectionalLight => map
+                            .next_value()
+                            .map(|l| scene.lights.push(LightDescription::Directional(l))),
+                        SceneKey::DirectionalLights => map.next_value::<Vec<_>>().map(|l| {
+                            scene
+                                .lights
+                                .extend(l.into_iter().map(LightDescription::Directional))
+                        }),
+                        SceneKey::Camera => map.next_value().map(|c| scene.camera = Some(c)),
+                        SceneKey::Background => {
+                            map.next_value().map(|b| scene.background = Some(b))
+                        }
+                    };
+
+                    // a broken block does not stop the rest of the scene from being checked
+                    if let Err(error) = block {
+                        failure.get_or_insert(error);
+                    }
+                }
+
+                match failure {
+                    Some(error) => Err(error),
+                    None => Ok(scene),
+                }
+            }
+        }
+
+        deserializer.deserialize_struct("SceneDescription", SCENE_KEYS, SceneVisitor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrimitiveKind {
+    Sphere,
+    Plane,
+    Cube,
+    Mesh,
+    Cylinder,
+    Cone,
+    Csg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CsgKind {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl From<CsgKind> for CsgOperation {
+    fn from(kind: CsgKind) -> Self {
+        match kind {
+            CsgKind::Union => CsgOperation::Union,
+            CsgKind::Intersection => CsgOperation::Intersection,
+            CsgKind::Difference => CsgOperation::Difference,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrimitiveDescription {
+    pub primitive_type: PrimitiveKind,
+    /// OBJ file of a mesh primitive.
+    #[serde(default)]
+    pub path: Option<Marked<String>>,
+    /// Lower end of a cylinder or cone, unbounded when missing.
+    #[serde(default)]
+    pub minimum: Option<f64>,
+    /// Upper end of a cylinder or cone, unbounded when missing.
+    #[serde(default)]
+    pub maximum: Option<f64>,
+    /// Caps the ends of a truncated cylinder or cone.
+    #[serde(default)]
+    pub closed: bool,
+    /// How a csg primitive combines `Left` and `Right`.
+    #[serde(default)]
+    pub operation: Option<CsgKind>,
+    #[serde(rename = "Left", default)]
+    pub left: Option<Box<Marked<PrimitiveDescription>>>,
+    #[serde(rename = "Right", default)]
+    pub right: Option<Box<Marked<PrimitiveDescription>>>,
+    #[serde(rename = "Material", default)]
+    pub material: MaterialDescription,
+    /// Materials for the `usemtl` names of a mesh, faces without one use `Material`.
+    #[serde(rename = "Materials", default)]
+    pub materials: HashMap<String, MaterialDescription>,
+    #[serde(rename = "Transform", default)]
+    pub transform: TransformDescription,
+    /// Where the primitive has moved to when the shutter closes, it stays put when missing.
+    #[serde(rename = "EndTransform", default)]
+    pub end_transform: Option<TransformDescription>,
+}
+
+impl Marked<PrimitiveDescription> {
+    fn walk<'a>(&'a self, primitives: &mut Vec<&'a Marked<PrimitiveDescription>>) {
+        if let Some(left) = &self.left {
+            left.walk(primitives);
+        }
+        if let Some(right) = &self.right {
+            right.walk(primitives);
+        }
+        primitives.push(self);
+    }
+}
+
+impl PrimitiveDescription {
+    fn minimum(&self) -> f64 {
+        self.minimum.unwrap_or(f64::NEG_INFINITY)
+    }
+
+    fn maximum(&self) -> f64 {
+        self.maximum.unwrap_or(f64::INFINITY)
+    }
+
+    /// Patterns of the materials of the primitive including the patterns they wrap and those
+    /// of their bumps.
+    fn patterns(&self) -> Vec<&Marked<PatternDescription>> {
+        let mut patterns = vec![];
+        for material in std::iter::once(&self.material).chain(self.materials.values()) {
+            if let Some(pattern) = &material.pattern {
+                pattern.walk(&mut patterns);
+            }
+            if let Some(bump) = &material.bump {
+                bump.pattern.walk(&mut patterns);
+            }
+        }
+        patterns
+    }
+
+    /// Child of a csg primitive, a missing one is rejected by `validate` and left empty here.
+    fn child(
+        child: &Option<Box<Marked<PrimitiveDescription>>>,
+        meshes: &MeshLibrary,
+        textures: &TextureLibrary,
+    ) -> PrimitiveShape {
+        match child {
+            Some(child) => child.primitive(meshes, textures),
+            None => GroupShape(Group::new()),
+        }
+    }
+
+    pub fn primitive(&self, meshes: &MeshLibrary, textures: &TextureLibrary) -> PrimitiveShape {
+        match &self.end_transform {
+            Some(end_transform) => MovingShape(Moving::new(
+                self.shape(&Matrix4::identity(), meshes, textures),
+                self.transform.transformation(),
+                end_transform.transformation(),
+            )),
+            None => self.shape(&self.transform.transformation(), meshes, textures),
+        }
+    }
+
+    fn shape(
+        &self,
+        transformation: &Matrix4,
+        meshes: &MeshLibrary,
+        textures: &TextureLibrary,
+    ) -> PrimitiveShape {
+        let material = self.material.material(textures);
+
+        match self.primitive_type {
+            PrimitiveKind::Sphere => SphereShape(
+                Sphere::default()
+                    .transform(transformation)
+                    .apply_material(material),
+            ),
+            PrimitiveKind::Plane => PlaneShape(
+                Plane::default()
+                    .transform(transformation)
+                    .apply_material(material),
+            ),
+            PrimitiveKind::Cube => CubeShape(
+                Cube::default()
+                    .transform(transformation)
+                    .apply_material(material),
+            ),
+            PrimitiveKind::Cylinder => CylinderShape(
+                Cylinder::default()
+                    .apply_limits(self.minimum(), self.maximum())
+                    .apply_closed(self.closed)
+                    .transform(transformation)
+                    .apply_material(material),
+            ),
+            PrimitiveKind::Cone => ConeShape(
+                Cone::default()
+                    .apply_limits(self.minimum(), self.maximum())
+                    .apply_closed(self.closed)
+                    .transform(transformation)
+                    .apply_material(material),
+            ),
+            PrimitiveKind::Csg => CsgShape(
+                Csg::new(
+                    self.operation.unwrap_or(CsgKind::Union).into(),
+                    PrimitiveDescription::child(&self.left, meshes, textures),
+                    PrimitiveDescription::child(&self.right, meshes, textures),
+                )
+                .transform(transformation),
+            ),
+            PrimitiveKind::Mesh => {
+                let data = self
+                    .path
+                    .as_ref()
+                    .and_then(|path| meshes.get(path.as_str()).cloned())
+                    .unwrap_or_default();
+                let materials = self
+                    .materials
+                    .iter()
+                    .map(|(name, material)| (name.clone(), material.material(textures)))
+                    .collect();
+
+                MeshShape(
+                    Mesh::new(data)
+                        .transform(transformation)
+                        .apply_material(material)
+                        .apply_materials(&materials),
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MaterialDescription {
+    #[serde(rename = "Color")]
+    pub color: ColorDescription,
+    pub ambient: f64,
+    pub diffuse: f64,
+    pub specular: f64,
+    pub reflective: f64,
+    pub refractive_index: f64,
+    pub transparency: f64,
+    pub shininess: f64,
+    pub casts_shadow: bool,
+    #[serde(rename = "Pattern")]
+    pub pattern: Option<Marked<PatternDescription>>,
+    #[serde(rename = "Bump")]
+    pub bump: Option<BumpDescription>,
+}
+
+impl MaterialDescription {
+    pub fn material(&self, textures: &TextureLibrary) -> Material {
+        let material = Material::default()
+            .color(self.color.color())
+            .ambient(self.ambient)
+            .diffuse(self.diffuse)
+            .specular(self.specular)
+            .reflective(self.reflective)
+            .refractive_index(self.refractive_index)
+            .transparency(self.transparency)
+            .shininess(self.shininess)
+            .casts_shadow(self.casts_shadow);
+
+        let material = match &self.pattern {
+            Some(pattern) => material.apply_pattern(pattern.pattern(textures)),
+            None => material,
+        };
+
+        match &self.bump {
+            Some(bump) => material.apply_bump(bump.bump(textures)),
+            None => material,
+        }
+    }
+}
+
+impl Default for MaterialDescription {
+    fn default() -> Self {
+        let material = Material::default();
+
+        Self {
+            color: material.color.into(),
+            ambient: material.ambient,
+            diffuse: material.diffuse,
+            specular: material.specular,
+            reflective: material.reflective,
+            refractive_index: material.refractive_index,
+            transparency: material.transparency,
+            shininess: material.shininess,
+            casts_shadow: material.casts_shadow,
+            pattern: None,
+            bump: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BumpKind {
+    Height,
+    Normalmap,
+}
+
+/// Detail added to the shading normal, `Pattern` is read as heights or as a normal map.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BumpDescription {
+    pub bump_type: BumpKind,
+    pub strength: f64,
+    #[serde(rename = "Pattern")]
+    pub pattern: Marked<PatternDescription>,
+}
+
+impl BumpDescription {
+    pub fn bump(&self, textures: &TextureLibrary) -> Bump {
+        let pattern = self.pattern.pattern(textures);
+        let bump = match self.bump_type {
+            BumpKind::Height => Bump::height(pattern),
+            BumpKind::Normalmap => Bump::normal_map(pattern),
+        };
+
+        bump.with_strength(self.strength)
+    }
+}
+
+impl Default for BumpDescription {
+    fn default() -> Self {
+        Self {
+            bump_type: BumpKind::Height,
+            strength: 1.0,
+            pattern: Marked::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternKind {
+    Plain,
+    Stripe,
+    Gradient,
+    Ring,
+    Checker,
+    Radial,
+    Blend,
+    Texture,
+    Noise,
+    Fbm,
+    Turbulence,
+    Marble,
+    Wood,
+    Perturbed,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PatternDescription {
+    pub pattern_type: PatternKind,
+    #[serde(rename = "Color_a")]
+    pub color_a: ColorDescription,
+    #[serde(rename = "Color_b")]
+    pub color_b: ColorDescription,
+    /// Image of a texture pattern.
+    pub path: Option<Marked<String>>,
+    /// How a texture pattern is laid onto the primitive.
+    pub mapping: MappingKind,
+    pub filter: TextureFilterKind,
+    pub wrap: WrapKind,
+    /// Seed of the noise of noise, marble, wood and perturbed patterns.
+    pub seed: u64,
+    /// Octaves of fractal noise, the pattern's own default when missing.
+    pub octaves: Option<usize>,
+    /// How much the noise moves marble veins, wood rings or the points of a perturbed
+    /// pattern, the pattern's own default when missing.
+    pub strength: Option<f64>,
+    /// Pattern wrapped by a perturbed pattern.
+    #[serde(rename = "Pattern")]
+    pub pattern: Option<Box<Marked<PatternDescription>>>,
+    /// Pattern in place of `Color_a`, with a transformation of its own on top of this one.
+    #[serde(rename = "Pattern_a")]
+    pub pattern_a: Option<Box<Marked<PatternDescription>>>,
+    /// Pattern in place of `Color_b`.
+    #[serde(rename = "Pattern_b")]
+    pub pattern_b: Option<Box<Marked<PatternDescription>>>,
+    /// How a blend pattern puts `Pattern_a` and `Pattern_b` together.
+    pub blend: BlendKind,
+    #[serde(rename = "Transform")]
+    pub transform: TransformDescription,
+}
+
+impl PatternDescription {
+    /// `pattern` when there is one, `color` otherwise.
+    fn slot(
+        pattern: &Option<Box<Marked<PatternDescription>>>,
+        color: &ColorDescription,
+        textures: &TextureLibrary,
+    ) -> PatternSlot {
+        match pattern {
+            Some(pattern) => pattern.pattern(textures).into(),
+            None => color.color().into(),
+        }
+    }
+
+    /// A texture whose image isn't in `textures` is rejected by `load_textures` and left
+    /// plain here.
+    pub fn pattern(&self, textures: &TextureLibrary) -> Pattern {
+        let (slot_a, slot_b) = (
+            PatternDescription::slot(&self.pattern_a, &self.color_a, textures),
+            PatternDescription::slot(&self.pattern_b, &self.color_b, textures),
+        );
+
+        let pattern = match self.pattern_type {
+            PatternKind::Plain => Pattern::new_plain(self.color_a.color()),
+            PatternKind::Stripe => Pattern::new_stripe(slot_a, slot_b),
+            PatternKind::Gradient => Pattern::new_gradient(slot_a, slot_b),
+            PatternKind::Radial => Pattern::new_radial_gradient(slot_a, slot_b),
+            PatternKind::Ring => Pattern::new_ring(slot_a, slot_b),
+            PatternKind::Checker => Pattern::new_checker(slot_a, slot_b),
+            PatternKind::Blend => {
+                let pattern = |slot| match slot {
+                    PatternSlot::Pattern(pattern) => *pattern,
+                    PatternSlot::Color(color) => Pattern::new_plain(color),
+                };
+                Pattern::new_blended(BlendedPattern::new(
+                    pattern(slot_a),
+                    pattern(slot_b),
+                    self.blend.into(),
+                ))
+            }
+            PatternKind::Texture => self
+                .path
+                .as_ref()
+                .and_then(|path| textures.get(path.as_str()))
+                .map(|image| {
+                    Pattern::new_texture(
+                        TexturePattern::new(image.clone(), self.mapping.into())
+                            .with_filter(self.filter.into())
+                            .with_wrap(self.wrap.into()),
+                    )
+                })
+                .unwrap_or_default(),
+            PatternKind::Noise => self.noise(slot_a, slot_b, NoiseKind::Perlin),
+            PatternKind::Fbm => self.noise(slot_a, slot_b, NoiseKind::Fbm),
+            PatternKind::Turbulence => self.noise(slot_a, slot_b, NoiseKind::Turbulence),
+            PatternKind::Marble => {
+                let mut marble = MarblePattern::from(slot_a, slot_b, self.seed);
+                if let Some(octaves) = self.octaves {
+                    marble = marble.with_octaves(octaves);
+                }
+                if let Some(strength) = self.strength {
+                    marble = marble.with_strength(strength);
+                }
+                Pattern::new_marble(marble)
+            }
+            PatternKind::Wood => {
+                let mut wood = WoodPattern::from(slot_a, slot_b, self.seed);
+                if let Some(strength) = self.strength {
+                    wood = wood.with_strength(strength);
+                }
+                Pattern::new_wood(wood)
+            }
+            // a missing `Pattern` is rejected by `validate` and perturbs the default here
+            PatternKind::Perturbed => {
+                let inner = match &self.pattern {
+                    Some(pattern) => pattern.pattern(textures),
+                    None => Pattern::default(),
+                };
+                let mut perturbed = PerturbedPattern::new(inner, self.seed);
+                if let Some(strength) = self.strength {
+                    perturbed = perturbed.with_scale(strength);
+                }
+                Pattern::new_perturbed(perturbed)
+            }
+        };
+
+        pattern.transform(&self.transform.transformation())
+    }
+
+    fn noise(&self, slot_a: PatternSlot, slot_b: PatternSlot, kind: NoiseKind) -> Pattern {
+        let noise = NoisePattern::from(slot_a, slot_b, kind, self.seed);
+
+        match self.octaves {
+            Some(octaves) => Pattern::new_noise(noise.with_octaves(octaves)),
+            None => Pattern::new_noise(noise),
+        }
+    }
+}
+
+impl Marked<PatternDescription> {
+    fn walk<'a>(&'a self, patterns: &mut Vec<&'a Marked<PatternDescription>>) {
+        for pattern in [&self.pattern, &self.pattern_a, &self.pattern_b]
+            .iter()
+            .copied()
+            .flatten()
+        {
+            pattern.walk(patterns);
+        }
+        patterns.push(self);
+    }
+}
+
+impl Default for PatternDescription {
+    // mirrors `Pattern::default()`, plain white
+    fn default() -> Self {
+        Self {
+            pattern_type: PatternKind::Plain,
+            color_a: Color::white().into(),
+            color_b: Color::black().into(),
+            path: None,
+            mapping: MappingKind::Planar,
+            filter: TextureFilterKind::Bilinear,
+            wrap: WrapKind::Repeat,
+            seed: 0,
+            octaves: None,
+            strength: None,
+            pattern: None,
+            pattern_a: None,
+            pattern_b: None,
+            blend: BlendKind::Average,
+            transform: TransformDescription::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlendKind {
+    Average,
+    Multiply,
+}
+
+impl From<BlendKind> for BlendMode {
+    fn from(kind: BlendKind) -> Self {
+        match kind {
+            BlendKind::Average => BlendMode::Average,
+            BlendKind::Multiply => BlendMode::Multiply,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MappingKind {
+    Spherical,
+    Planar,
+    Cylindrical,
+    Cube,
+}
+
+impl From<MappingKind> for UvMapping {
+    fn from(kind: MappingKind) -> Self {
+        match kind {
+            MappingKind::Spherical => UvMapping::Spherical,
+            MappingKind::Planar => UvMapping::Planar,
+            MappingKind::Cylindrical => UvMapping::Cylindrical,
+            MappingKind::Cube => UvMapping::Cube,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TextureFilterKind {
+    Nearest,
+    Bilinear,
+}
+
+impl From<TextureFilterKind> for TextureFilter {
+    fn from(kind: TextureFilterKind) -> Self {
+        match kind {
+            TextureFilterKind::Nearest => TextureFilter::Nearest,
+            TextureFilterKind::Bilinear => TextureFilter::Bilinear,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WrapKind {
+    Repeat,
+    Clamp,
+}
+
+impl From<WrapKind> for TextureWrap {
+    fn from(kind: WrapKind) -> Self {
+        match kind {
+            WrapKind::Repeat => TextureWrap::Repeat,
+            WrapKind::Clamp => TextureWrap::Clamp,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackgroundKind {
+    Solid,
+    Gradient,
+    Equirectangular,
+    Cubemap,
+}
+
+/// What rays that miss every primitive see. A solid background is `Color_a`, a gradient goes
+/// from `Color_a` straight down to `Color_b` straight up, the environment maps read `path`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackgroundDescription {
+    pub background_type: BackgroundKind,
+    #[serde(rename = "Color_a")]
+    pub color_a: ColorDescription,
+    #[serde(rename = "Color_b")]
+    pub color_b: ColorDescription,
+    pub path: Option<Marked<String>>,
+    /// Part of the average background color lighting every surface.
+    pub ambient: f64,
+}
+
+impl BackgroundDescription {
+    fn needs_image(&self) -> bool {
+        matches!(
+            self.background_type,
+            BackgroundKind::Equirectangular | BackgroundKind::Cubemap
+        )
+    }
+
+    /// An environment map whose image isn't in `textures` is rejected by `load_textures` and
+    /// left black here.
+    pub fn background(&self, textures: &TextureLibrary) -> Background {
+        let image = self
+            .path
+            .as_ref()
+            .and_then(|path| textures.get(path.as_str()))
+            .cloned();
+
+        match (self.background_type, image) {
+            (BackgroundKind::Solid, _) => Background::Solid(self.color_a.color()),
+            (BackgroundKind::Gradient, _) => Background::Gradient {
+                bottom: self.color_a.color(),
+                top: self.color_b.color(),
+            },
+            (BackgroundKind::Equirectangular, Some(image)) => Background::equirectangular(image),
+            (BackgroundKind::Cubemap, Some(image)) => Background::cube_map(image),
+            (_, None) => Background::default(),
+        }
+    }
+}
+
+impl Default for BackgroundDescription {
+    // mirrors `Background::default()`, solid black
+    fn default() -> Self {
+        Self {
+            background_type: BackgroundKind::Solid,
+            color_a: Color::black().into(),
+            color_b: Color::black().into(),
+            path: None,
+            ambient: 0.0,
+        }
+    }
+}
+
+/// Any of the light blocks, in the order they appear in the scene.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LightDescription {
+    Point(PointLightDescription),
+    Spot(SpotLightDescription),
+    Directional(DirectionalLightDescription),
+}
+
+impl LightDescription {
+    pub fn light(&self) -> Light {
+        match self {
+            LightDescription::Point(light) => light.light().into(),
+            LightDescription::Spot(light) => light.light().into(),
+            LightDescription::Directional(light) => light.light().into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PointLightDescription {
+    #[serde(rename = "OriginPoint")]
+    pub position: CoordinatesDescription,
+    #[serde(rename = "Color")]
+    pub intensity: ColorDescription,
+    /// Attenuation terms, the light doesn't fade with the defaults.
+    pub constant: f64,
+    pub linear: f64,
+    pub quadratic: f64,
+}
+
+impl PointLightDescription {
+    pub fn light(&self) -> PointLight {
+        let mut light = PointLight::new(self.intensity.color(), self.position.point());
+        light.with_attenuation(self.constant, self.linear, self.quadratic);
+        light
+    }
+}
+
+impl Default for PointLightDescription {
+    fn default() -> Self {
+        Self {
+            position: CoordinatesDescription::default(),
+            intensity: Color::white().into(),
+            constant: 1.0,
+            linear: 0.0,
+            quadratic: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpotLightDescription {
+    #[serde(rename = "OriginPoint")]
+    pub position: CoordinatesDescription,
+    #[serde(rename = "Direction")]
+    pub direction: Marked<CoordinatesDescription>,
+    #[serde(rename = "Color")]
+    pub intensity: ColorDescription,
+    /// Half angle of the cone in degrees.
+    pub angle: f64,
+    /// Width of the fading edge of the cone in degrees.
+    pub falloff: f64,
+}
+
+impl SpotLightDescription {
+    pub fn light(&self) -> SpotLight {
+        SpotLight::new(
+            self.intensity.color(),
+            self.position.point(),
+            self.direction.vector(),
+            degrees_to_radians(self.angle),
+        )
+        .with_falloff(degrees_to_radians(self.falloff))
+    }
+}
+
+impl Default for SpotLightDescription {
+    fn default() -> Self {
+        Self {
+            position: CoordinatesDescription::default(),
+            direction: CoordinatesDescription::new(0.0, -1.0, 0.0).into(),
+            intensity: Color::white().into(),
+            angle: 30.0,
+            falloff: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DirectionalLightDescription {
+    #[serde(rename = "Direction")]
+    pub direction: Marked<CoordinatesDescription>,
+    #[serde(rename = "Color")]
+    pub intensity: ColorDescription,
+}
+
+impl DirectionalLightDescription {
+    pub fn light(&self) -> DirectionalLight {
+        DirectionalLight::new(self.intensity.color(), self.direction.vector())
+    }
+}
+
+impl Default for DirectionalLightDescription {
+    fn default() -> Self {
+        Self {
+            direction: CoordinatesDescription::new(0.0, -1.0, 0.0).into(),
+            intensity: Color::white().into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CameraDescription {
+    pub factor: usize,
+    pub horizontal_base: usize,
+    pub vertical_base: usize,
+    /// Field of view in degrees.
+    pub fov: f64,
+    #[serde(rename = "VectorFrom")]
+    pub from: CoordinatesDescription,
+    #[serde(rename = "VectorTo")]
+    pub to: CoordinatesDescription,
+    #[serde(rename = "VectorUp")]
+    pub up: CoordinatesDescription,
+    pub projection: ProjectionKind,
+    /// Width of the view in world units, only used by the orthographic projection.
+    pub view_width: f64,
+    /// Radius of the lens, 0.0 keeps everything sharp.
+    pub aperture: f64,
+    /// Distance to the plane in focus.
+    pub focal_distance: Marked<f64>,
+    /// Shutter interval, primitives with an `EndTransform` move from 0.0 to 1.0.
+    pub shutter_open: f64,
+    pub shutter_close: f64,
+    /// Samples per pixel.
+    pub samples: usize,
+    pub sampler: SamplerKind,
+    pub filter: FilterKind,
+}
+
+impl CameraDescription {
+    pub fn camera(&self) -> Camera {
+        let orientation = Orientation(self.from.vector(), self.to.vector(), self.up.vector());
+
+        Camera::new(
+            self.factor * self.horizontal_base,
+            self.factor * self.vertical_base,
+            degrees_to_radians(self.fov),
+        )
+        .with_projection(self.projection())
+        .with_lens(self.aperture, *self.focal_distance)
+        .with_shutter(self.shutter_open, self.shutter_close)
+        .transform(&orientation.transformation())
+    }
+
+    fn projection(&self) -> Projection {
+        match self.projection {
+            ProjectionKind::Perspective => Projection::Perspective,
+            ProjectionKind::Orthographic => Projection::Orthographic {
+                width: self.view_width,
+            },
+            ProjectionKind::Fisheye => Projection::Fisheye,
+            ProjectionKind::Equirectangular => Projection::Equirectangular,
+        }
+    }
+
+    pub fn sampling(&self) -> Sampling {
+        Sampling::new(self.samples, self.sampler.into(), self.filter.into())
+    }
+}
+
+impl Default for CameraDescription {
+    fn default() -> Self {
+        Self {
+            factor: 1,
+            horizontal_base: 64,
+            vertical_base: 48,
+            fov: 60.0,
+            from: CoordinatesDescription::new(0.0, 0.0, 0.0),
+            to: CoordinatesDescription::new(0.0, 0.0, -1.0),
+            up: CoordinatesDescription::new(0.0, 1.0, 0.0),
+            projection: ProjectionKind::Perspective,
+            view_width: 2.0,
+            aperture: 0.0,
+            focal_distance: 1.0.into(),
+            shutter_open: 0.0,
+            shutter_close: 0.0,
+            samples: 1,
+            sampler: SamplerKind::Grid,
+            filter: FilterKind::Box,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectionKind {
+    Perspective,
+    Orthographic,
+    Fisheye,
+    Equirectangular,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SamplerKind {
+    Grid,
+    Jittered,
+    LowDiscrepancy,
+}
+
+impl From<SamplerKind> for Sampler {
+    fn from(kind: SamplerKind) -> Self {
+        match kind {
+            SamplerKind::Grid => Sampler::Grid,
+            SamplerKind::Jittered => Sampler::Jittered,
+            SamplerKind::LowDiscrepancy => Sampler::LowDiscrepancy,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterKind {
+    Box,
+    Tent,
+    Gaussian,
+    Mitchell,
+}
+
+impl From<FilterKind> for Filter {
+    fn from(kind: FilterKind) -> Self {
+        match kind {
+            FilterKind::Box => Filter::Box,
+            FilterKind::Tent => Filter::Tent,
+            FilterKind::Gaussian => Filter::Gaussian,
+            FilterKind::Mitchell => Filter::Mitchell,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColorDescription {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+}
+
+impl ColorDescription {
+    pub fn color(&self) -> Color {
+        Color::new(self.r, self.g, self.b)
+    }
+}
+
+impl From<Color> for ColorDescription {
+    fn from(color: Color) -> Self {
+        Self {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CoordinatesDescription {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl CoordinatesDescription {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn point(&self) -> Point {
+        Point::new(self.x, self.y, self.z)
+    }
+
+    pub fn vector(&self) -> Vector3 {
+        Vector3::new(self.x, self.y, self.z)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RotateDescription {
+    pub over: Axis,
+    /// Rotation angle in degrees.
+    pub angle: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScaleDescription {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Default for ScaleDescription {
+    fn default() -> Self {
+        Self {
+            x: 1.0,
+            y: 1.0,
+            z: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShearDescription {
+    pub xy: f64,
+    pub xz: f64,
+    pub yx: f64,
+    pub yz: f64,
+    pub zx: f64,
+    pub zy: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum TransformStep {
+    Rotate(RotateDescription),
+    Scale(ScaleDescription),
+    Translate(CoordinatesDescription),
+    Shear(ShearDescription),
+}
+
+impl TransformStep {
+    pub fn transformation(&self) -> Matrix4 {
+        match *self {
+            TransformStep::Rotate(RotateDescription { over, angle }) => {
+                let over = match over {
+                    Axis::X => Over::X,
+                    Axis::Y => Over::Y,
+                    Axis::Z => Over::Z,
+                };
+                Rotate(over, degrees_to_radians(angle)).transformation()
+            }
+            TransformStep::Scale(ScaleDescription { x, y, z }) => Scale(x, y, z).transformation(),
+            TransformStep::Translate(CoordinatesDescription { x, y, z }) => {
+                Translate(x, y, z).transformation()
+            }
+            TransformStep::Shear(ShearDescription {
+                xy,
+                xz,
+                yx,
+                yz,
+                zx,
+                zy,
+            }) => Shear(xy, xz, yx, yz, zx, zy).transformation(),
+        }
+    }
+}
+
+/// Ordered list of transformations, applied in the same order as they are written.
+/// Accepts either a mapping (`Rotate`, `Scale`, ... may repeat) or a list of steps.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransformDescription {
+    pub steps: Vec<TransformStep>,
+}
+
+impl TransformDescription {
+    pub fn transformation(&self) -> Matrix4 {
+        self.steps
+            .iter()
+            .fold(Matrix4::identity(), |acc, step| step.transformation() * acc)
+    }
+}
+
+const TRANSFORM_KEYS: &[&str] = &["Rotate", "Scale", "Translate", "Shear"];
+
+#[derive(Deserialize)]
+enum TransformKey {
+    Rotate,
+    Scale,
+    Translate,
+    Shear,
+}
+
+impl<'de> Deserialize<'de> for TransformDescription {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TransformVisitor;
+
+        impl<'de> Visitor<'de> for TransformVisitor {
+            type Value = TransformDescription;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("mapping or list of transformations")
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E> {
+                Ok(TransformDescription::default())
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut steps = vec![];
+
+                while let Some(key) = map.next_key::<TransformKey>()? {
+                    steps.push(match key {
+                        TransformKey::Rotate => TransformStep::Rotate(map.next_value()?),
+                        TransformKey::Scale => TransformStep::Scale(map.next_value()?),
+                        TransformKey::Translate => TransformStep::Translate(map.next_value()?),
+                        TransformKey::Shear => TransformStep::Shear(map.next_value()?),
+                    });
+                }
+
+                Ok(TransformDescription { steps })
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut steps = vec![];
+
+                while let Some(step) = seq.next_element()? {
+                    steps.push(step);
+                }
+
+                Ok(TransformDescription { steps })
+            }
+        }
+
+        deserializer.deserialize_struct("Transform", TRANSFORM_KEYS, TransformVisitor)
+    }
+}
@@ -1,13 +1,36 @@
 use crate::canvas::Canvas;
 use crate::color::Color;
+use crate::float_eq::EPSILON;
 use crate::matrices::{Matrix, Matrix4};
 use crate::point::Point;
+use crate::progressive::{Accumulator, Pass, Progressive};
 use crate::ray::Ray;
 use crate::render::{Render, Rendering};
+use crate::sampling::{Adaptive, Rng, Sampler, Sampling};
+use crate::tiling::{Progress, Tiling};
 use crate::transformations::Transformable;
 use crate::vector::Vector3;
 use crate::world::World;
 use rayon::prelude::*;
+use std::f64::consts::PI;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::Instant;
+
+/// How the image is mapped onto rays leaving the camera.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    Perspective,
+    /// Parallel rays, `width` is the width of the view in world units.
+    Orthographic {
+        width: f64,
+    },
+    /// Equidistant fisheye, the field of view spans the circle fitting the shorter side.
+    Fisheye,
+    /// Full sphere around the camera, longitude along x and latitude along y. Best with an
+    /// image twice as wide as it is high.
+    Equirectangular,
+}
 
 #[derive(Debug, PartialEq)]
 pub struct Camera {
@@ -19,6 +42,11 @@ pub struct Camera {
     pixel_size: f64,
     half_width: f64,
     half_height: f64,
+    aperture: f64,
+    focal_distance: f64,
+    projection: Projection,
+    shutter_open: f64,
+    shutter_close: f64,
 }
 
 impl Camera {
@@ -43,69 +71,503 @@ impl Camera {
             pixel_size,
             half_width,
             half_height,
+            aperture: 0.0,
+            focal_distance: 1.0,
+            projection: Projection::Perspective,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
+        }
+    }
+
+    /// Same camera with another image size, field of view, orientation, lens, projection and
+    /// shutter are kept.
+    pub fn with_size(self, hsize: usize, vsize: usize) -> Self {
+        Self {
+            transformation: self.transformation,
+            transformation_inverse: self.transformation_inverse,
+            aperture: self.aperture,
+            focal_distance: self.focal_distance,
+            projection: self.projection,
+            shutter_open: self.shutter_open,
+            shutter_close: self.shutter_close,
+            ..Self::new(hsize, vsize, self.fov)
+        }
+    }
+
+    /// Thin lens of `aperture` radius, only what is `focal_distance` away from the camera is
+    /// sharp. An aperture of 0.0 is a pinhole camera where everything is sharp.
+    ///
+    /// A `focal_distance` that isn't positive is clamped to `EPSILON`, rays would otherwise
+    /// go nowhere or backwards.
+    pub fn with_lens(self, aperture: f64, focal_distance: f64) -> Self {
+        Self {
+            aperture: aperture.max(0.0),
+            focal_distance: focal_distance.max(EPSILON),
+            ..self
+        }
+    }
+
+    pub fn with_projection(self, projection: Projection) -> Self {
+        Self { projection, ..self }
+    }
+
+    /// Shutter open from time `open` to `close`, rays are spread over the interval so moving
+    /// primitives blur. Moving primitives go from their start to their end between 0.0 and
+    /// 1.0, a closed interval (the default) freezes the scene at `open`.
+    pub fn with_shutter(self, open: f64, close: f64) -> Self {
+        Self {
+            shutter_open: open,
+            shutter_close: close.max(open),
+            ..self
         }
     }
 
+    pub fn shutter_open(&self) -> f64 {
+        self.shutter_open
+    }
+
+    pub fn shutter_close(&self) -> f64 {
+        self.shutter_close
+    }
+
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    pub fn aperture(&self) -> f64 {
+        self.aperture
+    }
+
+    pub fn focal_distance(&self) -> f64 {
+        self.focal_distance
+    }
+
+    pub fn horizontal_size(&self) -> usize {
+        self.horizontal_size
+    }
+
+    pub fn vertical_size(&self) -> usize {
+        self.vertical_size
+    }
+
     pub fn ray_for_pixel(&self, px: f64, py: f64) -> Ray {
-        let x_offset = (px + 0.5) * self.pixel_size;
-        let y_offset = (py + 0.5) * self.pixel_size;
+        self.ray_through(px + 0.5, py + 0.5)
+    }
+
+    /// Ray through a point of the image, `x` and `y` are in pixels from its top left corner.
+    pub fn ray_through(&self, x: f64, y: f64) -> Ray {
+        self.ray_through_lens(x, y, (0.0, 0.0))
+    }
+
+    /// Same as `ray_through`, but starting at a point of the lens, `lens` is in the unit disk
+    /// with the center of the lens at the origin. Only perspective cameras have a lens.
+    pub fn ray_through_lens(&self, x: f64, y: f64, lens: (f64, f64)) -> Ray {
+        let x_offset = x * self.pixel_size;
+        let y_offset = y * self.pixel_size;
 
         let world_x = self.half_width - x_offset;
         let world_y = self.half_height - y_offset;
 
-        let transformation_inv = self.transformation_inverse;
-        let pixel = transformation_inv * Point::new(world_x, world_y, -1.0);
+        // origin and a point further along the ray, both in camera space
+        let (origin, target) = match self.projection {
+            Projection::Perspective => {
+                // the image plane sits at z = -1, its point is in focus `focal_distance` away
+                let focus = Point::new(world_x, world_y, -1.0) * self.focal_distance;
+                let lens_point = Point::new(lens.0 * self.aperture, lens.1 * self.aperture, 0.0);
+
+                (lens_point, focus)
+            }
+            Projection::Orthographic { width } => {
+                let scale = width / (2.0 * self.half_width);
+                let origin = Point::new(world_x * scale, world_y * scale, 0.0);
 
-        let origin = transformation_inv * Point::default();
-        let direction = (pixel - origin).normalize();
+                (origin, origin + Vector3::new(0.0, 0.0, -1.0))
+            }
+            Projection::Fisheye => {
+                let half_side = self.half_width.min(self.half_height);
+                let (nx, ny) = (world_x / half_side, world_y / half_side);
+                let radius = nx.hypot(ny);
+                let theta = radius * self.fov / 2.0;
+                let (sin_theta, cos_theta) = theta.sin_cos();
+
+                let direction = if radius > 0.0 {
+                    Vector3::new(sin_theta * nx / radius, sin_theta * ny / radius, -cos_theta)
+                } else {
+                    Vector3::new(0.0, 0.0, -1.0)
+                };
+
+                (Point::default(), Point::default() + direction)
+            }
+            Projection::Equirectangular => {
+                let longitude = world_x / self.half_width * PI;
+                let latitude = world_y / self.half_height * PI / 2.0;
+                let direction = Vector3::new(
+                    latitude.cos() * longitude.sin(),
+                    latitude.sin(),
+                    -latitude.cos() * longitude.cos(),
+                );
+
+                (Point::default(), Point::default() + direction)
+            }
+        };
+
+        let transformation_inv = self.transformation_inverse;
+        let target = transformation_inv * target;
+        let origin = transformation_inv * origin;
+        let direction = (target - origin).normalize();
 
         Ray::new(origin, Vector3::new(direction.x, direction.y, direction.z))
     }
 
+    /// Point of the unit disk to shoot a ray from, always the center for a pinhole camera.
+    fn lens_sample(&self, rng: &mut Rng) -> (f64, f64) {
+        if self.aperture <= 0.0 || self.projection != Projection::Perspective {
+            return (0.0, 0.0);
+        }
+
+        let radius = rng.next_f64().sqrt();
+        let angle = 2.0 * PI * rng.next_f64();
+
+        (radius * angle.cos(), radius * angle.sin())
+    }
+
+    /// Moment to cast a ray at, always the opening time when the shutter is instant.
+    fn shutter_sample(&self, rng: &mut Rng) -> f64 {
+        if self.shutter_close <= self.shutter_open {
+            return self.shutter_open;
+        }
+
+        self.shutter_open + (self.shutter_close - self.shutter_open) * rng.next_f64()
+    }
+
+    /// Ray through `x`, `y` from a random point of the lens at a random moment of the shutter.
+    fn sample_ray(&self, x: f64, y: f64, rng: &mut Rng) -> Ray {
+        let lens = self.lens_sample(rng);
+        self.ray_through_lens(x, y, lens)
+            .with_time(self.shutter_sample(rng))
+    }
+
+    /// Color of the pixel at `x`, `y`: the samples of `sampling` weighted by its filter.
+    pub fn color_for_pixel(&self, world: &World, x: usize, y: usize, sampling: &Sampling) -> Color {
+        let (color, total_weight) = self.pixel_samples(world, x, y, sampling);
+
+        if total_weight.abs() < EPSILON {
+            Color::black()
+        } else {
+            color / total_weight
+        }
+    }
+
+    /// Weighted sum of the samples of the pixel at `x`, `y` and the sum of their weights.
+    fn pixel_samples(
+        &self,
+        world: &World,
+        x: usize,
+        y: usize,
+        sampling: &Sampling,
+    ) -> (Color, f64) {
+        let (center_x, center_y) = (x as f64 + 0.5, y as f64 + 0.5);
+        let mut lens_rng = Rng::at_pixel(!sampling.seed, x, y);
+        let mut color = Color::black();
+        let mut total_weight = 0.0;
+
+        for (dx, dy) in sampling.pixel_offsets(x, y) {
+            let weight = sampling.filter.weight(dx, dy);
+            let ray = self.sample_ray(center_x + dx, center_y + dy, &mut lens_rng);
+
+            color = color + world.color_at(&ray) * weight;
+            total_weight += weight;
+        }
+
+        (color, total_weight)
+    }
+
     pub fn sequential_render(&self, world: &World) -> Canvas {
+        self.sequential_render_sampled(world, &Sampling::default())
+    }
+
+    pub fn parallel_render(&self, world: &World) -> Canvas {
+        self.parallel_render_sampled(world, &Sampling::default())
+    }
+
+    fn sequential_render_sampled(&self, world: &World, sampling: &Sampling) -> Canvas {
         let mut image = Canvas::new(self.horizontal_size, self.vertical_size, Color::default());
 
         for x in 0..self.horizontal_size {
             for y in 0..self.vertical_size {
-                let ray = self.ray_for_pixel(x as f64, y as f64);
-                let color = world.color_at(&ray);
-
-                image[y][x] = color;
+                image[y][x] = self.color_for_pixel(world, x, y, sampling);
             }
         }
 
         image
     }
 
-    pub fn parallel_render(&self, world: &World) -> Canvas {
-        const BAND_SIZE: usize = 12;
+    fn parallel_render_sampled(&self, world: &World, sampling: &Sampling) -> Canvas {
+        self.tiled_render(
+            world,
+            sampling,
+            &Tiling::default(),
+            &AtomicBool::new(false),
+            |_| {},
+        )
+    }
+
+    /// Render the image tile by tile on the rayon threads, which take the tiles in the order
+    /// of `tiling`. `on_progress` is called from the render threads after every tile. Once
+    /// `cancel` is set no more tiles are started, the ones left out stay black.
+    pub fn tiled_render<F>(
+        &self,
+        world: &World,
+        sampling: &Sampling,
+        tiling: &Tiling,
+        cancel: &AtomicBool,
+        on_progress: F,
+    ) -> Canvas
+    where
+        F: Fn(&Progress) + Sync,
+    {
+        let start = Instant::now();
+        let tiles = tiling.tiles(self.horizontal_size, self.vertical_size);
+        let (next, done) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let (sender, receiver) = mpsc::channel();
+
+        rayon::scope(|scope| {
+            for _ in 0..rayon::current_num_threads() {
+                let sender = sender.clone();
+                let (tiles, next, done, on_progress) = (&tiles, &next, &done, &on_progress);
+
+                scope.spawn(move |_| {
+                    while !cancel.load(Ordering::Relaxed) {
+                        let tile = match tiles.get(next.fetch_add(1, Ordering::Relaxed)) {
+                            Some(tile) => *tile,
+                            None => break,
+                        };
+
+                        let colors = (tile.y..tile.y + tile.height)
+                            .flat_map(|y| {
+                                (tile.x..tile.x + tile.width)
+                                    .map(move |x| self.color_for_pixel(world, x, y, sampling))
+                            })
+                            .collect::<Vec<_>>();
+                        // the receiver outlives the scope, sending can't fail
+                        let _ = sender.send((tile, colors));
+
+                        on_progress(&Progress {
+                            tiles_done: done.fetch_add(1, Ordering::Relaxed) + 1,
+                            tiles_total: tiles.len(),
+                            elapsed: start.elapsed(),
+                        });
+                    }
+                });
+            }
+        });
+        drop(sender);
+
         let mut image = Canvas::new(self.horizontal_size, self.vertical_size, Color::default());
+        for (tile, colors) in receiver {
+            for (i, color) in colors.into_iter().enumerate() {
+                image[tile.y + i / tile.width][tile.x + i % tile.width] = color;
+            }
+        }
 
         image
+    }
+
+    /// Render one sample per pixel, then refine the pixels that stand out from a neighbor
+    /// by splitting them into quadrants until they are smooth or out of samples. Returns the
+    /// image and the number of samples taken in total.
+    pub fn adaptive_render(&self, world: &World, adaptive: &Adaptive) -> (Canvas, usize) {
+        let mut first_pass =
+            Canvas::new(self.horizontal_size, self.vertical_size, Color::default());
+
+        first_pass
             .pixels()
-            .par_chunks_mut(self.horizontal_size * BAND_SIZE)
+            .par_chunks_mut(self.horizontal_size)
             .enumerate()
-            .for_each(|(i, band)| {
-                for row in 0..BAND_SIZE {
-                    for col in 0..self.horizontal_size {
-                        let ray = self.ray_for_pixel(col as f64, (row + i * BAND_SIZE) as f64);
-                        let color = world.color_at(&ray);
-                        band[row * self.horizontal_size + col] = color;
-                    }
+            .for_each(|(y, row)| {
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    let mut rng = Rng::at_pixel(0, x, y);
+                    let ray = self.sample_ray(x as f64 + 0.5, y as f64 + 0.5, &mut rng);
+                    *pixel = world.color_at(&ray);
                 }
             });
 
-        image
+        let mut image = Canvas::new(self.horizontal_size, self.vertical_size, Color::default());
+
+        let samples = image
+            .pixels()
+            .par_chunks_mut(self.horizontal_size)
+            .enumerate()
+            .map(|(y, row)| {
+                let mut samples = 0;
+
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    let color = first_pass[y][x];
+                    let mut taken = 1;
+
+                    *pixel = if self.neighbor_contrast(&first_pass, x, y) > adaptive.threshold {
+                        let center = (x as f64 + 0.5, y as f64 + 0.5);
+                        // the first lens and shutter samples were taken by the first pass
+                        let mut rng = Rng::at_pixel(0, x, y);
+                        self.lens_sample(&mut rng);
+                        self.shutter_sample(&mut rng);
+                        let mut refinement = Refinement {
+                            adaptive,
+                            taken: &mut taken,
+                            rng: &mut rng,
+                        };
+                        self.refine(world, center, 1.0, color, &mut refinement)
+                    } else {
+                        color
+                    };
+                    samples += taken;
+                }
+
+                samples
+            })
+            .sum();
+
+        (image, samples)
+    }
+
+    /// Largest channel difference between the pixel at `x`, `y` and its four neighbors.
+    fn neighbor_contrast(&self, image: &Canvas, x: usize, y: usize) -> f64 {
+        let color = image[y][x];
+        let neighbors = [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ];
+
+        neighbors
+            .iter()
+            .filter(|(nx, ny)| *nx < image.width() && *ny < image.height())
+            .map(|(nx, ny)| contrast(&color, &image[*ny][*nx]))
+            .fold(0.0, f64::max)
+    }
+
+    /// Average color of the square of `size` pixels around `center`, sampled at the centers
+    /// of its quadrants, which are split again while they disagree and samples are left.
+    fn refine(
+        &self,
+        world: &World,
+        center: (f64, f64),
+        size: f64,
+        color: Color,
+        refinement: &mut Refinement,
+    ) -> Color {
+        if *refinement.taken + 4 > refinement.adaptive.max_samples {
+            return color;
+        }
+
+        let quarter = size / 4.0;
+        let (x, y) = center;
+        let quadrants = [
+            (x - quarter, y - quarter),
+            (x + quarter, y - quarter),
+            (x - quarter, y + quarter),
+            (x + quarter, y + quarter),
+        ];
+        let colors = quadrants
+            .iter()
+            .map(|(qx, qy)| world.color_at(&self.sample_ray(*qx, *qy, refinement.rng)))
+            .collect::<Vec<_>>();
+        *refinement.taken += 4;
+
+        let threshold = refinement.adaptive.threshold;
+        let average = colors.iter().fold(Color::black(), |acc, c| acc + *c) / 4.0;
+        if colors.iter().all(|c| contrast(c, &average) <= threshold) {
+            return average;
+        }
+
+        quadrants
+            .iter()
+            .zip(colors)
+            .fold(Color::black(), |acc, (quadrant, quadrant_color)| {
+                acc + self.refine(world, *quadrant, size / 2.0, quadrant_color, refinement)
+            })
+            / 4.0
+    }
+
+    /// Render pass after pass of `sampling`, each with another seed, adding them up until
+    /// `progressive` says to stop. `on_pass` gets the image so far after every pass. A grid
+    /// sampler is jittered, otherwise every pass would take the very same samples.
+    pub fn progressive_render<F>(
+        &self,
+        world: &World,
+        sampling: &Sampling,
+        progressive: &Progressive,
+        mut on_pass: F,
+    ) -> Canvas
+    where
+        F: FnMut(&Pass, &Canvas),
+    {
+        let start = Instant::now();
+        let (width, height) = (self.horizontal_size, self.vertical_size);
+        let samples_per_pass = sampling.pixel_offsets(0, 0).len();
+        let mut accumulator = Accumulator::new(width, height);
+        let sampling = match sampling.sampler {
+            Sampler::Grid => Sampling {
+                sampler: Sampler::Jittered,
+                ..*sampling
+            },
+            _ => *sampling,
+        };
+
+        loop {
+            let pass_sampling =
+                sampling.with_seed(sampling.seed.wrapping_add(accumulator.passes() as u64));
+            let samples = (0..width * height)
+                .into_par_iter()
+                .map(|i| self.pixel_samples(world, i % width, i / width, &pass_sampling))
+                .collect::<Vec<_>>();
+            accumulator.add_pass(&samples);
+
+            let image = accumulator.canvas();
+            let pass = Pass {
+                number: accumulator.passes(),
+                samples_per_pixel: accumulator.passes() * samples_per_pass,
+                elapsed: start.elapsed(),
+                noise: accumulator.noise(),
+            };
+            on_pass(&pass, &image);
+
+            if progressive.is_done(&pass) {
+                return image;
+            }
+        }
     }
 
     pub fn render(&self, world: &World, setting: Render) -> Canvas {
         match setting.render_mode {
-            Rendering::Parallel => self.parallel_render(&world),
-            Rendering::Sequential => self.sequential_render(&world),
+            Rendering::Parallel => self.tiled_render(
+                world,
+                &setting.sampling,
+                &setting.tiling,
+                &AtomicBool::new(false),
+                |_| {},
+            ),
+            Rendering::Sequential => self.sequential_render_sampled(world, &setting.sampling),
+            Rendering::Adaptive(adaptive) => self.adaptive_render(world, &adaptive).0,
         }
     }
 }
 
+/// What is left of the sample budget of a refined pixel.
+struct Refinement<'a> {
+    adaptive: &'a Adaptive,
+    taken: &'a mut usize,
+    rng: &'a mut Rng,
+}
+
+fn contrast(a: &Color, b: &Color) -> f64 {
+    (a.r - b.r)
+        .abs()
+        .max((a.g - b.g).abs())
+        .max((a.b - b.b).abs())
+}
+
 impl Transformable for Camera {
     fn transform(self, transformation: &Matrix4) -> Self {
         let delta = *transformation * self.transformation;
@@ -119,13 +581,27 @@ impl Transformable for Camera {
 
 #[cfg(test)]
 mod camera_tests {
-    use crate::camera::Camera;
+    use crate::camera::{Camera, Projection};
+    use crate::color::Color;
+    use crate::float_eq::EPSILON;
+    use crate::lights::PointLight;
+    use crate::material::Material;
     use crate::matrices::{Matrix, Matrix4};
     use crate::point::Point;
+    use crate::primitives::PrimitiveShape::{CubeShape, MovingShape};
+    use crate::primitives::{Cube, Moving};
+    use crate::progressive::Progressive;
     use crate::ray::Ray;
-    use crate::transformations::{Over, Transformable};
+    use crate::render::{Render, Rendering};
+    use crate::sampling::{Adaptive, Filter, Rng, Sampler, Sampling};
+    use crate::tiling::{TileOrder, Tiling};
+    use crate::transformations::{Over, Transform, Transformable};
     use crate::vector::Vector3;
+    use crate::world::World;
     use std::f64::consts::PI;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::time::Duration;
 
     #[test]
     fn camera_creation() {
@@ -139,6 +615,11 @@ mod camera_tests {
             pixel_size: 0.012499999999999999,
             half_width: 0.9999999999999999,
             half_height: 0.75,
+            aperture: 0.0,
+            focal_distance: 1.0,
+            projection: Projection::Perspective,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
         };
 
         assert_eq!(expected_camera, camera);
@@ -177,4 +658,399 @@ mod camera_tests {
 
         assert_eq!(expected_ray, camera.ray_for_pixel(100.0, 50.0));
     }
+
+    #[test]
+    fn camera_with_size_keeps_orientation() {
+        let camera = Camera::new(201, 101, PI / 2.0)
+            .translate(0.0, -2.0, 5.0)
+            .rotate(Over::Y, PI / 4.0)
+            .transform()
+            .with_size(401, 201);
+
+        let expected_ray = Ray::new(
+            Point::new(0.0, 2.0, -5.0),
+            Vector3::new(f64::sqrt(2.0) / 2.0, 0.0, -f64::sqrt(2.0) / 2.0),
+        );
+
+        assert_eq!(401, camera.horizontal_size());
+        assert_eq!(201, camera.vertical_size());
+        assert_eq!(expected_ray, camera.ray_for_pixel(200.0, 100.0));
+    }
+
+    #[test]
+    fn camera_supersampling_blends_edges() {
+        // a flat white wall covering the right half of a single pixel image
+        let wall = Cube::default()
+            .apply_material(Material::default().ambient(1.0).diffuse(0.0).specular(0.0))
+            .scale(10.0, 10.0, 1.0)
+            .translate(10.0, 0.0, -5.0)
+            .transform();
+        let world = World::default()
+            .add_object(CubeShape(wall))
+            .add_light_source(PointLight::new(Color::white(), Point::default()));
+        let camera = Camera::new(1, 1, PI / 2.0);
+        let sampling = Sampling::new(16, Sampler::Grid, Filter::Box);
+        let expected_color = Color::new(0.5, 0.5, 0.5);
+
+        assert_eq!(
+            expected_color,
+            camera.color_for_pixel(&world, 0, 0, &sampling)
+        );
+        assert_eq!(
+            world.color_at(&camera.ray_for_pixel(0.0, 0.0)),
+            camera.color_for_pixel(&world, 0, 0, &Sampling::default())
+        );
+    }
+
+    fn half_wall_world() -> World {
+        let wall = Cube::default()
+            .apply_material(Material::default().ambient(1.0).diffuse(0.0).specular(0.0))
+            .scale(10.0, 10.0, 1.0)
+            .translate(10.0, 0.0, -5.0)
+            .transform();
+
+        World::default()
+            .add_object(CubeShape(wall))
+            .add_light_source(PointLight::new(Color::white(), Point::default()))
+    }
+
+    #[test]
+    fn camera_parallel_render_any_size() {
+        // 13 rows used to overrun the last band of 12 rows
+        let world = half_wall_world();
+        let camera = Camera::new(7, 13, PI / 2.0);
+
+        let sequential = camera.sequential_render(&world);
+        let parallel = camera.parallel_render(&world);
+
+        for y in 0..13 {
+            assert_eq!(sequential[y], parallel[y]);
+        }
+    }
+
+    #[test]
+    fn camera_tiled_render_reports_every_tile() {
+        let world = half_wall_world();
+        let camera = Camera::new(10, 7, PI / 2.0);
+        let tiling = Tiling::new(3, TileOrder::Hilbert);
+        let done = Mutex::new(vec![]);
+
+        let image = camera.tiled_render(
+            &world,
+            &Sampling::default(),
+            &tiling,
+            &AtomicBool::new(false),
+            |progress| done.lock().unwrap().push(*progress),
+        );
+        let mut done = done.into_inner().unwrap();
+        done.sort_by_key(|progress| progress.tiles_done);
+
+        assert_eq!(12, done.len());
+        assert!(done.iter().all(|progress| progress.tiles_total == 12));
+        assert_eq!(12, done[11].tiles_done);
+        assert_eq!(camera.sequential_render(&world)[6], image[6]);
+    }
+
+    #[test]
+    fn camera_tiled_render_cancellation() {
+        let world = half_wall_world();
+        let camera = Camera::new(8, 8, PI / 2.0);
+        let tiling = Tiling::new(1, TileOrder::Rows);
+        let cancel = AtomicBool::new(false);
+        let done = AtomicUsize::new(0);
+
+        camera.tiled_render(&world, &Sampling::default(), &tiling, &cancel, |_| {
+            done.fetch_add(1, Ordering::Relaxed);
+            cancel.store(true, Ordering::Relaxed);
+        });
+
+        // every thread finishes the tile it is on, no new tile is started
+        assert!(done.load(Ordering::Relaxed) <= rayon::current_num_threads());
+
+        let image = camera.tiled_render(&world, &Sampling::default(), &tiling, &cancel, |_| {
+            panic!("no tile should be rendered once cancelled")
+        });
+        assert_eq!(Color::black(), image[0][0]);
+    }
+
+    #[test]
+    fn camera_progressive_render_converges_on_edges() {
+        let world = half_wall_world();
+        let camera = Camera::new(1, 1, PI / 2.0);
+        let sampling = Sampling::new(4, Sampler::Jittered, Filter::Box);
+        let mut passes = vec![];
+
+        let image =
+            camera.progressive_render(&world, &sampling, &Progressive::new(8), |pass, _| {
+                passes.push(*pass)
+            });
+
+        assert_eq!(8, passes.len());
+        assert_eq!(None, passes[0].noise);
+        assert_eq!(32, passes[7].samples_per_pixel);
+        assert!((image[0][0].r - 0.5).abs() < 0.15);
+    }
+
+    #[test]
+    fn camera_progressive_render_jitters_a_grid() {
+        let world = half_wall_world();
+        let camera = Camera::new(1, 1, PI / 2.0);
+        let mut passes = vec![];
+
+        let image = camera.progressive_render(
+            &world,
+            &Sampling::default(),
+            &Progressive::new(8),
+            |pass, _| passes.push(*pass),
+        );
+
+        assert!(passes[7].noise.unwrap() > 0.0);
+        assert!(image[0][0].r > 0.0 && image[0][0].r < 1.0);
+    }
+
+    #[test]
+    fn camera_progressive_render_stops_when_smooth() {
+        // the edge runs between pixels, so none of them changes and the noise is gone after two
+        let camera = Camera::new(4, 3, PI / 2.0);
+        let world = half_wall_world();
+        let progressive = Progressive::new(10).with_noise_threshold(0.001);
+        let mut snapshots = 0;
+
+        let image =
+            camera.progressive_render(&world, &Sampling::default(), &progressive, |_, _| {
+                snapshots += 1
+            });
+        let expected_image = camera.sequential_render(&world);
+
+        assert_eq!(2, snapshots);
+        assert_eq!(expected_image[1][0], image[1][0]);
+        assert_eq!(expected_image[1][3], image[1][3]);
+    }
+
+    #[test]
+    fn camera_progressive_render_stops_when_out_of_time() {
+        let camera = Camera::new(4, 3, PI / 2.0);
+        let progressive = Progressive::new(10).with_time_budget(Duration::from_secs(0));
+        let mut snapshots = 0;
+
+        camera.progressive_render(
+            &half_wall_world(),
+            &Sampling::default(),
+            &progressive,
+            |_, _| snapshots += 1,
+        );
+
+        assert_eq!(1, snapshots);
+    }
+
+    #[test]
+    fn camera_adaptive_render_refines_only_edges() {
+        let world = half_wall_world();
+        let camera = Camera::new(8, 8, PI / 2.0);
+        let adaptive = Adaptive::new(0.1, 17);
+
+        let (image, samples) = camera.adaptive_render(&world, &adaptive);
+
+        // the two columns around the edge are refined, 16 more samples each at most
+        assert!(samples > 64);
+        assert!(samples <= 64 + 16 * 16);
+        assert_eq!(Color::white(), image[0][0]);
+        assert_eq!(Color::black(), image[0][7]);
+        assert!(image[4][3].r + image[4][4].r > 0.0);
+    }
+
+    #[test]
+    fn camera_adaptive_render_flat_image_takes_one_sample_per_pixel() {
+        let camera = Camera::new(6, 4, PI / 2.0);
+
+        let (image, samples) = camera.adaptive_render(&World::default(), &Adaptive::default());
+
+        assert_eq!(24, samples);
+        assert_eq!(Color::black(), image[3][5]);
+    }
+
+    #[test]
+    fn camera_adaptive_render_respects_sample_budget() {
+        let world = half_wall_world();
+        let camera = Camera::new(2, 1, PI / 2.0);
+        let (_, samples) = camera.adaptive_render(&world, &Adaptive::new(0.0, 5));
+        let (_, no_budget) = camera.adaptive_render(&world, &Adaptive::new(0.0, 3));
+
+        assert_eq!(10, samples);
+        assert_eq!(2, no_budget);
+    }
+
+    #[test]
+    fn camera_render_adaptive_setting() {
+        let world = half_wall_world();
+        let camera = Camera::new(8, 8, PI / 2.0);
+        let adaptive = Adaptive::new(0.1, 17);
+        let setting = Render {
+            render_mode: Rendering::Adaptive(adaptive),
+            ..Render::default()
+        };
+
+        let image = camera.render(&world, setting);
+        let (expected, _) = camera.adaptive_render(&world, &adaptive);
+
+        for y in 0..8 {
+            assert_eq!(expected[y], image[y]);
+        }
+    }
+
+    #[test]
+    fn camera_lens_clamps_focal_distance() {
+        let zero = Camera::new(201, 101, PI / 2.0).with_lens(0.5, 0.0);
+        let negative = Camera::new(201, 101, PI / 2.0).with_lens(0.5, -4.0);
+        let nan = Camera::new(201, 101, PI / 2.0).with_lens(0.5, f64::NAN);
+
+        assert_eq!(EPSILON, zero.focal_distance());
+        assert_eq!(EPSILON, negative.focal_distance());
+        assert_eq!(EPSILON, nan.focal_distance());
+    }
+
+    #[test]
+    fn camera_lens_rays_meet_on_focal_plane() {
+        let camera = Camera::new(201, 101, PI / 2.0).with_lens(0.5, 4.0);
+        let center = camera.ray_through(100.5, 50.5);
+        let edge = camera.ray_through_lens(100.5, 50.5, (1.0, 0.0));
+        let expected_focus = Point::new(0.0, 0.0, -4.0);
+
+        assert_eq!(Point::new(0.5, 0.0, 0.0), edge.origin);
+        assert_eq!(expected_focus, center.position(4.0));
+        assert_eq!(
+            expected_focus,
+            edge.position((expected_focus - edge.origin).magnitude())
+        );
+    }
+
+    #[test]
+    fn camera_pinhole_ignores_lens_samples() {
+        let camera = Camera::new(201, 101, PI / 2.0);
+
+        assert_eq!(
+            camera.ray_for_pixel(10.0, 20.0),
+            camera.ray_through_lens(10.5, 20.5, (0.7, -0.3))
+        );
+    }
+
+    #[test]
+    fn camera_with_size_keeps_lens() {
+        let camera = Camera::new(201, 101, PI / 2.0)
+            .with_lens(0.2, 3.0)
+            .with_size(20, 10);
+
+        assert_eq!(0.2, camera.aperture());
+        assert_eq!(3.0, camera.focal_distance());
+    }
+
+    #[test]
+    fn camera_shutter_spreads_ray_times() {
+        let camera = Camera::new(20, 10, PI / 2.0)
+            .with_shutter(0.25, 0.75)
+            .with_size(10, 10);
+        let mut rng = Rng::new(7);
+
+        assert_eq!(0.25, camera.shutter_open());
+        assert_eq!(0.75, camera.shutter_close());
+        for _ in 0..100 {
+            let time = camera.sample_ray(5.0, 5.0, &mut rng).time;
+            assert!((0.25..0.75).contains(&time));
+        }
+        assert_eq!(
+            0.0,
+            Camera::new(10, 10, PI / 2.0)
+                .sample_ray(5.0, 5.0, &mut rng)
+                .time
+        );
+    }
+
+    #[test]
+    fn camera_shutter_blurs_moving_primitives() {
+        // a white wall filling the image slides out of view while the shutter is open
+        let wall = Cube::default()
+            .apply_material(Material::default().ambient(1.0).diffuse(0.0).specular(0.0))
+            .scale(10.0, 10.0, 1.0)
+            .translate(0.0, 0.0, -5.0)
+            .transform();
+        let moving = Moving::new(
+            CubeShape(wall),
+            Matrix4::identity(),
+            Transform::Translate(40.0, 0.0, 0.0).transformation(),
+        );
+        let world = World::default()
+            .add_object(MovingShape(moving))
+            .add_light_source(PointLight::new(Color::white(), Point::default()));
+        let sampling = Sampling::new(64, Sampler::Jittered, Filter::Box);
+
+        let color_between = |open, close| {
+            Camera::new(1, 1, PI / 2.0)
+                .with_shutter(open, close)
+                .color_for_pixel(&world, 0, 0, &sampling)
+        };
+
+        assert_eq!(Color::white(), color_between(0.0, 0.0));
+        assert_eq!(Color::black(), color_between(1.0, 1.0));
+        let blurred = color_between(0.0, 1.0);
+        assert!(blurred.r > 0.1 && blurred.r < 0.5);
+    }
+
+    #[test]
+    fn camera_orthographic_rays_are_parallel() {
+        let camera = Camera::new(200, 100, PI / 2.0)
+            .with_projection(Projection::Orthographic { width: 20.0 })
+            .translate(0.0, 0.0, 5.0)
+            .transform();
+        let expected_direction = Vector3::new(0.0, 0.0, -1.0);
+
+        let center = camera.ray_through(100.0, 50.0);
+        let corner = camera.ray_through(0.0, 0.0);
+
+        assert_eq!(Point::new(0.0, 0.0, -5.0), center.origin);
+        assert_eq!(Point::new(10.0, 5.0, -5.0), corner.origin);
+        assert_eq!(expected_direction, center.direction);
+        assert_eq!(expected_direction, corner.direction);
+    }
+
+    #[test]
+    fn camera_fisheye_angle_grows_with_radius() {
+        let camera = Camera::new(100, 100, PI).with_projection(Projection::Fisheye);
+
+        let center = camera.ray_through(50.0, 50.0);
+        let edge = camera.ray_through(0.0, 50.0);
+
+        assert_eq!(Vector3::new(0.0, 0.0, -1.0), center.direction);
+        assert_eq!(Vector3::new(1.0, 0.0, 0.0), edge.direction);
+    }
+
+    #[test]
+    fn camera_equirectangular_covers_the_sphere() {
+        let camera = Camera::new(200, 100, PI / 2.0).with_projection(Projection::Equirectangular);
+
+        assert_eq!(
+            Vector3::new(0.0, 0.0, -1.0),
+            camera.ray_through(100.0, 50.0).direction
+        );
+        assert_eq!(
+            Vector3::new(1.0, 0.0, 0.0),
+            camera.ray_through(50.0, 50.0).direction
+        );
+        assert_eq!(
+            Vector3::new(0.0, 0.0, 1.0),
+            camera.ray_through(0.0, 50.0).direction
+        );
+        assert_eq!(
+            Vector3::new(0.0, 1.0, 0.0),
+            camera.ray_through(100.0, 0.0).direction
+        );
+    }
+
+    #[test]
+    fn camera_with_size_keeps_projection() {
+        let camera = Camera::new(20, 10, PI / 2.0)
+            .with_projection(Projection::Fisheye)
+            .with_size(40, 20);
+
+        assert_eq!(Projection::Fisheye, camera.projection());
+    }
 }
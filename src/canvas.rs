@@ -39,17 +39,19 @@ impl Canvas {
     }
 
     fn convert_component(&self, component: f64) -> u8 {
-        let component = if component < 0.0 {
-            0.0
-        } else if component > 1.0 {
-            1.0
-        } else {
-            component
-        };
+        let component = component.clamp(0.0, 1.0);
 
         (component * 255.0) as u8
     }
 
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
     pub fn pixels(&mut self) -> &mut Vec<Color> {
         &mut self.pixels
     }
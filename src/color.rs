@@ -57,7 +57,7 @@ impl Default for Color {
 
 impl PartialEq for Color {
     fn eq(&self, other: &Self) -> bool {
-        self.approx_eq_low(&other)
+        self.approx_eq_low(other)
     }
 }
 
@@ -5,11 +5,23 @@ use std::{cmp::Ordering, fmt::Debug, ops::Index};
 pub struct Intersection {
     pub time: f64,
     pub object: PrimitiveShape,
+    /// Barycentric coordinates of the hit, only triangles fill them in.
+    pub u: f64,
+    pub v: f64,
 }
 
 impl Intersection {
     pub fn new(time: f64, object: PrimitiveShape) -> Intersection {
-        Intersection { time, object }
+        Intersection {
+            time,
+            object,
+            u: 0.0,
+            v: 0.0,
+        }
+    }
+
+    pub fn with_uv(time: f64, object: PrimitiveShape, u: f64, v: f64) -> Intersection {
+        Intersection { time, object, u, v }
     }
 }
 
@@ -55,11 +67,7 @@ impl Intersections {
     }
 
     pub fn hit(&self) -> Option<&Intersection> {
-        self.intersections
-            .iter()
-            .filter(|i| i.time >= 0.0)
-            .into_iter()
-            .min()
+        self.intersections.iter().filter(|i| i.time >= 0.0).min()
     }
 
     pub fn merge(&mut self, other: Intersections) {
@@ -67,7 +75,7 @@ impl Intersections {
     }
 
     pub fn sort(mut self) -> Self {
-        self.intersections.sort_by(|a, b| a.cmp(b));
+        self.intersections.sort();
         self
     }
 
@@ -76,6 +84,12 @@ impl Intersections {
     }
 }
 
+impl Default for Intersections {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Index<usize> for Intersections {
     type Output = Intersection;
 
@@ -93,7 +107,7 @@ mod intersection_tests {
     #[test]
     fn intersection_creation() {
         let sphere = Sphere::default();
-        let intersection_a = Intersection::new(3.5, PrimitiveShape::SphereShape(sphere));
+        let intersection_a = Intersection::new(3.5, PrimitiveShape::SphereShape(sphere.clone()));
 
         assert_eq!(3.5, intersection_a.time);
         assert_eq!(PrimitiveShape::SphereShape(sphere), intersection_a.object);
@@ -102,7 +116,7 @@ mod intersection_tests {
     #[test]
     fn intersections_aggregating() {
         let sphere = Sphere::default();
-        let intersection_a = Intersection::new(1.0, PrimitiveShape::SphereShape(sphere));
+        let intersection_a = Intersection::new(1.0, PrimitiveShape::SphereShape(sphere.clone()));
         let intersection_b = Intersection::new(2.0, PrimitiveShape::SphereShape(sphere));
 
         let intersections = Intersections::new().with(vec![intersection_a, intersection_b]);
@@ -116,7 +130,7 @@ mod intersection_tests {
     #[test]
     fn intersections_hit_all_positive() {
         let sphere = Sphere::default();
-        let intersection_a = Intersection::new(1.0, PrimitiveShape::SphereShape(sphere));
+        let intersection_a = Intersection::new(1.0, PrimitiveShape::SphereShape(sphere.clone()));
         let intersection_b = Intersection::new(2.0, PrimitiveShape::SphereShape(sphere));
         let intersections =
             Intersections::new().with(vec![intersection_a.clone(), intersection_b.clone()]);
@@ -128,7 +142,7 @@ mod intersection_tests {
     #[test]
     fn intersections_hit_some_negative() {
         let sphere = Sphere::default();
-        let intersection_a = Intersection::new(-1.0, PrimitiveShape::SphereShape(sphere));
+        let intersection_a = Intersection::new(-1.0, PrimitiveShape::SphereShape(sphere.clone()));
         let intersection_b = Intersection::new(2.0, PrimitiveShape::SphereShape(sphere));
         let intersections =
             Intersections::new().with(vec![intersection_a.clone(), intersection_b.clone()]);
@@ -140,7 +154,7 @@ mod intersection_tests {
     #[test]
     fn intersections_hit_all_negative() {
         let sphere = Sphere::default();
-        let intersection_a = Intersection::new(-1.0, PrimitiveShape::SphereShape(sphere));
+        let intersection_a = Intersection::new(-1.0, PrimitiveShape::SphereShape(sphere.clone()));
         let intersection_b = Intersection::new(-2.0, PrimitiveShape::SphereShape(sphere));
         let intersections =
             Intersections::new().with(vec![intersection_a.clone(), intersection_b.clone()]);
@@ -152,9 +166,9 @@ mod intersection_tests {
     #[test]
     fn intersections_hit_lowest_non_negative() {
         let sphere = Sphere::default();
-        let intersection_a = Intersection::new(5.0, PrimitiveShape::SphereShape(sphere));
-        let intersection_b = Intersection::new(7.0, PrimitiveShape::SphereShape(sphere));
-        let intersection_c = Intersection::new(-1.0, PrimitiveShape::SphereShape(sphere));
+        let intersection_a = Intersection::new(5.0, PrimitiveShape::SphereShape(sphere.clone()));
+        let intersection_b = Intersection::new(7.0, PrimitiveShape::SphereShape(sphere.clone()));
+        let intersection_c = Intersection::new(-1.0, PrimitiveShape::SphereShape(sphere.clone()));
         let intersection_d = Intersection::new(2.0, PrimitiveShape::SphereShape(sphere));
         let intersections = Intersections::new().with(vec![
             intersection_a,
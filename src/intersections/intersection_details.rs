@@ -19,23 +19,19 @@ pub struct IntersectionDetails {
     pub n1: f64,
     pub n2: f64,
     pub under_point: Point,
+    /// Time of the ray that hit, secondary rays are cast at the same moment.
+    pub ray_time: f64,
 }
 
 impl IntersectionDetails {
     pub fn from(intersection: &Intersection, ray: &Ray) -> Self {
         let point = ray.position(intersection.time);
         let eye_vector = -ray.direction;
-        let mut normal_vector = intersection.object.normal(&point);
+        let (geometric_normal, normal_vector, inside) =
+            IntersectionDetails::normals(intersection, &point, &eye_vector);
 
-        let inside = if normal_vector.dot(&eye_vector) < 0.0 {
-            normal_vector = -normal_vector;
-            true
-        } else {
-            false
-        };
-
-        let over_point = point + normal_vector * f64::EPSILON * 10000.0;
-        let under_point = point - normal_vector * f64::EPSILON * 10000.0;
+        let over_point = point + geometric_normal * f64::EPSILON * 10000.0;
+        let under_point = point - geometric_normal * f64::EPSILON * 10000.0;
         let reflection_vector = ray.direction.reflect(&normal_vector);
 
         Self {
@@ -50,6 +46,7 @@ impl IntersectionDetails {
             n1: 1.0, // TEMP
             n2: 1.0, // TEMP
             under_point,
+            ray_time: ray.time,
         }
     }
 
@@ -60,20 +57,14 @@ impl IntersectionDetails {
     ) -> Self {
         let point = ray.position(hit_intersection.time);
         let eye_vector = -ray.direction;
-        let mut normal_vector = hit_intersection.object.normal(&point);
-
-        let inside = if normal_vector.dot(&eye_vector) < 0.0 {
-            normal_vector = -normal_vector;
-            true
-        } else {
-            false
-        };
+        let (geometric_normal, normal_vector, inside) =
+            IntersectionDetails::normals(hit_intersection, &point, &eye_vector);
 
-        let over_point = point + normal_vector * f64::EPSILON * 10000.0;
-        let under_point = point - normal_vector * f64::EPSILON * 10000.0;
+        let over_point = point + geometric_normal * f64::EPSILON * 10000.0;
+        let under_point = point - geometric_normal * f64::EPSILON * 10000.0;
         let reflection_vector = ray.direction.reflect(&normal_vector);
 
-        let mut container: VecDeque<PrimitiveShape> = VecDeque::new();
+        let mut container: VecDeque<&PrimitiveShape> = VecDeque::new();
 
         let mut n1 = 1.0;
         let mut n2 = 1.0;
@@ -87,15 +78,15 @@ impl IntersectionDetails {
                 };
             }
 
-            if container.contains(&intersection.object) {
+            if container.contains(&&intersection.object) {
                 let index = container
                     .iter()
-                    .position(|x| *x == intersection.object)
+                    .position(|x| **x == intersection.object)
                     .expect("INTERSECTION_DETAILS: unable to find object in vector!");
 
                 container.remove(index);
             } else {
-                container.push_back(intersection.object.clone())
+                container.push_back(&intersection.object)
             }
 
             if intersection == hit_intersection {
@@ -119,9 +110,27 @@ impl IntersectionDetails {
             n1,
             n2,
             under_point,
+            ray_time: ray.time,
         }
     }
 
+    /// Geometric normal and shading normal at `point` facing `eye_vector`, and whether the
+    /// hit is from inside the object. The points above and below the surface follow the
+    /// geometric normal so a bump can't push them through it.
+    fn normals(
+        intersection: &Intersection,
+        point: &Point,
+        eye_vector: &Vector3,
+    ) -> (Vector3, Vector3, bool) {
+        let normal = intersection.object.normal_at(point, intersection);
+        let inside = normal.dot(eye_vector) < 0.0;
+        let normal = if inside { -normal } else { normal };
+        let material = intersection.object.material();
+        let shading_normal = material.normal_at(&intersection.object, point, &normal);
+
+        (normal, shading_normal, inside)
+    }
+
     pub fn schlick(&self) -> f64 {
         let mut cos = self.eye_vector.dot(&self.normal_vector);
 
@@ -144,9 +153,11 @@ impl IntersectionDetails {
 
 #[cfg(test)]
 mod intersection_details_tests {
+    use crate::color::Color;
     use crate::float_eq::ApproxEq;
     use crate::intersections::{Intersection, IntersectionDetails, Intersections};
     use crate::material::Material;
+    use crate::patterns::{Bump, Pattern};
     use crate::point::Point;
     use crate::primitives::PrimitiveShape::{PlaneShape, SphereShape};
     use crate::primitives::{Plane, Sphere};
@@ -160,7 +171,7 @@ mod intersection_details_tests {
         let ray = Ray::new(Point::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
         let sphere_default = Sphere::default();
         let sphere = SphereShape(sphere_default);
-        let intersection = Intersection::new(1.0, sphere.clone());
+        let intersection = Intersection::new(1.0, sphere);
         let expected_eye_vector = Vector3::new(0.0, 0.0, -1.0);
         let expected_normal_vector = Vector3::new(0.0, 0.0, -1.0);
 
@@ -184,6 +195,29 @@ mod intersection_details_tests {
         assert!(intersection_details.point.z > intersection_details.over_point.z);
     }
 
+    #[test]
+    fn intersection_details_bumped_normal() {
+        let ray = Ray::new(Point::new(0.5, 1.0, 0.3), Vector3::new(0.0, -1.0, 0.0));
+        let bump = Bump::height(
+            Pattern::new_gradient(Color::black(), Color::white())
+                .scale(2.0, 1.0, 1.0)
+                .transform(),
+        )
+        .with_strength(2.0);
+        let plane_default = Plane::default().apply_material(Material::default().apply_bump(bump));
+        let plane = PlaneShape(plane_default);
+        let intersection = Intersection::new(1.0, plane);
+        let expected_normal_vector = Vector3::new(-1.0, 1.0, 0.0).normalize();
+
+        let intersection_details = IntersectionDetails::from(&intersection, &ray);
+
+        assert!(expected_normal_vector.approx_eq_low(&intersection_details.normal_vector));
+        // the points around the surface still follow the plane
+        assert_eq!(0.5, intersection_details.over_point.x);
+        assert!(intersection_details.over_point.y > 0.0);
+        assert!(intersection_details.under_point.y < 0.0);
+    }
+
     #[test]
     fn intersection_details_reflection_vector() {
         let ray = Ray::new(
@@ -228,9 +262,9 @@ mod intersection_details_tests {
         let intersection_a_1 = Intersection::new(2.0, sphere_a.clone());
         let intersection_b_1 = Intersection::new(2.75, sphere_b.clone());
         let intersection_c_1 = Intersection::new(3.25, sphere_c.clone());
-        let intersection_b_2 = Intersection::new(4.75, sphere_b.clone());
-        let intersection_c_2 = Intersection::new(5.25, sphere_c.clone());
-        let intersection_a_2 = Intersection::new(6.0, sphere_a.clone());
+        let intersection_b_2 = Intersection::new(4.75, sphere_b);
+        let intersection_c_2 = Intersection::new(5.25, sphere_c);
+        let intersection_a_2 = Intersection::new(6.0, sphere_a);
 
         let intersections = Intersections::new().with(vec![
             intersection_a_1.clone(),
@@ -322,7 +356,7 @@ mod intersection_details_tests {
             .apply_material(Material::default().refractive_index(1.5).transparency(1.0));
         let sphere_a = SphereShape(sphere_a_default);
         let intersection_a = Intersection::new(-sqrt2 / 2.0, sphere_a.clone());
-        let intersection_b = Intersection::new(sqrt2 / 2.0, sphere_a.clone());
+        let intersection_b = Intersection::new(sqrt2 / 2.0, sphere_a);
         let intersections =
             Intersections::new().with(vec![intersection_a.clone(), intersection_b.clone()]);
         let intersection_details =
@@ -339,7 +373,7 @@ mod intersection_details_tests {
             .apply_material(Material::default().refractive_index(1.5).transparency(1.0));
         let sphere_a = SphereShape(sphere_a_default);
         let intersection_a = Intersection::new(-1.0, sphere_a.clone());
-        let intersection_b = Intersection::new(1.0, sphere_a.clone());
+        let intersection_b = Intersection::new(1.0, sphere_a);
         let intersections =
             Intersections::new().with(vec![intersection_a.clone(), intersection_b.clone()]);
         let intersection_details =
@@ -355,7 +389,7 @@ mod intersection_details_tests {
         let sphere_a_default = Sphere::default()
             .apply_material(Material::default().refractive_index(1.5).transparency(1.0));
         let sphere_a = SphereShape(sphere_a_default);
-        let intersection_a = Intersection::new(1.8589, sphere_a.clone());
+        let intersection_a = Intersection::new(1.8589, sphere_a);
         let intersections = Intersections::new().with(vec![intersection_a.clone()]);
         let intersection_details =
             IntersectionDetails::from_many(&intersection_a, &intersections, &ray);
@@ -1,3 +1,8 @@
+// some tests compare against rounded values such as 0.70711
+#![cfg_attr(test, allow(clippy::approx_constant))]
+
+pub mod arguments;
+pub mod background;
 pub mod camera;
 pub mod canvas;
 pub mod color;
@@ -6,47 +11,93 @@ pub mod float_eq;
 pub mod material;
 pub mod point;
 pub mod preset;
+pub mod progressive;
 pub mod ray;
 pub mod render;
+pub mod sampling;
+pub mod tiling;
 pub mod utils;
 pub mod vector;
 pub mod world;
 
+pub mod acceleration {
+    pub use bounding_box::BoundingBox;
+    pub use bvh::{Acceleration, Bvh};
+    mod bounding_box;
+    mod bvh;
+}
+
 pub mod patterns {
+    pub use blended::{BlendMode, BlendedPattern};
+    pub use bump::{Bump, BumpKind};
     pub use checker::CheckerPattern;
     pub use gradient::GradientPattern;
-    pub use pattern::{Pattern, PatternType};
+    pub use marble::MarblePattern;
+    pub use noise::{NoiseKind, NoisePattern, Perlin};
+    pub use pattern::{Pattern, PatternSlot, PatternType};
+    pub use perturbed::PerturbedPattern;
     pub use plain::PlainPattern;
+    pub use radial_gradient::RadialGradientPattern;
     pub use ring::RingPattern;
     pub use stripe::StripePattern;
-    pub use texture::TexturePattern;
+    pub use texture::{TextureFilter, TextureImage, TexturePattern, TextureWrap};
+    pub use uv_mapping::UvMapping;
+    pub use wood::WoodPattern;
+    mod blended;
+    mod bump;
     mod checker;
     mod gradient;
+    mod marble;
+    mod noise;
     mod pattern;
+    mod perturbed;
     mod plain;
+    mod radial_gradient;
     mod ring;
     mod stripe;
     mod texture;
+    mod uv_mapping;
+    mod wood;
 }
 
 pub mod primitives {
     pub use primitive::{Primitive, PrimitiveShape};
 
+    pub use cone::Cone;
+    pub use csg::{Csg, CsgOperation};
     pub use cube::Cube;
+    pub use cylinder::Cylinder;
+    pub use group::Group;
+    pub use mesh::{Face, Mesh, MeshData};
+    pub use moving::Moving;
     pub use plane::Plane;
     pub use sphere::Sphere;
     pub use triangle::Triangle;
     mod primitive;
 
+    mod cone;
+    mod csg;
     mod cube;
+    mod cylinder;
+    mod group;
+    mod mesh;
+    mod moving;
     mod plane;
     mod sphere;
     mod triangle;
 }
 
 pub mod lights {
+    pub use area_light::{AreaLight, AreaShape};
+    pub use directional_light::DirectionalLight;
+    pub use light::{Light, LightSample};
     pub use point_light::PointLight;
+    pub use spot_light::SpotLight;
+    mod area_light;
+    mod directional_light;
+    mod light;
     mod point_light;
+    mod spot_light;
 }
 
 pub mod intersections {
@@ -74,6 +125,10 @@ pub mod transformations {
 }
 
 pub mod builder {
+    pub use document::{Diagnostic, Mark, Marked, SceneError};
     pub use terraform::Terraform;
+    mod document;
+    pub mod obj;
+    pub mod scene;
     pub mod terraform;
 }
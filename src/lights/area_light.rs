@@ -0,0 +1,200 @@
+use crate::color::Color;
+use crate::point::Point;
+use crate::sampling::Rng;
+use crate::vector::Vector3;
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AreaShape {
+    /// Parallelogram spanned by `u` and `v` from `corner`.
+    Rectangle {
+        corner: Point,
+        u: Vector3,
+        v: Vector3,
+    },
+    /// Disk around `center`, `u` and `v` are its radii.
+    Disk {
+        center: Point,
+        u: Vector3,
+        v: Vector3,
+    },
+    Sphere {
+        center: Point,
+        radius: f64,
+    },
+}
+
+/// Light with a surface. Its surface is split into `usteps` by `vsteps` cells and every cell
+/// is sampled at a jittered point, the jitter only depends on `seed` and the lit point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AreaLight {
+    pub intensity: Color,
+    pub shape: AreaShape,
+    pub usteps: usize,
+    pub vsteps: usize,
+    pub seed: u64,
+}
+
+impl AreaLight {
+    pub fn new(intensity: Color, shape: AreaShape, usteps: usize, vsteps: usize) -> Self {
+        Self {
+            intensity,
+            shape,
+            usteps: usteps.max(1),
+            vsteps: vsteps.max(1),
+            seed: 0,
+        }
+    }
+
+    pub fn rectangle(
+        intensity: Color,
+        corner: Point,
+        u: Vector3,
+        v: Vector3,
+        usteps: usize,
+        vsteps: usize,
+    ) -> Self {
+        Self::new(
+            intensity,
+            AreaShape::Rectangle { corner, u, v },
+            usteps,
+            vsteps,
+        )
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.usteps * self.vsteps
+    }
+
+    /// Center of the light, where it is for anything that needs a single position.
+    pub fn position(&self) -> Point {
+        match self.shape {
+            AreaShape::Rectangle { corner, u, v } => corner + u * 0.5 + v * 0.5,
+            AreaShape::Disk { center, .. } | AreaShape::Sphere { center, .. } => center,
+        }
+    }
+
+    /// Point on the surface for the unit square coordinates `s`, `t`.
+    fn point_on_surface(&self, s: f64, t: f64) -> Point {
+        match self.shape {
+            AreaShape::Rectangle { corner, u, v } => corner + u * s + v * t,
+            AreaShape::Disk { center, u, v } => {
+                let radius = s.sqrt();
+                let angle = 2.0 * PI * t;
+                center + u * (radius * angle.cos()) + v * (radius * angle.sin())
+            }
+            AreaShape::Sphere { center, radius } => {
+                let z = 1.0 - 2.0 * s;
+                let ring = (1.0 - z * z).max(0.0).sqrt();
+                let angle = 2.0 * PI * t;
+                center + Vector3::new(ring * angle.cos(), ring * angle.sin(), z) * radius
+            }
+        }
+    }
+
+    /// One jittered sample per cell, the same `point` always gets the same samples.
+    pub fn samples(&self, point: &Point) -> Vec<Point> {
+        let mut rng = Rng::at_point(self.seed, point);
+        let mut samples = Vec::with_capacity(self.sample_count());
+
+        for v in 0..self.vsteps {
+            for u in 0..self.usteps {
+                let s = (u as f64 + rng.next_f64()) / self.usteps as f64;
+                let t = (v as f64 + rng.next_f64()) / self.vsteps as f64;
+                samples.push(self.point_on_surface(s, t));
+            }
+        }
+
+        samples
+    }
+}
+
+#[cfg(test)]
+mod area_light_tests {
+    use crate::color::Color;
+    use crate::lights::{AreaLight, AreaShape};
+    use crate::point::Point;
+    use crate::vector::Vector3;
+
+    fn rectangle() -> AreaLight {
+        AreaLight::rectangle(
+            Color::white(),
+            Point::new(0.0, 0.0, 0.0),
+            Vector3::new(2.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 1.0),
+            4,
+            2,
+        )
+    }
+
+    #[test]
+    fn area_light_creation() {
+        let light = rectangle();
+
+        assert_eq!(8, light.sample_count());
+        assert_eq!(Point::new(1.0, 0.0, 0.5), light.position());
+    }
+
+    #[test]
+    fn area_light_samples_stay_in_their_cell() {
+        let light = rectangle();
+        let samples = light.samples(&Point::new(0.0, 5.0, 0.0));
+
+        assert_eq!(8, samples.len());
+        for (index, sample) in samples.iter().enumerate() {
+            let (u, v) = ((index % 4) as f64, (index / 4) as f64);
+
+            assert!(sample.x >= u * 0.5 && sample.x <= (u + 1.0) * 0.5);
+            assert!(sample.z >= v * 0.5 && sample.z <= (v + 1.0) * 0.5);
+            assert_eq!(0.0, sample.y);
+        }
+    }
+
+    #[test]
+    fn area_light_samples_are_deterministic() {
+        let point = Point::new(0.3, 1.0, -2.0);
+
+        assert_eq!(rectangle().samples(&point), rectangle().samples(&point));
+        assert_ne!(
+            rectangle().samples(&point),
+            rectangle().with_seed(1).samples(&point)
+        );
+    }
+
+    #[test]
+    fn area_light_disk_and_sphere_samples_on_surface() {
+        let disk = AreaLight::new(
+            Color::white(),
+            AreaShape::Disk {
+                center: Point::new(0.0, 3.0, 0.0),
+                u: Vector3::new(1.0, 0.0, 0.0),
+                v: Vector3::new(0.0, 0.0, 1.0),
+            },
+            3,
+            3,
+        );
+        let sphere = AreaLight::new(
+            Color::white(),
+            AreaShape::Sphere {
+                center: Point::new(0.0, 3.0, 0.0),
+                radius: 0.5,
+            },
+            3,
+            3,
+        );
+
+        for sample in disk.samples(&Point::default()) {
+            assert_eq!(3.0, sample.y);
+            assert!(sample.x.powi(2) + sample.z.powi(2) <= 1.0);
+        }
+        for sample in sphere.samples(&Point::default()) {
+            let distance = (sample - Point::new(0.0, 3.0, 0.0)).magnitude();
+            assert!((distance - 0.5).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::color::Color;
+use crate::vector::Vector3;
+
+/// Light infinitely far away, like the sun. Every point is lit from the same `direction`
+/// (the direction the light travels in) with the same intensity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirectionalLight {
+    pub intensity: Color,
+    pub direction: Vector3,
+}
+
+impl DirectionalLight {
+    /// Panics when `direction` is zero, the light would come from nowhere.
+    pub fn new(intensity: Color, direction: Vector3) -> Self {
+        assert!(
+            !direction.near_zero(),
+            "directional light direction is zero"
+        );
+
+        Self {
+            intensity,
+            direction: direction.normalize(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod directional_light_tests {
+    use crate::color::Color;
+    use crate::lights::DirectionalLight;
+    use crate::vector::Vector3;
+
+    #[test]
+    fn directional_light_creation() {
+        let light = DirectionalLight::new(Color::white(), Vector3::new(0.0, -3.0, 0.0));
+
+        assert_eq!(Color::white(), light.intensity);
+        assert_eq!(Vector3::new(0.0, -1.0, 0.0), light.direction);
+    }
+
+    #[test]
+    #[should_panic(expected = "directional light direction is zero")]
+    fn directional_light_needs_a_direction() {
+        DirectionalLight::new(Color::white(), Vector3::new(0.0, 0.0, 0.0));
+    }
+}
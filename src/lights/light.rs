@@ -0,0 +1,182 @@
+use crate::color::Color;
+use crate::lights::{AreaLight, DirectionalLight, PointLight, SpotLight};
+use crate::point::Point;
+use crate::vector::Vector3;
+
+/// Any light of a world. Shading and shadows only see a light through the samples it gives
+/// for a point, so they don't need to know what kind of light it is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Light {
+    Point(PointLight),
+    Area(AreaLight),
+    Spot(SpotLight),
+    Directional(DirectionalLight),
+}
+
+/// One sample of a light as seen from a lit point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightSample {
+    /// Unit vector from the lit point towards the light.
+    pub direction: Vector3,
+    /// Distance to the light, infinite for directional lights.
+    pub distance: f64,
+    /// Intensity arriving at the lit point.
+    pub intensity: Color,
+}
+
+impl LightSample {
+    fn towards(position: &Point, point: &Point, intensity: Color) -> Self {
+        let delta = Vector3::from(*position - *point);
+
+        Self {
+            direction: delta.normalize(),
+            distance: delta.magnitude(),
+            intensity,
+        }
+    }
+}
+
+impl Light {
+    /// Intensity the light is emitted with, before cones or shadows.
+    pub fn intensity(&self) -> Color {
+        match self {
+            Light::Point(light) => light.intensity,
+            Light::Area(light) => light.intensity,
+            Light::Spot(light) => light.intensity,
+            Light::Directional(light) => light.intensity,
+        }
+    }
+
+    /// Unit vector from `point` towards the light, area lights are seen as their center.
+    pub fn direction_from(&self, point: &Point) -> Vector3 {
+        self.sample_from(point).direction
+    }
+
+    /// Distance between `point` and the light, infinite for directional lights.
+    pub fn distance_from(&self, point: &Point) -> f64 {
+        self.sample_from(point).distance
+    }
+
+    /// Intensity arriving at `point` when nothing is in the way.
+    pub fn intensity_at(&self, point: &Point) -> Color {
+        self.sample_from(point).intensity
+    }
+
+    /// Intensity lighting the ambient term at `point`. It fades with the distance like the
+    /// rest of the light, but isn't cut off by the cone of a spot light.
+    pub fn ambient_at(&self, point: &Point) -> Color {
+        match self {
+            Light::Point(_) => self.intensity_at(point),
+            _ => self.intensity(),
+        }
+    }
+
+    fn sample_from(&self, point: &Point) -> LightSample {
+        match self {
+            Light::Point(light) => {
+                let sample = LightSample::towards(&light.position, point, light.intensity);
+
+                LightSample {
+                    intensity: sample.intensity * light.attenuation(sample.distance),
+                    ..sample
+                }
+            }
+            Light::Area(light) => LightSample::towards(&light.position(), point, light.intensity),
+            Light::Spot(light) => LightSample::towards(
+                &light.position,
+                point,
+                light.intensity * light.cone_factor(point),
+            ),
+            Light::Directional(light) => LightSample {
+                direction: -light.direction,
+                distance: f64::INFINITY,
+                intensity: light.intensity,
+            },
+        }
+    }
+
+    /// Samples of the light seen from `point`, only area lights have more than one.
+    pub fn samples(&self, point: &Point) -> Vec<LightSample> {
+        match self {
+            Light::Area(light) => light
+                .samples(point)
+                .iter()
+                .map(|sample| LightSample::towards(sample, point, light.intensity))
+                .collect(),
+            _ => vec![self.sample_from(point)],
+        }
+    }
+}
+
+impl From<PointLight> for Light {
+    fn from(light: PointLight) -> Self {
+        Light::Point(light)
+    }
+}
+
+impl From<AreaLight> for Light {
+    fn from(light: AreaLight) -> Self {
+        Light::Area(light)
+    }
+}
+
+impl From<SpotLight> for Light {
+    fn from(light: SpotLight) -> Self {
+        Light::Spot(light)
+    }
+}
+
+impl From<DirectionalLight> for Light {
+    fn from(light: DirectionalLight) -> Self {
+        Light::Directional(light)
+    }
+}
+
+#[cfg(test)]
+mod light_tests {
+    use crate::color::Color;
+    use crate::lights::{DirectionalLight, Light, PointLight, SpotLight};
+    use crate::point::Point;
+    use crate::vector::Vector3;
+    use std::f64::consts::FRAC_PI_4;
+
+    #[test]
+    fn light_point_queries() {
+        let light = Light::Point(PointLight::new(Color::white(), Point::new(0.0, 4.0, 3.0)));
+        let point = Point::default();
+
+        assert_eq!(Vector3::new(0.0, 0.8, 0.6), light.direction_from(&point));
+        assert_eq!(5.0, light.distance_from(&point));
+        assert_eq!(Color::white(), light.intensity_at(&point));
+    }
+
+    #[test]
+    fn light_directional_queries() {
+        let light = Light::Directional(DirectionalLight::new(
+            Color::white(),
+            Vector3::new(0.0, -1.0, 0.0),
+        ));
+
+        for point in [Point::default(), Point::new(100.0, -3.0, 42.0)] {
+            assert_eq!(Vector3::new(0.0, 1.0, 0.0), light.direction_from(&point));
+            assert_eq!(f64::INFINITY, light.distance_from(&point));
+            assert_eq!(Color::white(), light.intensity_at(&point));
+        }
+    }
+
+    #[test]
+    fn light_spot_intensity_outside_the_cone() {
+        let light = Light::Spot(SpotLight::new(
+            Color::white(),
+            Point::new(0.0, 10.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            FRAC_PI_4,
+        ));
+
+        assert_eq!(Color::white(), light.intensity_at(&Point::default()));
+        assert_eq!(
+            Color::black(),
+            light.intensity_at(&Point::new(20.0, 0.0, 0.0))
+        );
+    }
+}
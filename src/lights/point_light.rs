@@ -1,10 +1,16 @@
 use crate::color::Color;
 use crate::point::Point;
 
+/// Light from a single point. Its intensity is divided by
+/// `constant + linear * d + quadratic * d^2` at a distance `d`, which is 1.0 by default so
+/// the light doesn't fade.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct PointLight {
     pub intensity: Color,
     pub position: Point,
+    pub constant: f64,
+    pub linear: f64,
+    pub quadratic: f64,
 }
 
 impl PointLight {
@@ -12,6 +18,26 @@ impl PointLight {
         Self {
             intensity,
             position,
+            constant: 1.0,
+            linear: 0.0,
+            quadratic: 0.0,
+        }
+    }
+
+    pub fn with_attenuation(&mut self, constant: f64, linear: f64, quadratic: f64) {
+        self.constant = constant;
+        self.linear = linear;
+        self.quadratic = quadratic;
+    }
+
+    /// Part of the intensity left after travelling `distance`.
+    pub fn attenuation(&self, distance: f64) -> f64 {
+        let divisor = self.constant + self.linear * distance + self.quadratic * distance.powi(2);
+
+        if divisor > 0.0 {
+            1.0 / divisor
+        } else {
+            1.0
         }
     }
 
@@ -26,10 +52,7 @@ impl PointLight {
 
 impl Default for PointLight {
     fn default() -> Self {
-        Self {
-            intensity: Color::default(),
-            position: Point::default(),
-        }
+        Self::new(Color::default(), Point::default())
     }
 }
 
@@ -46,5 +69,16 @@ mod point_light_tests {
 
         assert_eq!(expected_intensity, point_light.intensity);
         assert_eq!(Point::default(), point_light.position);
+        assert_eq!(1.0, point_light.attenuation(100.0));
+    }
+
+    #[test]
+    fn point_light_attenuation() {
+        let mut point_light = PointLight::new(Color::new(1.0, 1.0, 1.0), Point::default());
+        point_light.with_attenuation(1.0, 0.5, 0.5);
+
+        assert_eq!(1.0, point_light.attenuation(0.0));
+        assert_eq!(0.5, point_light.attenuation(1.0));
+        assert_eq!(0.25, point_light.attenuation(2.0));
     }
 }
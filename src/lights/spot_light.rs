@@ -0,0 +1,108 @@
+use crate::color::Color;
+use crate::point::Point;
+use crate::vector::Vector3;
+
+/// Point light only shining in a cone around `direction`. Points up to `angle - falloff`
+/// away from the axis get the full intensity, which then fades out to nothing at `angle`.
+/// Both angles are in radians.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpotLight {
+    pub intensity: Color,
+    pub position: Point,
+    pub direction: Vector3,
+    pub angle: f64,
+    pub falloff: f64,
+}
+
+impl SpotLight {
+    /// Panics when `direction` is zero, the cone would point nowhere.
+    pub fn new(intensity: Color, position: Point, direction: Vector3, angle: f64) -> Self {
+        assert!(!direction.near_zero(), "spot light direction is zero");
+
+        Self {
+            intensity,
+            position,
+            direction: direction.normalize(),
+            angle,
+            falloff: 0.0,
+        }
+    }
+
+    pub fn with_falloff(mut self, falloff: f64) -> Self {
+        self.falloff = falloff.clamp(0.0, self.angle);
+        self
+    }
+
+    /// Part of the intensity reaching `point`, 1.0 inside the cone and 0.0 outside of it.
+    pub fn cone_factor(&self, point: &Point) -> f64 {
+        let to_point = Vector3::from(*point - self.position).normalize();
+        let cos_angle = to_point.dot(&self.direction);
+        let (outer, inner) = (self.angle.cos(), (self.angle - self.falloff).cos());
+
+        if cos_angle >= inner {
+            1.0
+        } else if cos_angle <= outer {
+            0.0
+        } else {
+            let t = (cos_angle - outer) / (inner - outer);
+            t * t * (3.0 - 2.0 * t)
+        }
+    }
+}
+
+#[cfg(test)]
+mod spot_light_tests {
+    use crate::color::Color;
+    use crate::lights::SpotLight;
+    use crate::point::Point;
+    use crate::vector::Vector3;
+    use std::f64::consts::FRAC_PI_4;
+
+    fn spot_light() -> SpotLight {
+        SpotLight::new(
+            Color::white(),
+            Point::new(0.0, 10.0, 0.0),
+            Vector3::new(0.0, -2.0, 0.0),
+            FRAC_PI_4,
+        )
+    }
+
+    #[test]
+    fn spot_light_creation() {
+        let light = spot_light();
+
+        assert_eq!(Vector3::new(0.0, -1.0, 0.0), light.direction);
+        assert_eq!(0.0, light.falloff);
+    }
+
+    #[test]
+    #[should_panic(expected = "spot light direction is zero")]
+    fn spot_light_needs_a_direction() {
+        SpotLight::new(
+            Color::white(),
+            Point::default(),
+            Vector3::new(0.0, 0.0, 0.0),
+            FRAC_PI_4,
+        );
+    }
+
+    #[test]
+    fn spot_light_hard_cone() {
+        let light = spot_light();
+
+        assert_eq!(1.0, light.cone_factor(&Point::new(0.0, 0.0, 0.0)));
+        assert_eq!(1.0, light.cone_factor(&Point::new(9.9, 0.0, 0.0)));
+        assert_eq!(0.0, light.cone_factor(&Point::new(10.1, 0.0, 0.0)));
+        assert_eq!(0.0, light.cone_factor(&Point::new(0.0, 20.0, 0.0)));
+    }
+
+    #[test]
+    fn spot_light_falloff_fades_the_edge() {
+        let light = spot_light().with_falloff(FRAC_PI_4 / 2.0);
+        let edge = light.cone_factor(&Point::new(7.0, 0.0, 0.0));
+
+        assert_eq!(1.0, light.cone_factor(&Point::new(4.0, 0.0, 0.0)));
+        assert!(edge > 0.0 && edge < 1.0);
+        assert_eq!(0.0, light.cone_factor(&Point::new(10.1, 0.0, 0.0)));
+    }
+}
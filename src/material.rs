@@ -1,11 +1,11 @@
 use crate::color::Color;
-use crate::lights::PointLight;
-use crate::patterns::Pattern;
+use crate::lights::Light;
+use crate::patterns::{Bump, Pattern};
 use crate::point::Point;
 use crate::primitives::PrimitiveShape;
 use crate::vector::Vector3;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Material {
     pub color: Color,
     pub ambient: f64,
@@ -15,11 +15,16 @@ pub struct Material {
     pub refractive_index: f64,
     pub transparency: f64,
     pub shininess: f64,
+    /// Whether the surface blocks light on its way to other surfaces.
+    pub casts_shadow: bool,
     // TODO: replace with default Pattern
     pub pattern: Option<Pattern>,
+    /// Detail added to the shading normal.
+    pub bump: Option<Bump>,
 }
 
 impl Material {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         color: Color,
         ambient: f64,
@@ -42,51 +47,79 @@ impl Material {
             refractive_index,
             transparency,
             shininess,
+            casts_shadow: true,
             pattern: None,
+            bump: None,
         }
     }
 
+    /// Phong shading of `position`, `light_intensity` is the part of the light that reaches
+    /// it per channel: black in full shadow, white when every sample of the light is visible.
     pub fn phong_reflection(
         &self,
-        light: &PointLight,
+        light: &Light,
         primitive: &PrimitiveShape,
         position: &Point,
         eye_vector: &Vector3,
         normal_vector: &Vector3,
-        in_shadow: bool,
+        light_intensity: Color,
     ) -> Color {
-        let mut color = self.color;
-        if let Some(pattern) = &self.pattern {
-            color = pattern.pattern_at_local(primitive, position);
+        let color = self.color_at(primitive, position);
+        let ambient = color * light.ambient_at(position) * self.ambient;
+
+        if light_intensity == Color::black() {
+            return ambient;
         }
-        let effective_color = color * light.intensity;
-        let ambient = effective_color * self.ambient;
 
-        if in_shadow {
-            ambient
-        } else {
-            let mut diffuse = Color::black();
-            let mut specular = Color::black();
+        let samples = light.samples(position);
+        let mut lit = Color::black();
 
-            let delta = (light.position - *position).normalize();
-            let light_vector = Vector3::new(delta.x, delta.y, delta.z);
-            let light_dot_normal = light_vector.dot(&normal_vector);
+        for sample in samples.iter() {
+            let effective_color = color * sample.intensity;
+            let light_dot_normal = sample.direction.dot(normal_vector);
 
             if light_dot_normal >= 0.0 {
-                diffuse = effective_color * self.diffuse * light_dot_normal;
-                let reflect_vector = (-light_vector).reflect(&normal_vector);
-                let reflect_dot_eye = reflect_vector.dot(&eye_vector);
+                lit = lit + effective_color * self.diffuse * light_dot_normal;
+                let reflect_vector = (-sample.direction).reflect(normal_vector);
+                let reflect_dot_eye = reflect_vector.dot(eye_vector);
 
                 if reflect_dot_eye > 0.0 {
                     let factor = f64::powf(reflect_dot_eye, self.shininess);
-                    specular = light.intensity * self.specular * factor
+                    lit = lit + sample.intensity * self.specular * factor
                 }
             }
+        }
+
+        ambient + lit * light_intensity / samples.len() as f64
+    }
 
-            ambient + diffuse + specular
+    /// Color of the surface at `position`, the pattern when there is one.
+    pub fn color_at(&self, primitive: &PrimitiveShape, position: &Point) -> Color {
+        match &self.pattern {
+            Some(pattern) => pattern.pattern_at_local(primitive, position),
+            None => self.color,
+        }
+    }
+
+    /// Normal used to shade `position`, the geometric `normal` with the bump when there is
+    /// one.
+    pub fn normal_at(
+        &self,
+        primitive: &PrimitiveShape,
+        position: &Point,
+        normal: &Vector3,
+    ) -> Vector3 {
+        match &self.bump {
+            Some(bump) => bump.perturb(primitive, position, normal),
+            None => *normal,
         }
     }
 
+    pub fn apply_bump(mut self, bump: Bump) -> Self {
+        self.bump = Some(bump);
+        self
+    }
+
     pub fn apply_pattern(mut self, pattern: Pattern) -> Self {
         self.pattern = Some(pattern);
         self
@@ -131,6 +164,11 @@ impl Material {
         self.transparency = transparency;
         self
     }
+
+    pub fn casts_shadow(mut self, casts_shadow: bool) -> Self {
+        self.casts_shadow = casts_shadow;
+        self
+    }
 }
 
 impl Default for Material {
@@ -144,7 +182,9 @@ impl Default for Material {
             refractive_index: 1.0,
             transparency: 0.0,
             shininess: 200.0,
+            casts_shadow: true,
             pattern: Default::default(),
+            bump: None,
         }
     }
 }
@@ -152,7 +192,7 @@ impl Default for Material {
 #[cfg(test)]
 mod material_tests {
     use crate::color::Color;
-    use crate::lights::PointLight;
+    use crate::lights::{AreaLight, Light, PointLight};
     use crate::material::Material;
     use crate::patterns::Pattern;
     use crate::point::Point;
@@ -179,6 +219,7 @@ mod material_tests {
         assert_eq!(expected_transparency, default_material.transparency);
         assert_eq!(expected_specular, default_material.specular);
         assert_eq!(expected_shininess, default_material.shininess);
+        assert!(default_material.casts_shadow);
     }
 
     #[test]
@@ -187,7 +228,10 @@ mod material_tests {
         let position = Point::default();
         let eye_vector = Vector3::new(0.0, 0.0, -1.0);
         let normal_vector = Vector3::new(0.0, 0.0, -1.0);
-        let light = PointLight::new(Color::new(1.0, 1.0, 1.0), Point::new(0.0, 0.0, -10.0));
+        let light = Light::Point(PointLight::new(
+            Color::new(1.0, 1.0, 1.0),
+            Point::new(0.0, 0.0, -10.0),
+        ));
 
         let expected_color = Color::new(1.9, 1.9, 1.9);
 
@@ -199,7 +243,7 @@ mod material_tests {
                 &position,
                 &eye_vector,
                 &normal_vector,
-                false
+                Color::white()
             )
         );
     }
@@ -210,8 +254,11 @@ mod material_tests {
         let position = Point::default();
         let eye_vector = Vector3::new(0.0, f64::sqrt(2.0) / 2.0, -f64::sqrt(2.0) / 2.0);
         let normal_vector = Vector3::new(0.0, 0.0, -1.0);
-        let light = PointLight::new(Color::new(1.0, 1.0, 1.0), Point::new(0.0, 0.0, -10.0));
-        let in_shadow = false;
+        let light = Light::Point(PointLight::new(
+            Color::new(1.0, 1.0, 1.0),
+            Point::new(0.0, 0.0, -10.0),
+        ));
+        let light_intensity = Color::white();
         let expected_color = Color::new(1.0, 1.0, 1.0);
 
         assert_eq!(
@@ -222,7 +269,7 @@ mod material_tests {
                 &position,
                 &eye_vector,
                 &normal_vector,
-                in_shadow
+                light_intensity
             )
         );
     }
@@ -233,8 +280,11 @@ mod material_tests {
         let position = Point::default();
         let eye_vector = Vector3::new(0.0, 0.0, -1.0);
         let normal_vector = Vector3::new(0.0, 0.0, -1.0);
-        let light = PointLight::new(Color::new(1.0, 1.0, 1.0), Point::new(0.0, 10.0, -10.0));
-        let in_shadow = false;
+        let light = Light::Point(PointLight::new(
+            Color::new(1.0, 1.0, 1.0),
+            Point::new(0.0, 10.0, -10.0),
+        ));
+        let light_intensity = Color::white();
         let expected_color = Color::new(0.7364, 0.7364, 0.7364);
 
         assert_eq!(
@@ -245,7 +295,7 @@ mod material_tests {
                 &position,
                 &eye_vector,
                 &normal_vector,
-                in_shadow
+                light_intensity
             )
         );
     }
@@ -256,8 +306,11 @@ mod material_tests {
         let position = Point::default();
         let eye_vector = Vector3::new(0.0, -f64::sqrt(2.0) / 2.0, -f64::sqrt(2.0) / 2.0);
         let normal_vector = Vector3::new(0.0, 0.0, -1.0);
-        let light = PointLight::new(Color::new(1.0, 1.0, 1.0), Point::new(0.0, 10.0, -10.0));
-        let in_shadow = false;
+        let light = Light::Point(PointLight::new(
+            Color::new(1.0, 1.0, 1.0),
+            Point::new(0.0, 10.0, -10.0),
+        ));
+        let light_intensity = Color::white();
         let expected_color = Color::new(1.6364, 1.6364, 1.6364);
 
         assert_eq!(
@@ -268,7 +321,7 @@ mod material_tests {
                 &position,
                 &eye_vector,
                 &normal_vector,
-                in_shadow
+                light_intensity
             )
         );
     }
@@ -279,8 +332,11 @@ mod material_tests {
         let position = Point::default();
         let eye_vector = Vector3::new(0.0, 0.0, -1.0);
         let normal_vector = Vector3::new(0.0, 0.0, -1.0);
-        let light = PointLight::new(Color::new(1.0, 1.0, 1.0), Point::new(0.0, 0.0, 10.0));
-        let in_shadow = false;
+        let light = Light::Point(PointLight::new(
+            Color::new(1.0, 1.0, 1.0),
+            Point::new(0.0, 0.0, 10.0),
+        ));
+        let light_intensity = Color::white();
         let expected_color = Color::new(0.1, 0.1, 0.1);
 
         assert_eq!(
@@ -291,7 +347,7 @@ mod material_tests {
                 &position,
                 &eye_vector,
                 &normal_vector,
-                in_shadow
+                light_intensity
             )
         );
     }
@@ -302,8 +358,11 @@ mod material_tests {
         let position = Point::default();
         let eye_vector = Vector3::new(0.0, 0.0, -1.0);
         let normal_vector = Vector3::new(0.0, 0.0, -1.0);
-        let light = PointLight::new(Color::new(1.0, 1.0, 1.0), Point::new(0.0, 0.0, -10.0));
-        let in_shadow = true;
+        let light = Light::Point(PointLight::new(
+            Color::new(1.0, 1.0, 1.0),
+            Point::new(0.0, 0.0, -10.0),
+        ));
+        let light_intensity = Color::black();
 
         let expected_color = Color::new(0.1, 0.1, 0.1);
 
@@ -315,7 +374,64 @@ mod material_tests {
                 &position,
                 &eye_vector,
                 &normal_vector,
-                in_shadow
+                light_intensity
+            )
+        );
+    }
+
+    #[test]
+    fn material_with_partial_light_intensity() {
+        let material = Material::default();
+        let position = Point::default();
+        let eye_vector = Vector3::new(0.0, 0.0, -1.0);
+        let normal_vector = Vector3::new(0.0, 0.0, -1.0);
+        let light = Light::Point(PointLight::new(
+            Color::new(1.0, 1.0, 1.0),
+            Point::new(0.0, 0.0, -10.0),
+        ));
+        let light_intensity = Color::new(0.5, 0.5, 0.5);
+        let expected_color = Color::new(1.0, 1.0, 1.0);
+
+        assert_eq!(
+            expected_color,
+            material.phong_reflection(
+                &light,
+                &PrimitiveShape::SphereShape(Sphere::default()),
+                &position,
+                &eye_vector,
+                &normal_vector,
+                light_intensity
+            )
+        );
+    }
+
+    #[test]
+    fn material_averages_area_light_samples() {
+        let material = Material::default();
+        let position = Point::default();
+        let eye_vector = Vector3::new(0.0, 0.0, -1.0);
+        let normal_vector = Vector3::new(0.0, 0.0, -1.0);
+        // one sample in front of the surface and one behind it
+        let light = Light::Area(AreaLight::rectangle(
+            Color::new(1.0, 1.0, 1.0),
+            Point::new(0.0, 0.0, -10.0),
+            Vector3::new(0.0, 0.0, 20.0),
+            Vector3::new(0.0, 0.0, 0.0),
+            2,
+            1,
+        ));
+        let light_intensity = Color::white();
+        let expected_color = Color::new(1.0, 1.0, 1.0);
+
+        assert_eq!(
+            expected_color,
+            material.phong_reflection(
+                &light,
+                &PrimitiveShape::SphereShape(Sphere::default()),
+                &position,
+                &eye_vector,
+                &normal_vector,
+                light_intensity
             )
         );
     }
@@ -334,8 +450,11 @@ mod material_tests {
         let position_b = Point::new(1.1, 0.0, 0.0);
         let eye_vector = Vector3::new(0.0, 0.0, -1.0);
         let normal_vector = Vector3::new(0.0, 0.0, -1.0);
-        let light = PointLight::new(Color::new(1.0, 1.0, 1.0), Point::new(0.0, 0.0, -10.0));
-        let in_shadow = false;
+        let light = Light::Point(PointLight::new(
+            Color::new(1.0, 1.0, 1.0),
+            Point::new(0.0, 0.0, -10.0),
+        ));
+        let light_intensity = Color::white();
 
         let expected_color_white = Color::white();
         let expected_color_black = Color::black();
@@ -348,7 +467,7 @@ mod material_tests {
                 &position_a,
                 &eye_vector,
                 &normal_vector,
-                in_shadow
+                light_intensity
             )
         );
 
@@ -360,7 +479,28 @@ mod material_tests {
                 &position_b,
                 &eye_vector,
                 &normal_vector,
-                in_shadow
+                light_intensity
+            )
+        );
+    }
+
+    #[test]
+    fn material_ambient_fades_with_point_light_attenuation() {
+        let material = Material::default();
+        let mut light = PointLight::new(Color::new(1.0, 1.0, 1.0), Point::new(0.0, 0.0, -3.0));
+        light.with_attenuation(1.0, 0.0, 1.0);
+        let expected_ambient = Color::new(0.01, 0.01, 0.01);
+
+        // in full shadow only the ambient term is left, a tenth of it reaches 3 units away
+        assert_eq!(
+            expected_ambient,
+            material.phong_reflection(
+                &Light::Point(light),
+                &PrimitiveShape::SphereShape(Sphere::default()),
+                &Point::default(),
+                &Vector3::new(0.0, 0.0, -1.0),
+                &Vector3::new(0.0, 0.0, -1.0),
+                Color::black()
             )
         );
     }
@@ -43,7 +43,7 @@ impl Matrix3 {
     fn cofactor(&self, row: usize, column: usize) -> f64 {
         let minor = self.minor(row, column);
 
-        if (row + column) % 2 == 0 {
+        if (row + column).is_multiple_of(2) {
             minor
         } else {
             -minor
@@ -92,7 +92,7 @@ mod matrix3_tests {
             vec![1.0, -2.0, 3.0],
             vec![4.0, -6.0, 1.0],
         ]);
-        let matrix_b = matrix_a.clone();
+        let matrix_b = matrix_a;
 
         assert_eq!(matrix_a, matrix_b);
     }
@@ -67,7 +67,7 @@ impl Matrix4 {
     fn cofactor(&self, row: usize, column: usize) -> f64 {
         let minor = self.minor(row, column);
 
-        if (row + column) % 2 == 0 {
+        if (row + column).is_multiple_of(2) {
             minor
         } else {
             -minor
@@ -248,7 +248,7 @@ mod matrix4_tests {
             vec![4.0, -6.0, 1.0, 2.0],
             vec![2.0, -4.0, 4.0, -1.0],
         ]);
-        let matrix_b = matrix_a.clone();
+        let matrix_b = matrix_a;
 
         assert_eq!(matrix_a, matrix_b);
     }
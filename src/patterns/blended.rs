@@ -0,0 +1,88 @@
+use crate::color::Color;
+use crate::patterns::Pattern;
+use crate::point::Point;
+
+/// How a `BlendedPattern` puts its two patterns together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    /// Half of each.
+    Average,
+    /// Channel by channel product, the darker pattern wins.
+    Multiply,
+}
+
+/// Two patterns seen through each other, each with its own transformation.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BlendedPattern {
+    pattern_a: Box<Pattern>,
+    pattern_b: Box<Pattern>,
+    mode: BlendMode,
+}
+
+impl BlendedPattern {
+    pub fn new(pattern_a: Pattern, pattern_b: Pattern, mode: BlendMode) -> Self {
+        Self {
+            pattern_a: Box::new(pattern_a),
+            pattern_b: Box::new(pattern_b),
+            mode,
+        }
+    }
+
+    pub fn blended_at(&self, point: &Point) -> Color {
+        let (a, b) = (
+            self.pattern_a.nested_at(point),
+            self.pattern_b.nested_at(point),
+        );
+
+        match self.mode {
+            BlendMode::Average => (a + b) / 2.0,
+            BlendMode::Multiply => a * b,
+        }
+    }
+}
+
+#[cfg(test)]
+mod blended_pattern_tests {
+    use crate::color::Color;
+    use crate::patterns::{BlendMode, BlendedPattern, Pattern};
+    use crate::point::Point;
+    use crate::transformations::{Over, Transformable};
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn blended_pattern_average_crosses_stripes() {
+        let along_x = Pattern::new_stripe(Color::white(), Color::black());
+        let along_z = Pattern::new_stripe(Color::white(), Color::black())
+            .rotate(Over::Y, FRAC_PI_2)
+            .transform();
+        let pattern =
+            Pattern::new_blended(BlendedPattern::new(along_x, along_z, BlendMode::Average));
+
+        assert_eq!(
+            Color::white(),
+            pattern.pattern_at(&Point::new(0.5, 0.0, -0.5))
+        );
+        assert_eq!(
+            Color::new(0.5, 0.5, 0.5),
+            pattern.pattern_at(&Point::new(1.5, 0.0, -0.5))
+        );
+        assert_eq!(
+            Color::black(),
+            pattern.pattern_at(&Point::new(1.5, 0.0, 0.5))
+        );
+    }
+
+    #[test]
+    fn blended_pattern_multiply() {
+        let pattern = Pattern::new_blended(BlendedPattern::new(
+            Pattern::new_plain(Color::new(0.5, 1.0, 0.2)),
+            Pattern::new_plain(Color::new(0.5, 0.5, 1.0)),
+            BlendMode::Multiply,
+        ));
+
+        assert_eq!(
+            Color::new(0.25, 0.5, 0.2),
+            pattern.pattern_at(&Point::new(0.0, 0.0, 0.0))
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use crate::color::Color;
+use crate::patterns::Pattern;
+use crate::point::Point;
+use crate::primitives::PrimitiveShape;
+use crate::vector::Vector3;
+
+/// Step of the finite differences taken along the surface, in world units.
+const BUMP_EPSILON: f64 = 1e-4;
+
+/// How a `Bump` reads its pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BumpKind {
+    /// The brightness of the pattern is a height above the surface, the normal leans away
+    /// from where it rises.
+    Height,
+    /// The color of the pattern is a normal in tangent space, red along `u`, green along `v`
+    /// and blue along the surface normal, each channel going from -1.0 at 0.0 to 1.0 at 1.0.
+    /// The tangents follow the uv mapping of a texture, other patterns get an arbitrary one.
+    NormalMap,
+}
+
+/// Detail added to the shading normal of a material without changing the geometry, the
+/// surface is still hit and offset along its true normal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bump {
+    pattern: Pattern,
+    kind: BumpKind,
+    strength: f64,
+}
+
+impl Bump {
+    pub fn height(pattern: Pattern) -> Self {
+        Self {
+            pattern,
+            kind: BumpKind::Height,
+            strength: 1.0,
+        }
+    }
+
+    pub fn normal_map(pattern: Pattern) -> Self {
+        Self {
+            pattern,
+            kind: BumpKind::NormalMap,
+            strength: 1.0,
+        }
+    }
+
+    /// Scale of the height or of the tangent part of the mapped normals, 0.0 leaves the
+    /// normal as it is.
+    pub fn with_strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    /// `normal` of `primitive` at `point` with the bump applied, both in world space.
+    pub fn perturb(&self, primitive: &PrimitiveShape, point: &Point, normal: &Vector3) -> Vector3 {
+        let (tangent, bitangent) = tangent_basis(normal);
+
+        let perturbed = match self.kind {
+            BumpKind::Height => {
+                let height = |direction: &Vector3| {
+                    let ahead = brightness(
+                        &self
+                            .pattern
+                            .pattern_at_local(primitive, &(*point + *direction * BUMP_EPSILON)),
+                    );
+                    let behind = brightness(
+                        &self
+                            .pattern
+                            .pattern_at_local(primitive, &(*point - *direction * BUMP_EPSILON)),
+                    );
+                    (ahead - behind) / (2.0 * BUMP_EPSILON)
+                };
+                let slope = tangent * height(&tangent) + bitangent * height(&bitangent);
+
+                *normal - slope * self.strength
+            }
+            BumpKind::NormalMap => {
+                let (tangent, bitangent) = self
+                    .uv_tangents(primitive, point, normal, &tangent, &bitangent)
+                    .unwrap_or((tangent, bitangent));
+                let color = self.pattern.pattern_at_local(primitive, point);
+
+                tangent * ((2.0 * color.r - 1.0) * self.strength)
+                    + bitangent * ((2.0 * color.g - 1.0) * self.strength)
+                    + *normal * (2.0 * color.b - 1.0)
+            }
+        };
+
+        if perturbed.near_zero() {
+            *normal
+        } else {
+            perturbed.normalize()
+        }
+    }
+
+    /// Directions along the surface in which the `u` and `v` of the texture grow, `None` when
+    /// the pattern isn't a texture or the mapping is degenerate at `point`.
+    fn uv_tangents(
+        &self,
+        primitive: &PrimitiveShape,
+        point: &Point,
+        normal: &Vector3,
+        tangent: &Vector3,
+        bitangent: &Vector3,
+    ) -> Option<(Vector3, Vector3)> {
+        let uv = self.pattern.uv_at_local(primitive, point)?;
+        let step = |direction: &Vector3| {
+            let (u, v) = self
+                .pattern
+                .uv_at_local(primitive, &(*point + *direction * BUMP_EPSILON))?;
+            // a step across the seam of a mapping comes back from the other side
+            let wrap = |delta: f64| (delta + 0.5).rem_euclid(1.0) - 0.5;
+            Some((wrap(u - uv.0) / BUMP_EPSILON, wrap(v - uv.1) / BUMP_EPSILON))
+        };
+        let ((du_t, dv_t), (du_b, dv_b)) = (step(tangent)?, step(bitangent)?);
+
+        let determinant = du_t * dv_b - du_b * dv_t;
+        if determinant.abs() < 1e-9 {
+            return None;
+        }
+
+        // inverse of the jacobian, how far to go along the surface per unit of u and v
+        let along_u = (*tangent * dv_b - *bitangent * dv_t) / determinant;
+        let along_v = (*bitangent * du_t - *tangent * du_b) / determinant;
+
+        let along_u = (along_u - *normal * along_u.dot(normal)).normalize();
+        let across = normal.cross(&along_u);
+        let along_v = if across.dot(&along_v) < 0.0 {
+            -across
+        } else {
+            across
+        };
+
+        Some((along_u, along_v))
+    }
+}
+
+/// Two directions perpendicular to `normal` and to each other.
+fn tangent_basis(normal: &Vector3) -> (Vector3, Vector3) {
+    let helper = if normal.x.abs() < 0.9 {
+        Vector3::new(1.0, 0.0, 0.0)
+    } else {
+        Vector3::new(0.0, 1.0, 0.0)
+    };
+    let tangent = normal.cross(&helper).cross(normal).normalize();
+    let bitangent = normal.cross(&tangent);
+
+    (tangent, bitangent)
+}
+
+fn brightness(color: &Color) -> f64 {
+    (color.r + color.g + color.b) / 3.0
+}
+
+#[cfg(test)]
+mod bump_tests {
+    use crate::color::Color;
+    use crate::float_eq::ApproxEq;
+    use crate::patterns::{Bump, Pattern, TextureFilter, TextureImage, TexturePattern, UvMapping};
+    use crate::point::Point;
+    use crate::primitives::{Plane, PrimitiveShape};
+    use crate::transformations::Transformable;
+    use crate::vector::Vector3;
+    use std::sync::Arc;
+
+    fn plane() -> PrimitiveShape {
+        PrimitiveShape::PlaneShape(Plane::default())
+    }
+
+    fn up() -> Vector3 {
+        Vector3::new(0.0, 1.0, 0.0)
+    }
+
+    fn texture(color: Color) -> Pattern {
+        Pattern::new_texture(
+            TexturePattern::new(
+                Arc::new(TextureImage::new(1, 1, vec![color])),
+                UvMapping::Planar,
+            )
+            .with_filter(TextureFilter::Nearest),
+        )
+    }
+
+    #[test]
+    fn bump_flat_height_keeps_the_normal() {
+        let bump = Bump::height(Pattern::new_plain(Color::new(0.3, 0.3, 0.3)));
+
+        assert_eq!(
+            up(),
+            bump.perturb(&plane(), &Point::new(0.4, 0.0, 0.2), &up())
+        );
+    }
+
+    #[test]
+    fn bump_height_leans_away_from_the_slope() {
+        // brightness rises by 0.5 per unit along x
+        let pattern = Pattern::new_gradient(Color::black(), Color::white())
+            .scale(2.0, 1.0, 1.0)
+            .transform();
+        let bump = Bump::height(pattern).with_strength(2.0);
+        let expected_normal = Vector3::new(-1.0, 1.0, 0.0).normalize();
+
+        let normal = bump.perturb(&plane(), &Point::new(0.5, 0.0, 0.3), &up());
+
+        assert!(expected_normal.approx_eq_low(&normal));
+    }
+
+    #[test]
+    fn bump_normal_map_in_tangent_space() {
+        let flat = Bump::normal_map(texture(Color::new(0.5, 0.5, 1.0)));
+        let along_u = Bump::normal_map(texture(Color::new(1.0, 0.5, 0.5)));
+        let along_v = Bump::normal_map(texture(Color::new(0.5, 1.0, 0.5)));
+        let point = Point::new(0.3, 0.0, 0.6);
+
+        // the planar mapping puts u along x and v along z
+        assert!(up().approx_eq_low(&flat.perturb(&plane(), &point, &up())));
+        assert!(Vector3::new(1.0, 0.0, 0.0).approx_eq_low(&along_u.perturb(
+            &plane(),
+            &point,
+            &up()
+        )));
+        assert!(Vector3::new(0.0, 0.0, 1.0).approx_eq_low(&along_v.perturb(
+            &plane(),
+            &point,
+            &up()
+        )));
+    }
+
+    #[test]
+    fn bump_without_strength_keeps_the_normal() {
+        let bump = Bump::normal_map(texture(Color::new(1.0, 0.5, 0.5))).with_strength(0.0);
+
+        assert!(up().approx_eq_low(&bump.perturb(&plane(), &Point::new(0.3, 0.0, 0.6), &up())));
+    }
+}
@@ -1,24 +1,28 @@
 use crate::color::Color;
 use crate::float_eq::ApproxEq;
+use crate::patterns::PatternSlot;
 use crate::point::Point;
 
-#[derive(Clone, Debug, PartialEq, Copy)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct CheckerPattern {
-    color_a: Color,
-    color_b: Color,
+    color_a: PatternSlot,
+    color_b: PatternSlot,
 }
 
 impl CheckerPattern {
-    pub fn from(color_a: Color, color_b: Color) -> Self {
-        Self { color_a, color_b }
+    pub fn from(color_a: impl Into<PatternSlot>, color_b: impl Into<PatternSlot>) -> Self {
+        Self {
+            color_a: color_a.into(),
+            color_b: color_b.into(),
+        }
     }
 
     pub(crate) fn checker_at(&self, point: &Point) -> Color {
         let sum = point.x.floor() + point.y.floor() + point.z.floor();
         if (sum % 2.0).approx_eq_low(&0.0) {
-            self.color_a
+            self.color_a.color_at(point)
         } else {
-            self.color_b
+            self.color_b.color_at(point)
         }
     }
 }
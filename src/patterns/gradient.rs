@@ -1,23 +1,23 @@
 use crate::color::Color;
+use crate::patterns::PatternSlot;
 use crate::point::Point;
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct GradientPattern {
-    color_from: Color,
-    color_to: Color,
+    color_from: PatternSlot,
+    color_to: PatternSlot,
 }
 
 impl GradientPattern {
-    pub fn from(color_from: Color, color_to: Color) -> Self {
+    pub fn from(color_from: impl Into<PatternSlot>, color_to: impl Into<PatternSlot>) -> Self {
         Self {
-            color_from,
-            color_to,
+            color_from: color_from.into(),
+            color_to: color_to.into(),
         }
     }
     pub fn gradient_at(&self, point: &Point) -> Color {
         let fraction = point.x - point.x.floor();
-        let distance = self.color_to - self.color_from;
-        self.color_from + (distance * fraction)
+        self.color_from.blend(&self.color_to, point, fraction)
     }
 }
 
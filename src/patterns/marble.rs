@@ -0,0 +1,90 @@
+use crate::color::Color;
+use crate::patterns::noise::DEFAULT_OCTAVES;
+use crate::patterns::PatternSlot;
+use crate::patterns::Perlin;
+use crate::point::Point;
+use std::f64::consts::PI;
+
+/// Bands of `color_a` and `color_b` along x, two units apart, twisted into veins by
+/// turbulence.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MarblePattern {
+    color_a: PatternSlot,
+    color_b: PatternSlot,
+    perlin: Perlin,
+    octaves: usize,
+    strength: f64,
+}
+
+impl MarblePattern {
+    pub fn from(
+        color_a: impl Into<PatternSlot>,
+        color_b: impl Into<PatternSlot>,
+        seed: u64,
+    ) -> Self {
+        Self {
+            color_a: color_a.into(),
+            color_b: color_b.into(),
+            perlin: Perlin::new(seed),
+            octaves: DEFAULT_OCTAVES,
+            strength: 5.0,
+        }
+    }
+
+    pub fn with_octaves(mut self, octaves: usize) -> Self {
+        self.octaves = octaves.max(1);
+        self
+    }
+
+    /// How far the veins are pushed by the turbulence, straight bands with 0.0.
+    pub fn with_strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    pub fn marble_at(&self, point: &Point) -> Color {
+        let turbulence = self.perlin.turbulence(point, self.octaves);
+        let fraction = ((point.x * PI + self.strength * turbulence).sin() + 1.0) / 2.0;
+
+        self.color_a.blend(&self.color_b, point, fraction)
+    }
+}
+
+#[cfg(test)]
+mod marble_pattern_tests {
+    use crate::color::Color;
+    use crate::patterns::{MarblePattern, Pattern};
+    use crate::point::Point;
+
+    #[test]
+    fn marble_pattern_without_strength_is_banded() {
+        let pattern = Pattern::new_marble(
+            MarblePattern::from(Color::black(), Color::white(), 1).with_strength(0.0),
+        );
+
+        assert_eq!(
+            Color::white(),
+            pattern.pattern_at(&Point::new(0.5, 0.3, 0.7))
+        );
+        assert_eq!(
+            Color::new(0.5, 0.5, 0.5),
+            pattern.pattern_at(&Point::new(1.0, 0.3, 0.7))
+        );
+        assert_eq!(
+            Color::black(),
+            pattern.pattern_at(&Point::new(1.5, 4.0, -2.0))
+        );
+    }
+
+    #[test]
+    fn marble_pattern_veins_depend_on_the_seed() {
+        let point = Point::new(0.3, 0.6, 0.9);
+        let marble = |seed| {
+            Pattern::new_marble(MarblePattern::from(Color::black(), Color::white(), seed))
+                .pattern_at(&point)
+        };
+
+        assert_eq!(marble(4), marble(4));
+        assert_ne!(marble(4), marble(5));
+    }
+}
@@ -0,0 +1,236 @@
+use crate::color::Color;
+use crate::patterns::PatternSlot;
+use crate::point::Point;
+use crate::sampling::Rng;
+
+/// Gradients at the lattice points, the middles of the edges of a cube.
+const GRADIENTS: [(f64, f64, f64); 12] = [
+    (1.0, 1.0, 0.0),
+    (-1.0, 1.0, 0.0),
+    (1.0, -1.0, 0.0),
+    (-1.0, -1.0, 0.0),
+    (1.0, 0.0, 1.0),
+    (-1.0, 0.0, 1.0),
+    (1.0, 0.0, -1.0),
+    (-1.0, 0.0, -1.0),
+    (0.0, 1.0, 1.0),
+    (0.0, -1.0, 1.0),
+    (0.0, 1.0, -1.0),
+    (0.0, -1.0, -1.0),
+];
+
+/// Octaves summed by fractal noise unless told otherwise.
+pub const DEFAULT_OCTAVES: usize = 4;
+
+/// Perlin gradient noise. The gradient of every lattice point is hashed from its coordinates
+/// and the seed, so the same seed always gives the same noise and nothing is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Perlin {
+    seed: u64,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// Noise at `point`, between -1.0 and 1.0 and 0.0 on every lattice point.
+    pub fn noise(&self, point: &Point) -> f64 {
+        let (x0, y0, z0) = (point.x.floor(), point.y.floor(), point.z.floor());
+        let (fx, fy, fz) = (point.x - x0, point.y - y0, point.z - z0);
+        let (x0, y0, z0) = (x0 as i64, y0 as i64, z0 as i64);
+
+        let corner = |dx: i64, dy: i64, dz: i64| {
+            let (gx, gy, gz) = self.gradient(x0 + dx, y0 + dy, z0 + dz);
+            gx * (fx - dx as f64) + gy * (fy - dy as f64) + gz * (fz - dz as f64)
+        };
+        let (u, v, w) = (fade(fx), fade(fy), fade(fz));
+
+        let x00 = lerp(u, corner(0, 0, 0), corner(1, 0, 0));
+        let x10 = lerp(u, corner(0, 1, 0), corner(1, 1, 0));
+        let x01 = lerp(u, corner(0, 0, 1), corner(1, 0, 1));
+        let x11 = lerp(u, corner(0, 1, 1), corner(1, 1, 1));
+
+        lerp(w, lerp(v, x00, x10), lerp(v, x01, x11)).clamp(-1.0, 1.0)
+    }
+
+    /// Fractal Brownian motion, `octaves` layers of noise each twice as fine and half as
+    /// strong as the one before, scaled back between -1.0 and 1.0.
+    pub fn fbm(&self, point: &Point, octaves: usize) -> f64 {
+        self.octaves(point, octaves, |noise| noise)
+    }
+
+    /// Like `fbm` but summing the absolute noise, between 0.0 and 1.0 with sharp creases
+    /// where the noise crosses zero.
+    pub fn turbulence(&self, point: &Point, octaves: usize) -> f64 {
+        self.octaves(point, octaves, f64::abs)
+    }
+
+    fn octaves<F: Fn(f64) -> f64>(&self, point: &Point, octaves: usize, layer: F) -> f64 {
+        let (mut total, mut weight, mut amplitude, mut frequency) = (0.0, 0.0, 1.0, 1.0);
+
+        for _ in 0..octaves.max(1) {
+            total += amplitude * layer(self.noise(&(*point * frequency)));
+            weight += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+
+        total / weight
+    }
+
+    fn gradient(&self, x: i64, y: i64, z: i64) -> (f64, f64, f64) {
+        let mut rng = Rng::new(self.seed);
+        for coordinate in [x, y, z] {
+            rng = Rng::new(rng.next_u64() ^ coordinate as u64);
+        }
+
+        GRADIENTS[(rng.next_u64() % GRADIENTS.len() as u64) as usize]
+    }
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+/// Flavor of noise shown by a `NoisePattern`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoiseKind {
+    Perlin,
+    Fbm,
+    Turbulence,
+}
+
+/// Blend from `color_a` to `color_b` following the noise, `color_a` where the noise is lowest.
+#[derive(Debug, PartialEq, Clone)]
+pub struct NoisePattern {
+    color_a: PatternSlot,
+    color_b: PatternSlot,
+    kind: NoiseKind,
+    perlin: Perlin,
+    octaves: usize,
+}
+
+impl NoisePattern {
+    pub fn from(
+        color_a: impl Into<PatternSlot>,
+        color_b: impl Into<PatternSlot>,
+        kind: NoiseKind,
+        seed: u64,
+    ) -> Self {
+        Self {
+            color_a: color_a.into(),
+            color_b: color_b.into(),
+            kind,
+            perlin: Perlin::new(seed),
+            octaves: DEFAULT_OCTAVES,
+        }
+    }
+
+    pub fn with_octaves(mut self, octaves: usize) -> Self {
+        self.octaves = octaves.max(1);
+        self
+    }
+
+    pub fn noise_at(&self, point: &Point) -> Color {
+        let fraction = match self.kind {
+            NoiseKind::Perlin => (self.perlin.noise(point) + 1.0) / 2.0,
+            NoiseKind::Fbm => (self.perlin.fbm(point, self.octaves) + 1.0) / 2.0,
+            NoiseKind::Turbulence => self.perlin.turbulence(point, self.octaves),
+        };
+
+        self.color_a.blend(&self.color_b, point, fraction)
+    }
+}
+
+#[cfg(test)]
+mod noise_tests {
+    use crate::color::Color;
+    use crate::patterns::{NoiseKind, NoisePattern, Pattern, Perlin};
+    use crate::point::Point;
+
+    fn points() -> Vec<Point> {
+        (0..200)
+            .map(|i| {
+                let i = i as f64;
+                Point::new(i * 0.37 - 20.0, i * 0.13 + 0.5, -i * 0.21 + 3.3)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn perlin_zero_on_lattice_points() {
+        let perlin = Perlin::new(7);
+
+        assert_eq!(0.0, perlin.noise(&Point::new(0.0, 0.0, 0.0)));
+        assert_eq!(0.0, perlin.noise(&Point::new(3.0, -2.0, 5.0)));
+    }
+
+    #[test]
+    fn perlin_deterministic_under_a_seed() {
+        let (a, b, c) = (Perlin::new(1), Perlin::new(1), Perlin::new(2));
+        let values = |perlin: &Perlin| {
+            points()
+                .iter()
+                .map(|point| perlin.noise(point))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(values(&a), values(&b));
+        assert_ne!(values(&a), values(&c));
+    }
+
+    #[test]
+    fn perlin_ranges() {
+        let perlin = Perlin::new(42);
+
+        for point in points() {
+            let noise = perlin.noise(&point);
+            let fbm = perlin.fbm(&point, 5);
+            let turbulence = perlin.turbulence(&point, 5);
+
+            assert!((-1.0..=1.0).contains(&noise));
+            assert!((-1.0..=1.0).contains(&fbm));
+            assert!((0.0..=1.0).contains(&turbulence));
+        }
+        assert!(points().iter().any(|point| perlin.noise(point).abs() > 0.1));
+    }
+
+    #[test]
+    fn perlin_is_continuous() {
+        let perlin = Perlin::new(3);
+        let point = Point::new(1.5, 2.25, -0.75);
+        let near = Point::new(1.5001, 2.25, -0.75);
+
+        assert!((perlin.noise(&point) - perlin.noise(&near)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn noise_pattern_blends_colors() {
+        let pattern = Pattern::new_noise(NoisePattern::from(
+            Color::black(),
+            Color::white(),
+            NoiseKind::Perlin,
+            9,
+        ));
+        let expected_color = Color::new(0.5, 0.5, 0.5);
+
+        // noise is zero on lattice points, halfway between the colors
+        assert_eq!(
+            expected_color,
+            pattern.pattern_at(&Point::new(1.0, 2.0, 3.0))
+        );
+        assert_eq!(
+            Color::black(),
+            Pattern::new_noise(
+                NoisePattern::from(Color::black(), Color::white(), NoiseKind::Turbulence, 9)
+                    .with_octaves(1)
+            )
+            .pattern_at(&Point::new(1.0, 2.0, 3.0))
+        );
+    }
+}
@@ -1,11 +1,14 @@
 use crate::color::Color;
 use crate::matrices::{Matrix, Matrix4};
-use crate::patterns::{CheckerPattern, GradientPattern, PlainPattern, RingPattern, StripePattern};
+use crate::patterns::{
+    BlendedPattern, CheckerPattern, GradientPattern, MarblePattern, NoisePattern, PerturbedPattern,
+    PlainPattern, RadialGradientPattern, RingPattern, StripePattern, TexturePattern, WoodPattern,
+};
 use crate::point::Point;
 use crate::primitives::{Primitive, PrimitiveShape};
 use crate::transformations::Transformable;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Pattern {
     pub pattern: PatternType,
     transformation: Matrix4,
@@ -13,34 +16,51 @@ pub struct Pattern {
 }
 
 impl Pattern {
-    pub fn new_stripe(color_a: Color, color_b: Color) -> Self {
+    pub fn new_stripe(color_a: impl Into<PatternSlot>, color_b: impl Into<PatternSlot>) -> Self {
         Self {
             pattern: PatternType::Stripe(StripePattern::from(color_a, color_b)),
             ..Default::default()
         }
     }
 
-    pub fn new_gradient(color_a: Color, color_b: Color) -> Self {
+    pub fn new_gradient(color_a: impl Into<PatternSlot>, color_b: impl Into<PatternSlot>) -> Self {
         Self {
             pattern: PatternType::Gradient(GradientPattern::from(color_a, color_b)),
             ..Default::default()
         }
     }
 
-    pub fn new_ring(color_a: Color, color_b: Color) -> Self {
+    pub fn new_ring(color_a: impl Into<PatternSlot>, color_b: impl Into<PatternSlot>) -> Self {
         Self {
             pattern: PatternType::Ring(RingPattern::from(color_a, color_b)),
             ..Default::default()
         }
     }
 
-    pub fn new_checker(color_a: Color, color_b: Color) -> Self {
+    pub fn new_checker(color_a: impl Into<PatternSlot>, color_b: impl Into<PatternSlot>) -> Self {
         Self {
             pattern: PatternType::Checker(CheckerPattern::from(color_a, color_b)),
             ..Default::default()
         }
     }
 
+    pub fn new_radial_gradient(
+        color_a: impl Into<PatternSlot>,
+        color_b: impl Into<PatternSlot>,
+    ) -> Self {
+        Self {
+            pattern: PatternType::RadialGradient(RadialGradientPattern::from(color_a, color_b)),
+            ..Default::default()
+        }
+    }
+
+    pub fn new_blended(blended: BlendedPattern) -> Self {
+        Self {
+            pattern: PatternType::Blended(blended),
+            ..Default::default()
+        }
+    }
+
     pub fn new_plain(color: Color) -> Self {
         Self {
             pattern: PatternType::Plain(PlainPattern::from(color)),
@@ -48,6 +68,41 @@ impl Pattern {
         }
     }
 
+    pub fn new_texture(texture: TexturePattern) -> Self {
+        Self {
+            pattern: PatternType::Texture(texture),
+            ..Default::default()
+        }
+    }
+
+    pub fn new_noise(noise: NoisePattern) -> Self {
+        Self {
+            pattern: PatternType::Noise(noise),
+            ..Default::default()
+        }
+    }
+
+    pub fn new_marble(marble: MarblePattern) -> Self {
+        Self {
+            pattern: PatternType::Marble(marble),
+            ..Default::default()
+        }
+    }
+
+    pub fn new_wood(wood: WoodPattern) -> Self {
+        Self {
+            pattern: PatternType::Wood(wood),
+            ..Default::default()
+        }
+    }
+
+    pub fn new_perturbed(perturbed: PerturbedPattern) -> Self {
+        Self {
+            pattern: PatternType::Perturbed(perturbed),
+            ..Default::default()
+        }
+    }
+
     pub fn new_test() -> Self {
         Self {
             pattern: PatternType::Test(TestPattern {}),
@@ -55,23 +110,41 @@ impl Pattern {
         }
     }
     pub(crate) fn pattern_at(&self, point: &Point) -> Color {
+        self.pattern.pattern_at(point)
+    }
+
+    /// Color at `point` given in the space of the pattern holding this one, this pattern's
+    /// own transformation still applies.
+    pub(crate) fn nested_at(&self, point: &Point) -> Color {
+        self.pattern_at(&(self.transformation_inverse * *point))
+    }
+
+    /// Color at `world_point` on `primitive`. The point goes to object space, then to the
+    /// space of this pattern, and every nested pattern takes it further into its own space
+    /// from there.
+    pub fn pattern_at_local(&self, primitive: &PrimitiveShape, world_point: &Point) -> Color {
+        self.pattern_at(&self.pattern_point(primitive, world_point))
+    }
+
+    /// Texture coordinates of `world_point` on `primitive`, `None` unless this is a texture.
+    pub(crate) fn uv_at_local(
+        &self,
+        primitive: &PrimitiveShape,
+        world_point: &Point,
+    ) -> Option<(f64, f64)> {
         match &self.pattern {
-            PatternType::Plain(plain) => plain.plain_at(point),
-            PatternType::Stripe(stripe) => stripe.stripe_at(point),
-            PatternType::Gradient(gradient) => gradient.gradient_at(point),
-            PatternType::Ring(ring) => ring.ring_at(point),
-            PatternType::Checker(checker) => checker.checker_at(point),
-            PatternType::Test(pattern) => pattern.test_at(point),
+            PatternType::Texture(texture) => {
+                Some(texture.uv(&self.pattern_point(primitive, world_point)))
+            }
+            _ => None,
         }
     }
 
-    pub fn pattern_at_local(&self, primitive: &PrimitiveShape, world_point: &Point) -> Color {
+    fn pattern_point(&self, primitive: &PrimitiveShape, world_point: &Point) -> Point {
         let primitive_transformation_inv = primitive.transformation_invert();
         let primitive_point = *primitive_transformation_inv * *world_point;
 
-        let pattern_point = self.transformation_inverse * primitive_point;
-
-        self.pattern_at(&pattern_point)
+        self.transformation_inverse * primitive_point
     }
 }
 
@@ -96,13 +169,20 @@ impl Transformable for Pattern {
     }
 }
 
-#[derive(Debug, Copy, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum PatternType {
     Plain(PlainPattern),
     Stripe(StripePattern),
     Gradient(GradientPattern),
     Ring(RingPattern),
     Checker(CheckerPattern),
+    Texture(TexturePattern),
+    Noise(NoisePattern),
+    Marble(MarblePattern),
+    Wood(WoodPattern),
+    Perturbed(PerturbedPattern),
+    RadialGradient(RadialGradientPattern),
+    Blended(BlendedPattern),
     Test(TestPattern),
 }
 
@@ -114,10 +194,55 @@ impl PatternType {
             PatternType::Gradient(pattern) => pattern.gradient_at(point),
             PatternType::Ring(pattern) => pattern.ring_at(point),
             PatternType::Checker(pattern) => pattern.checker_at(point),
+            PatternType::Texture(pattern) => pattern.texture_at(point),
+            PatternType::Noise(pattern) => pattern.noise_at(point),
+            PatternType::Marble(pattern) => pattern.marble_at(point),
+            PatternType::Wood(pattern) => pattern.wood_at(point),
+            PatternType::Perturbed(pattern) => pattern.perturbed_at(point),
+            PatternType::RadialGradient(pattern) => pattern.radial_gradient_at(point),
+            PatternType::Blended(pattern) => pattern.blended_at(point),
             PatternType::Test(pattern) => pattern.test_at(point),
         }
     }
 }
+
+/// What fills one of the colors of a pattern, a plain color or another pattern with its own
+/// transformation, relative to the pattern holding it.
+#[derive(Debug, PartialEq, Clone)]
+pub enum PatternSlot {
+    Color(Color),
+    Pattern(Box<Pattern>),
+}
+
+impl PatternSlot {
+    /// Color at `point`, in the space of the pattern holding the slot.
+    pub fn color_at(&self, point: &Point) -> Color {
+        match self {
+            PatternSlot::Color(color) => *color,
+            PatternSlot::Pattern(pattern) => pattern.nested_at(point),
+        }
+    }
+
+    /// From this slot to `other` at `point`, all of this one with 0.0 and all of `other`
+    /// with 1.0.
+    pub fn blend(&self, other: &PatternSlot, point: &Point, fraction: f64) -> Color {
+        let from = self.color_at(point);
+        from + (other.color_at(point) - from) * fraction
+    }
+}
+
+impl From<Color> for PatternSlot {
+    fn from(color: Color) -> Self {
+        PatternSlot::Color(color)
+    }
+}
+
+impl From<Pattern> for PatternSlot {
+    fn from(pattern: Pattern) -> Self {
+        PatternSlot::Pattern(Box::new(pattern))
+    }
+}
+
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub struct TestPattern {}
 
@@ -129,10 +254,15 @@ impl TestPattern {
 
 #[cfg(test)]
 mod pattern_tests {
+    use crate::color::Color;
+    use crate::material::Material;
     use crate::matrices::{Matrix, Matrix4};
     use crate::patterns::pattern::Pattern;
     use crate::patterns::PatternType::Plain;
     use crate::patterns::PlainPattern;
+    use crate::point::Point;
+    use crate::primitives::{PrimitiveShape, Sphere};
+    use crate::transformations::Transformable;
 
     #[test]
     fn pattern_creation() {
@@ -145,4 +275,34 @@ mod pattern_tests {
 
         assert_eq!(expected_pattern, pattern);
     }
+
+    #[test]
+    fn pattern_nested_transformations_compose() {
+        // world point / 2 on the sphere, / 2 again in the checker, * 2 in the stripes
+        let stripes = Pattern::new_stripe(Color::white(), Color::black())
+            .scale(0.5, 1.0, 1.0)
+            .transform();
+        let pattern = Pattern::new_checker(stripes, Color::red())
+            .scale(2.0, 2.0, 2.0)
+            .transform();
+        let sphere = PrimitiveShape::SphereShape(
+            Sphere::default()
+                .scale(2.0, 2.0, 2.0)
+                .transform()
+                .apply_material(Material::default().apply_pattern(pattern.clone())),
+        );
+
+        assert_eq!(
+            Color::white(),
+            pattern.pattern_at_local(&sphere, &Point::new(1.0, 0.1, 0.1))
+        );
+        assert_eq!(
+            Color::black(),
+            pattern.pattern_at_local(&sphere, &Point::new(3.0, 0.1, 0.1))
+        );
+        assert_eq!(
+            Color::red(),
+            pattern.pattern_at_local(&sphere, &Point::new(5.0, 0.1, 0.1))
+        );
+    }
 }
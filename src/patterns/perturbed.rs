@@ -0,0 +1,82 @@
+use crate::color::Color;
+use crate::patterns::{Pattern, Perlin};
+use crate::point::Point;
+
+/// Another pattern read at points jittered by noise, which makes its straight edges ragged.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PerturbedPattern {
+    pattern: Box<Pattern>,
+    perlin: Perlin,
+    scale: f64,
+}
+
+impl PerturbedPattern {
+    pub fn new(pattern: Pattern, seed: u64) -> Self {
+        Self {
+            pattern: Box::new(pattern),
+            perlin: Perlin::new(seed),
+            scale: 0.2,
+        }
+    }
+
+    /// How far a point may be moved along each axis.
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn perturbed_at(&self, point: &Point) -> Color {
+        // the axes read the noise far apart from each other so they move independently
+        let jitter = |offset: f64| {
+            let shifted = Point::new(point.x + offset, point.y + offset, point.z + offset);
+            self.scale * self.perlin.noise(&shifted)
+        };
+        let jittered = Point::new(
+            point.x + jitter(0.0),
+            point.y + jitter(31.7),
+            point.z + jitter(-57.3),
+        );
+
+        self.pattern.nested_at(&jittered)
+    }
+}
+
+#[cfg(test)]
+mod perturbed_pattern_tests {
+    use crate::color::Color;
+    use crate::patterns::{Pattern, PerturbedPattern};
+    use crate::point::Point;
+    use crate::transformations::Transformable;
+
+    #[test]
+    fn perturbed_pattern_keeps_lattice_points() {
+        // stripes only change along x, which is not moved on lattice points where the noise
+        // is zero, and points away from an edge by more than the scale keep their color
+        let stripes = Pattern::new_stripe(Color::white(), Color::black())
+            .scale(0.5, 1.0, 1.0)
+            .transform();
+        let pattern = Pattern::new_perturbed(PerturbedPattern::new(stripes, 3));
+
+        assert_eq!(
+            Color::white(),
+            pattern.pattern_at(&Point::new(2.0, 1.0, 0.0))
+        );
+        assert_eq!(
+            Color::black(),
+            pattern.pattern_at(&Point::new(2.75, 1.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn perturbed_pattern_moves_edges() {
+        let stripes = Pattern::new_stripe(Color::white(), Color::black());
+        let still =
+            Pattern::new_perturbed(PerturbedPattern::new(stripes.clone(), 3).with_scale(0.0));
+        let ragged = Pattern::new_perturbed(PerturbedPattern::new(stripes, 3).with_scale(0.5));
+        let points = (0..50).map(|i| Point::new(0.98 + i as f64 * 0.001, i as f64 * 0.37, 0.3));
+
+        assert!(points
+            .into_iter()
+            .any(|point| still.pattern_at(&point) != ragged.pattern_at(&point)));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::color::Color;
+use crate::patterns::PatternSlot;
+use crate::point::Point;
+
+/// Gradient from `color_from` to `color_to` going out from the y axis, starting over every
+/// unit like the rings of a `RingPattern`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RadialGradientPattern {
+    color_from: PatternSlot,
+    color_to: PatternSlot,
+}
+
+impl RadialGradientPattern {
+    pub fn from(color_from: impl Into<PatternSlot>, color_to: impl Into<PatternSlot>) -> Self {
+        Self {
+            color_from: color_from.into(),
+            color_to: color_to.into(),
+        }
+    }
+
+    pub fn radial_gradient_at(&self, point: &Point) -> Color {
+        let distance = (point.x.powi(2) + point.z.powi(2)).sqrt();
+        self.color_from
+            .blend(&self.color_to, point, distance - distance.floor())
+    }
+}
+
+#[cfg(test)]
+mod radial_gradient_pattern_tests {
+    use crate::color::Color;
+    use crate::patterns::Pattern;
+    use crate::point::Point;
+
+    #[test]
+    fn radial_gradient_grows_from_the_y_axis() {
+        let pattern = Pattern::new_radial_gradient(Color::white(), Color::black());
+
+        assert_eq!(
+            Color::white(),
+            pattern.pattern_at(&Point::new(0.0, 3.0, 0.0))
+        );
+        assert_eq!(
+            Color::new(0.75, 0.75, 0.75),
+            pattern.pattern_at(&Point::new(0.0, 0.0, 0.25))
+        );
+        assert_eq!(
+            Color::new(0.5, 0.5, 0.5),
+            pattern.pattern_at(&Point::new(0.9, 0.0, 1.2))
+        );
+    }
+}
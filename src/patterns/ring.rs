@@ -1,23 +1,27 @@
 use crate::color::Color;
+use crate::patterns::PatternSlot;
 use crate::point::Point;
 
-#[derive(Clone, Debug, PartialEq, Copy)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct RingPattern {
-    color_a: Color,
-    color_b: Color,
+    color_a: PatternSlot,
+    color_b: PatternSlot,
 }
 
 impl RingPattern {
-    pub fn from(color_a: Color, color_b: Color) -> Self {
-        Self { color_a, color_b }
+    pub fn from(color_a: impl Into<PatternSlot>, color_b: impl Into<PatternSlot>) -> Self {
+        Self {
+            color_a: color_a.into(),
+            color_b: color_b.into(),
+        }
     }
     pub fn ring_at(&self, point: &Point) -> Color {
         let distance = (point.x.powf(2.0) + point.z.powf(2.0)).sqrt();
 
         if distance.floor() as i64 % 2 == 0 {
-            self.color_a
+            self.color_a.color_at(point)
         } else {
-            self.color_b
+            self.color_b.color_at(point)
         }
     }
 }
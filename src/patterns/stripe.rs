@@ -1,22 +1,26 @@
 use crate::color::Color;
+use crate::patterns::PatternSlot;
 use crate::point::Point;
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct StripePattern {
-    color_a: Color,
-    color_b: Color,
+    color_a: PatternSlot,
+    color_b: PatternSlot,
 }
 
 impl StripePattern {
-    pub fn from(color_a: Color, color_b: Color) -> Self {
-        Self { color_a, color_b }
+    pub fn from(color_a: impl Into<PatternSlot>, color_b: impl Into<PatternSlot>) -> Self {
+        Self {
+            color_a: color_a.into(),
+            color_b: color_b.into(),
+        }
     }
 
     pub fn stripe_at(&self, point: &Point) -> Color {
         if point.x.floor() as i32 % 2 == 0 {
-            self.color_a
+            self.color_a.color_at(point)
         } else {
-            self.color_b
+            self.color_b.color_at(point)
         }
     }
 }
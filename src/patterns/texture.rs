@@ -1,4 +1,251 @@
-// TODO: https://en.wikipedia.org/wiki/UV_mapping
+use crate::color::Color;
+use crate::patterns::UvMapping;
+use crate::point::Point;
+use image::ImageResult;
+use std::path::Path;
+use std::sync::Arc;
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct TexturePattern {}
+/// How a texture is read between the centers of its pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureFilter {
+    /// Color of the closest pixel.
+    Nearest,
+    /// Blend of the four closest pixels.
+    Bilinear,
+}
+
+/// What a texture does outside of its pixels, both along `u` and `v`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureWrap {
+    /// The texture tiles, past its right edge comes its left one.
+    Repeat,
+    /// The pixels along the edges stretch on.
+    Clamp,
+}
+
+/// Pixels of an image, row after row from the top. Components go from 0.0 to 1.0, read as
+/// they are stored like the canvas writes them, except for HDR images which go beyond 1.0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl TextureImage {
+    pub fn new(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        assert_eq!(width * height, pixels.len());
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Load a PNG, JPEG, HDR or any other format the `image` crate reads.
+    pub fn load<P: AsRef<Path>>(path: P) -> ImageResult<Self> {
+        let image = image::open(path)?.to_rgb32f();
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let pixels = image
+            .pixels()
+            .map(|pixel| {
+                let [r, g, b] = pixel.0;
+                Color::new(r as f64, g as f64, b as f64)
+            })
+            .collect();
+
+        Ok(Self::new(width, height, pixels))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn pixel(&self, x: usize, y: usize) -> Color {
+        self.pixels[y * self.width + x]
+    }
+}
+
+/// Image flattened onto primitives through a `UvMapping`. The image is shared, every
+/// material using the same file points to the same pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TexturePattern {
+    image: Arc<TextureImage>,
+    mapping: UvMapping,
+    filter: TextureFilter,
+    wrap: TextureWrap,
+}
+
+impl TexturePattern {
+    pub fn new(image: Arc<TextureImage>, mapping: UvMapping) -> Self {
+        Self {
+            image,
+            mapping,
+            filter: TextureFilter::Bilinear,
+            wrap: TextureWrap::Repeat,
+        }
+    }
+
+    pub fn with_filter(mut self, filter: TextureFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_wrap(mut self, wrap: TextureWrap) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn texture_at(&self, point: &Point) -> Color {
+        let (u, v) = self.uv(point);
+        self.uv_at(u, v)
+    }
+
+    /// Texture coordinates of `point` through the mapping.
+    pub fn uv(&self, point: &Point) -> (f64, f64) {
+        self.mapping.uv(point)
+    }
+
+    /// Color at `u`, `v`, the bottom left corner of the image being at 0.0, 0.0.
+    pub fn uv_at(&self, u: f64, v: f64) -> Color {
+        let (width, height) = (self.image.width, self.image.height);
+        if width == 0 || height == 0 {
+            return Color::black();
+        }
+
+        // pixel coordinates, rows are stored from the top
+        let x = u * width as f64;
+        let y = (1.0 - v) * height as f64;
+
+        match self.filter {
+            TextureFilter::Nearest => self.texel(x.floor() as i64, y.floor() as i64),
+            TextureFilter::Bilinear => {
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (tx, ty) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as i64, y0 as i64);
+
+                let top = self.texel(x0, y0) * (1.0 - tx) + self.texel(x0 + 1, y0) * tx;
+                let bottom = self.texel(x0, y0 + 1) * (1.0 - tx) + self.texel(x0 + 1, y0 + 1) * tx;
+
+                top * (1.0 - ty) + bottom * ty
+            }
+        }
+    }
+
+    /// Pixel at column `x` and row `y`, which may be outside the image.
+    fn texel(&self, x: i64, y: i64) -> Color {
+        let (width, height) = (self.image.width as i64, self.image.height as i64);
+        let (x, y) = match self.wrap {
+            TextureWrap::Repeat => (x.rem_euclid(width), y.rem_euclid(height)),
+            TextureWrap::Clamp => (x.clamp(0, width - 1), y.clamp(0, height - 1)),
+        };
+
+        self.image.pixel(x as usize, y as usize)
+    }
+}
+
+#[cfg(test)]
+mod texture_pattern_tests {
+    use crate::color::Color;
+    use crate::material::Material;
+    use crate::patterns::{
+        Pattern, TextureFilter, TextureImage, TexturePattern, TextureWrap, UvMapping,
+    };
+    use crate::point::Point;
+    use crate::primitives::{PrimitiveShape, Sphere};
+    use crate::transformations::Transformable;
+    use std::sync::Arc;
+
+    /// Black and white pixels on top, red and green below.
+    fn image() -> Arc<TextureImage> {
+        Arc::new(TextureImage::new(
+            2,
+            2,
+            vec![
+                Color::black(),
+                Color::white(),
+                Color::new(1.0, 0.0, 0.0),
+                Color::new(0.0, 1.0, 0.0),
+            ],
+        ))
+    }
+
+    #[test]
+    fn texture_nearest() {
+        let texture =
+            TexturePattern::new(image(), UvMapping::Planar).with_filter(TextureFilter::Nearest);
+
+        assert_eq!(Color::black(), texture.uv_at(0.25, 0.75));
+        assert_eq!(Color::white(), texture.uv_at(0.75, 0.75));
+        assert_eq!(Color::new(1.0, 0.0, 0.0), texture.uv_at(0.25, 0.25));
+        assert_eq!(Color::new(0.0, 1.0, 0.0), texture.uv_at(0.99, 0.01));
+    }
+
+    #[test]
+    fn texture_bilinear() {
+        let texture = TexturePattern::new(image(), UvMapping::Planar).with_wrap(TextureWrap::Clamp);
+        let expected_center = Color::new(0.5, 0.5, 0.25);
+        let expected_top = Color::new(0.5, 0.5, 0.5);
+
+        assert_eq!(Color::black(), texture.uv_at(0.25, 0.75));
+        assert_eq!(expected_center, texture.uv_at(0.5, 0.5));
+        assert_eq!(expected_top, texture.uv_at(0.5, 1.0));
+    }
+
+    #[test]
+    fn texture_wrap_and_clamp() {
+        let repeat =
+            TexturePattern::new(image(), UvMapping::Planar).with_filter(TextureFilter::Nearest);
+        let clamp = repeat.clone().with_wrap(TextureWrap::Clamp);
+
+        assert_eq!(Color::black(), repeat.uv_at(1.25, 1.75));
+        assert_eq!(Color::white(), clamp.uv_at(1.25, 1.75));
+        assert_eq!(Color::new(0.0, 1.0, 0.0), repeat.uv_at(-0.25, 0.25));
+        assert_eq!(Color::new(1.0, 0.0, 0.0), clamp.uv_at(-0.25, 0.25));
+
+        // between the right and left columns a repeating texture blends both
+        let bilinear = repeat.with_filter(TextureFilter::Bilinear);
+        assert_eq!(Color::new(0.5, 0.5, 0.5), bilinear.uv_at(1.0, 0.75));
+    }
+
+    #[test]
+    fn texture_load_image() {
+        let image = TextureImage::load("assets/scene.jpg").unwrap();
+
+        assert!(image.width() > 0);
+        assert!(image.height() > 0);
+        assert!(TextureImage::load("assets/missing.png").is_err());
+    }
+
+    #[test]
+    fn texture_on_sphere() {
+        let pattern = Pattern::new_texture(
+            TexturePattern::new(image(), UvMapping::Spherical).with_filter(TextureFilter::Nearest),
+        );
+        let sphere_default = Sphere::default()
+            .scale(2.0, 2.0, 2.0)
+            .transform()
+            .apply_material(Material::default().apply_pattern(pattern.clone()));
+        let sphere = PrimitiveShape::SphereShape(sphere_default);
+
+        // the north pole is on the top row, around the south pole is the bottom one
+        assert_eq!(
+            Color::white(),
+            pattern.pattern_at_local(&sphere, &Point::new(0.0, 2.0, 0.0))
+        );
+        assert_eq!(
+            Color::new(0.0, 1.0, 0.0),
+            pattern.pattern_at_local(&sphere, &Point::new(0.0, -1.8, 0.2))
+        );
+        assert_eq!(
+            Color::new(1.0, 0.0, 0.0),
+            pattern.pattern_at_local(&sphere, &Point::new(2.0, -1.0, 0.0))
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::point::Point;
+use crate::vector::Vector3;
+use std::f64::consts::PI;
+
+/// How the points of a primitive are flattened onto the `u`, `v` coordinates of a texture.
+/// Both go from 0.0 to 1.0 over the texture, `v` upwards. Points are in pattern space, the
+/// mappings fit the default shapes: the unit sphere, the plane at y = 0, the unit cylinder
+/// along y and the cube between -1 and 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UvMapping {
+    /// Longitude along `u` and latitude along `v`.
+    Spherical,
+    /// The xz plane tiled by the texture, one unit per repetition.
+    Planar,
+    /// Around the y axis along `u`, the height along `v` repeating every unit.
+    Cylindrical,
+    /// The six faces laid out as a cross, four side faces in the middle row with the top
+    /// face above and the bottom face below the front.
+    Cube,
+}
+
+impl UvMapping {
+    pub fn uv(&self, point: &Point) -> (f64, f64) {
+        match self {
+            UvMapping::Spherical => {
+                let radius = Vector3::from(*point).magnitude();
+                let theta = point.x.atan2(point.z);
+                let phi = if radius > 0.0 {
+                    (point.y / radius).clamp(-1.0, 1.0).acos()
+                } else {
+                    0.0
+                };
+
+                (1.0 - (theta / (2.0 * PI) + 0.5), 1.0 - phi / PI)
+            }
+            UvMapping::Planar => (point.x.rem_euclid(1.0), point.z.rem_euclid(1.0)),
+            UvMapping::Cylindrical => {
+                let theta = point.x.atan2(point.z);
+
+                (1.0 - (theta / (2.0 * PI) + 0.5), point.y.rem_euclid(1.0))
+            }
+            UvMapping::Cube => {
+                let ((u, v), (column, row)) = cube_face_uv(point);
+
+                ((column + u) / 4.0, (row + v) / 3.0)
+            }
+        }
+    }
+}
+
+/// `u`, `v` on the face of the cube `point` is on, with the column and row of that face in
+/// the cross layout, counted from the bottom left.
+fn cube_face_uv(point: &Point) -> ((f64, f64), (f64, f64)) {
+    let (x, y, z) = (point.x, point.y, point.z);
+    let along = |a: f64| ((a + 1.0) / 2.0).clamp(0.0, 1.0);
+    let coordinate = x.abs().max(y.abs()).max(z.abs());
+
+    if coordinate == x {
+        // right
+        ((along(-z), along(y)), (2.0, 1.0))
+    } else if coordinate == -x {
+        // left
+        ((along(z), along(y)), (0.0, 1.0))
+    } else if coordinate == y {
+        // up
+        ((along(x), along(-z)), (1.0, 2.0))
+    } else if coordinate == -y {
+        // down
+        ((along(x), along(z)), (1.0, 0.0))
+    } else if coordinate == z {
+        // front
+        ((along(x), along(y)), (1.0, 1.0))
+    } else {
+        // back
+        ((along(-x), along(y)), (3.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod uv_mapping_tests {
+    use crate::patterns::UvMapping;
+    use crate::point::Point;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    fn assert_uv(expected: (f64, f64), actual: (f64, f64)) {
+        assert!(
+            (expected.0 - actual.0).abs() < 1e-6 && (expected.1 - actual.1).abs() < 1e-6,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn uv_mapping_spherical() {
+        let cases = [
+            (Point::new(0.0, 0.0, -1.0), (0.0, 0.5)),
+            (Point::new(1.0, 0.0, 0.0), (0.25, 0.5)),
+            (Point::new(0.0, 0.0, 1.0), (0.5, 0.5)),
+            (Point::new(-1.0, 0.0, 0.0), (0.75, 0.5)),
+            (Point::new(0.0, 1.0, 0.0), (0.5, 1.0)),
+            (Point::new(0.0, -1.0, 0.0), (0.5, 0.0)),
+            (Point::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0), (0.25, 0.75)),
+        ];
+
+        for (point, expected_uv) in cases {
+            assert_uv(expected_uv, UvMapping::Spherical.uv(&point));
+        }
+    }
+
+    #[test]
+    fn uv_mapping_planar() {
+        let cases = [
+            (Point::new(0.25, 0.0, 0.5), (0.25, 0.5)),
+            (Point::new(0.25, 0.0, -0.25), (0.25, 0.75)),
+            (Point::new(0.25, 0.5, -0.25), (0.25, 0.75)),
+            (Point::new(1.25, 0.0, 0.5), (0.25, 0.5)),
+            (Point::new(-0.25, 0.0, -1.75), (0.75, 0.25)),
+        ];
+
+        for (point, expected_uv) in cases {
+            assert_uv(expected_uv, UvMapping::Planar.uv(&point));
+        }
+    }
+
+    #[test]
+    fn uv_mapping_cylindrical() {
+        let cases = [
+            (Point::new(0.0, 0.0, -1.0), (0.0, 0.0)),
+            (Point::new(0.0, 0.5, -1.0), (0.0, 0.5)),
+            (Point::new(0.0, 1.0, -1.0), (0.0, 0.0)),
+            (Point::new(FRAC_1_SQRT_2, 0.5, -FRAC_1_SQRT_2), (0.125, 0.5)),
+            (Point::new(1.0, 0.5, 0.0), (0.25, 0.5)),
+            (Point::new(-1.0, 1.25, 0.0), (0.75, 0.25)),
+        ];
+
+        for (point, expected_uv) in cases {
+            assert_uv(expected_uv, UvMapping::Cylindrical.uv(&point));
+        }
+    }
+
+    #[test]
+    fn uv_mapping_cube_cross_layout() {
+        let cases = [
+            // center of the front, right, back, left, top and bottom faces
+            (Point::new(0.0, 0.0, 1.0), (0.375, 0.5)),
+            (Point::new(1.0, 0.0, 0.0), (0.625, 0.5)),
+            (Point::new(0.0, 0.0, -1.0), (0.875, 0.5)),
+            (Point::new(-1.0, 0.0, 0.0), (0.125, 0.5)),
+            (Point::new(0.0, 1.0, 0.0), (0.375, 2.5 / 3.0)),
+            (Point::new(0.0, -1.0, 0.0), (0.375, 0.5 / 3.0)),
+            (Point::new(-0.5, 0.5, 1.0), (1.25 / 4.0, 1.75 / 3.0)),
+        ];
+
+        for (point, expected_uv) in cases {
+            assert_uv(expected_uv, UvMapping::Cube.uv(&point));
+        }
+    }
+}
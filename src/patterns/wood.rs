@@ -0,0 +1,77 @@
+use crate::color::Color;
+use crate::patterns::PatternSlot;
+use crate::patterns::Perlin;
+use crate::point::Point;
+
+/// Rings around the y axis, one unit apart, going from `color_a` to `color_b` from the inside
+/// of each ring out and wobbled by noise.
+#[derive(Debug, PartialEq, Clone)]
+pub struct WoodPattern {
+    color_a: PatternSlot,
+    color_b: PatternSlot,
+    perlin: Perlin,
+    strength: f64,
+}
+
+impl WoodPattern {
+    pub fn from(
+        color_a: impl Into<PatternSlot>,
+        color_b: impl Into<PatternSlot>,
+        seed: u64,
+    ) -> Self {
+        Self {
+            color_a: color_a.into(),
+            color_b: color_b.into(),
+            perlin: Perlin::new(seed),
+            strength: 0.5,
+        }
+    }
+
+    /// How far the rings are pushed by the noise, perfect circles with 0.0.
+    pub fn with_strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    pub fn wood_at(&self, point: &Point) -> Color {
+        let distance = (point.x.powi(2) + point.z.powi(2)).sqrt();
+        let fraction = (distance + self.strength * self.perlin.noise(point)).rem_euclid(1.0);
+
+        self.color_a.blend(&self.color_b, point, fraction)
+    }
+}
+
+#[cfg(test)]
+mod wood_pattern_tests {
+    use crate::color::Color;
+    use crate::patterns::{Pattern, WoodPattern};
+    use crate::point::Point;
+
+    #[test]
+    fn wood_pattern_rings_around_y() {
+        let pattern = Pattern::new_wood(
+            WoodPattern::from(Color::black(), Color::white(), 1).with_strength(0.0),
+        );
+        let expected_color = Color::new(0.25, 0.25, 0.25);
+
+        assert_eq!(
+            expected_color,
+            pattern.pattern_at(&Point::new(1.25, 0.0, 0.0))
+        );
+        assert_eq!(
+            expected_color,
+            pattern.pattern_at(&Point::new(0.0, 7.0, -2.25))
+        );
+    }
+
+    #[test]
+    fn wood_pattern_rings_wobble() {
+        let straight = WoodPattern::from(Color::black(), Color::white(), 1).with_strength(0.0);
+        let wobbly = straight.clone().with_strength(0.5);
+        let points = (1..20).map(|i| Point::new(i as f64 * 0.31, i as f64 * 0.17, 0.4));
+
+        assert!(points
+            .into_iter()
+            .any(|point| straight.wood_at(&point) != wobbly.wood_at(&point)));
+    }
+}
@@ -53,7 +53,7 @@ impl Point {
 
 impl PartialEq for Point {
     fn eq(&self, other: &Self) -> bool {
-        self.approx_eq(&other)
+        self.approx_eq(other)
     }
 }
 
@@ -174,9 +174,9 @@ impl Sub<Vector3> for Point {
     }
 }
 
-impl Into<Vector3> for Point {
-    fn into(self) -> Vector3 {
-        Vector3::new(self.x, self.y, self.z)
+impl From<Point> for Vector3 {
+    fn from(val: Point) -> Self {
+        Vector3::new(val.x, val.y, val.z)
     }
 }
 
@@ -1,35 +1,72 @@
-use std::env::Args;
-
+/// Quality presets selected on the command line with `-fast` or `-slow`.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Preset {
-    Fast { samples_per_pixel: i32, depth: u32 },
-    Slow { samples_per_pixel: i32, depth: u32 },
+    Fast {
+        samples_per_pixel: usize,
+        depth: usize,
+    },
+    Slow {
+        samples_per_pixel: usize,
+        depth: usize,
+    },
 }
 
-pub fn parse_preset(args: Args) -> Option<Preset> {
-    let mut preset = None;
+impl Preset {
+    pub fn from_flag(flag: &str) -> Option<Preset> {
+        match flag {
+            "-fast" => Some(Preset::Fast {
+                samples_per_pixel: 10,
+                depth: 10,
+            }),
+            "-slow" => Some(Preset::Slow {
+                samples_per_pixel: 100,
+                depth: 50,
+            }),
+            _ => None,
+        }
+    }
 
-    for arg in args.into_iter() {
-        match arg.as_str() {
-            "-fast" => {
-                preset = Some(Preset::Fast {
-                    samples_per_pixel: 10,
-                    depth: 10,
-                })
-            }
-            "-slow" => {
-                preset = Some(Preset::Slow {
-                    samples_per_pixel: 100,
-                    depth: 50,
-                })
-            }
-            _ => {
-                preset = Some(Preset::Fast {
-                    samples_per_pixel: 10,
-                    depth: 10,
-                })
+    pub fn samples_per_pixel(&self) -> usize {
+        match *self {
+            Preset::Fast {
+                samples_per_pixel, ..
             }
+            | Preset::Slow {
+                samples_per_pixel, ..
+            } => samples_per_pixel,
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        match *self {
+            Preset::Fast { depth, .. } | Preset::Slow { depth, .. } => depth,
+        }
+    }
+}
+
+#[cfg(test)]
+mod preset_tests {
+    use crate::preset::Preset;
+
+    #[test]
+    fn preset_from_flag() {
+        let expected_fast = Preset::Fast {
+            samples_per_pixel: 10,
+            depth: 10,
         };
+        let expected_slow = Preset::Slow {
+            samples_per_pixel: 100,
+            depth: 50,
+        };
+
+        assert_eq!(Some(expected_fast), Preset::from_flag("-fast"));
+        assert_eq!(Some(expected_slow), Preset::from_flag("-slow"));
+        assert_eq!(None, Preset::from_flag("-medium"));
     }
 
-    preset
+    #[test]
+    fn preset_depth() {
+        assert_eq!(10, Preset::from_flag("-fast").unwrap().depth());
+        assert_eq!(50, Preset::from_flag("-slow").unwrap().depth());
+    }
 }
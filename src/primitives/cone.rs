@@ -0,0 +1,289 @@
+use crate::acceleration::BoundingBox;
+use crate::float_eq::{EPSILON, LOW_EPSILON};
+use crate::intersections::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrices::{Matrix, Matrix4};
+use crate::point::Point;
+use crate::primitives::Primitive;
+use crate::primitives::PrimitiveShape::ConeShape;
+use crate::ray::Ray;
+use crate::transformations::Transformable;
+use crate::vector::Vector3;
+
+/// Double cone around the y axis with its tip in the origin, cut between `minimum` and
+/// `maximum`. The radius at any height equals the distance from the tip.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cone {
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+    pub transformation: Matrix4,
+    pub transformation_inverse: Matrix4,
+    pub transformation_inverse_transpose: Matrix4,
+    pub material: Material,
+}
+
+impl Cone {
+    pub fn new(material: Material) -> Self {
+        Self {
+            material,
+            ..Self::default()
+        }
+    }
+
+    pub fn apply_material(mut self, material: Material) -> Self {
+        self.material = material;
+        self
+    }
+
+    pub fn apply_limits(mut self, minimum: f64, maximum: f64) -> Self {
+        self.minimum = minimum;
+        self.maximum = maximum;
+        self
+    }
+
+    pub fn apply_closed(mut self, closed: bool) -> Self {
+        self.closed = closed;
+        self
+    }
+
+    /// True when the ray at `time` is inside the cap of the given radius.
+    fn check_cap(ray: &Ray, time: f64, radius: f64) -> bool {
+        let x = ray.origin.x + time * ray.direction.x;
+        let z = ray.origin.z + time * ray.direction.z;
+
+        x.powi(2) + z.powi(2) <= radius.powi(2)
+    }
+
+    fn intersect_caps(&self, ray: &Ray, intersections: &mut Vec<Intersection>) {
+        if !self.closed || ray.direction.y.abs() < EPSILON {
+            return;
+        }
+
+        for limit in [self.minimum, self.maximum] {
+            let time = (limit - ray.origin.y) / ray.direction.y;
+            if Cone::check_cap(ray, time, limit.abs()) {
+                intersections.push(Intersection::new(time, ConeShape(self.clone())));
+            }
+        }
+    }
+}
+
+impl Primitive for Cone {
+    fn intersect(&self, ray: &Ray) -> Intersections {
+        let mut intersections = vec![];
+        let (origin, direction) = (ray.origin, ray.direction);
+
+        let a = direction.x.powi(2) - direction.y.powi(2) + direction.z.powi(2);
+        let b = 2.0 * origin.x * direction.x - 2.0 * origin.y * direction.y
+            + 2.0 * origin.z * direction.z;
+        let c = origin.x.powi(2) - origin.y.powi(2) + origin.z.powi(2);
+
+        if a.abs() < EPSILON {
+            // ray parallel to one of the halves, it crosses the other one once
+            if b.abs() >= EPSILON {
+                let time = -c / (2.0 * b);
+                let y = origin.y + time * direction.y;
+                if self.minimum < y && y < self.maximum {
+                    intersections.push(Intersection::new(time, ConeShape(self.clone())));
+                }
+            }
+        } else {
+            let discriminant = b.powi(2) - 4.0 * a * c;
+
+            if discriminant < 0.0 {
+                return Intersections::new();
+            }
+
+            let t0 = (-b - discriminant.sqrt()) / (2.0 * a);
+            let t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+
+            for time in [t0.min(t1), t0.max(t1)] {
+                let y = origin.y + time * direction.y;
+                if self.minimum < y && y < self.maximum {
+                    intersections.push(Intersection::new(time, ConeShape(self.clone())));
+                }
+            }
+        }
+
+        self.intersect_caps(ray, &mut intersections);
+
+        Intersections::new().with(intersections)
+    }
+
+    fn normal(&self, local: &Point) -> Vector3 {
+        let distance = local.x.powi(2) + local.z.powi(2);
+
+        if distance < self.maximum.powi(2) && local.y >= self.maximum - LOW_EPSILON {
+            Vector3::new(0.0, 1.0, 0.0)
+        } else if distance < self.minimum.powi(2) && local.y <= self.minimum + LOW_EPSILON {
+            Vector3::new(0.0, -1.0, 0.0)
+        } else {
+            let y = distance.sqrt();
+            let y = if local.y > 0.0 { -y } else { y };
+
+            Vector3::new(local.x, y, local.z)
+        }
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn transformation(&self) -> &Matrix4 {
+        &self.transformation
+    }
+
+    fn transformation_invert(&self) -> &Matrix4 {
+        &self.transformation_inverse
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        let radius = self.minimum.abs().max(self.maximum.abs());
+
+        BoundingBox::new(
+            Point::new(-radius, self.minimum, -radius),
+            Point::new(radius, self.maximum, radius),
+        )
+    }
+}
+
+impl Transformable for Cone {
+    fn transform(self, transformation: &Matrix4) -> Self {
+        let delta = *transformation * self.transformation;
+        let mut delta_inverse = delta.invert();
+        Self {
+            transformation: delta,
+            transformation_inverse: delta_inverse,
+            transformation_inverse_transpose: delta_inverse.transpose(),
+            ..self
+        }
+    }
+}
+
+impl Default for Cone {
+    fn default() -> Self {
+        Self {
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+            material: Material::default(),
+            transformation: Matrix4::identity(),
+            transformation_inverse: Matrix4::identity(),
+            transformation_inverse_transpose: Matrix4::identity(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod cone_tests {
+    use crate::acceleration::BoundingBox;
+    use crate::point::Point;
+    use crate::primitives::{Cone, Primitive};
+    use crate::ray::Ray;
+    use crate::vector::Vector3;
+
+    #[test]
+    fn cone_ray_hit() {
+        let cone = Cone::default();
+        let cases = [
+            (
+                Point::new(0.0, 0.0, -5.0),
+                Vector3::new(0.0, 0.0, 1.0),
+                5.0,
+                5.0,
+            ),
+            (
+                Point::new(0.0, 0.0, -5.0),
+                Vector3::new(1.0, 1.0, 1.0),
+                8.66025,
+                8.66025,
+            ),
+            (
+                Point::new(1.0, 1.0, -5.0),
+                Vector3::new(-0.5, -1.0, 1.0),
+                4.55006,
+                49.44994,
+            ),
+        ];
+
+        for (origin, direction, expected_t0, expected_t1) in cases.iter() {
+            let ray = Ray::new(*origin, direction.normalize());
+            let intersections = cone.intersect(&ray);
+
+            assert_eq!(2, intersections.intersections.len());
+            assert!((expected_t0 - intersections[0].time).abs() < 1e-4);
+            assert!((expected_t1 - intersections[1].time).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn cone_ray_parallel_to_half() {
+        let cone = Cone::default();
+        let ray = Ray::new(
+            Point::new(0.0, 0.0, -1.0),
+            Vector3::new(0.0, 1.0, 1.0).normalize(),
+        );
+
+        let intersections = cone.intersect(&ray);
+
+        assert_eq!(1, intersections.intersections.len());
+        assert!((0.35355 - intersections[0].time).abs() < 1e-4);
+    }
+
+    #[test]
+    fn cone_closed_intersection() {
+        let cone = Cone::default().apply_limits(-0.5, 0.5).apply_closed(true);
+        let cases = [
+            (Point::new(0.0, 0.0, -5.0), Vector3::new(0.0, 1.0, 0.0), 0),
+            (Point::new(0.0, 0.0, -0.25), Vector3::new(0.0, 1.0, 1.0), 2),
+            (Point::new(0.0, 0.0, -0.25), Vector3::new(0.0, 1.0, 0.0), 4),
+        ];
+
+        for (origin, direction, expected_count) in cases.iter() {
+            let ray = Ray::new(*origin, direction.normalize());
+
+            assert_eq!(*expected_count, cone.intersect(&ray).intersections.len());
+        }
+    }
+
+    #[test]
+    fn cone_normal() {
+        let cone = Cone::default();
+        let cases = [
+            (Point::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.0)),
+            (
+                Point::new(1.0, 1.0, 1.0),
+                Vector3::new(1.0, -f64::sqrt(2.0), 1.0),
+            ),
+            (Point::new(-1.0, -1.0, 0.0), Vector3::new(-1.0, 1.0, 0.0)),
+        ];
+
+        for (point, expected_normal) in cases.iter() {
+            assert_eq!(*expected_normal, cone.normal(point));
+        }
+    }
+
+    #[test]
+    fn cone_cap_normal() {
+        let cone = Cone::default().apply_limits(-1.0, 2.0).apply_closed(true);
+
+        assert_eq!(
+            Vector3::new(0.0, 1.0, 0.0),
+            cone.normal(&Point::new(0.5, 2.0, 0.5))
+        );
+        assert_eq!(
+            Vector3::new(0.0, -1.0, 0.0),
+            cone.normal(&Point::new(0.5, -1.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn cone_bounds() {
+        let cone = Cone::default().apply_limits(-5.0, 3.0);
+        let expected_bounds =
+            BoundingBox::new(Point::new(-5.0, -5.0, -5.0), Point::new(5.0, 3.0, 5.0));
+
+        assert_eq!(expected_bounds, cone.bounds());
+    }
+}
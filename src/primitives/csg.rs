@@ -0,0 +1,358 @@
+use crate::acceleration::BoundingBox;
+use crate::intersections::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrices::{Matrix, Matrix4};
+use crate::point::Point;
+use crate::primitives::{Primitive, PrimitiveShape};
+use crate::ray::Ray;
+use crate::transformations::Transformable;
+use crate::vector::Vector3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl CsgOperation {
+    /// Whether a hit on one side survives, given which side was hit and whether the ray is
+    /// inside the left and right shape at that point.
+    pub fn allows(&self, left_hit: bool, inside_left: bool, inside_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => (left_hit && !inside_right) || (!left_hit && !inside_left),
+            CsgOperation::Intersection => (left_hit && inside_right) || (!left_hit && inside_left),
+            CsgOperation::Difference => (left_hit && !inside_right) || (!left_hit && inside_left),
+        }
+    }
+}
+
+/// Solid made of two shapes. Like a group, the transformation of the solid is composed into
+/// both children, and the intersections kept point at the child that was hit so the usual
+/// n1/n2 bookkeeping sees every surface it passes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Csg {
+    pub operation: CsgOperation,
+    pub left: Box<PrimitiveShape>,
+    pub right: Box<PrimitiveShape>,
+    pub transformation: Matrix4,
+    pub transformation_inverse: Matrix4,
+    pub transformation_inverse_transpose: Matrix4,
+    bounds: BoundingBox,
+    // never shaded, only there so `material` has something to lend
+    material: Material,
+}
+
+impl Csg {
+    pub fn new(operation: CsgOperation, left: PrimitiveShape, right: PrimitiveShape) -> Self {
+        let bounds = Csg::bounds_of(operation, &left, &right);
+
+        Self {
+            operation,
+            left: Box::new(left),
+            right: Box::new(right),
+            transformation: Matrix4::identity(),
+            transformation_inverse: Matrix4::identity(),
+            transformation_inverse_transpose: Matrix4::identity(),
+            bounds,
+            material: Material::default(),
+        }
+    }
+
+    pub fn union(left: PrimitiveShape, right: PrimitiveShape) -> Self {
+        Self::new(CsgOperation::Union, left, right)
+    }
+
+    pub fn intersection(left: PrimitiveShape, right: PrimitiveShape) -> Self {
+        Self::new(CsgOperation::Intersection, left, right)
+    }
+
+    pub fn difference(left: PrimitiveShape, right: PrimitiveShape) -> Self {
+        Self::new(CsgOperation::Difference, left, right)
+    }
+
+    fn bounds_of(
+        operation: CsgOperation,
+        left: &PrimitiveShape,
+        right: &PrimitiveShape,
+    ) -> BoundingBox {
+        match operation {
+            CsgOperation::Union => left.bounds().merge(&right.bounds()),
+            // nothing outside the left shape survives
+            CsgOperation::Intersection | CsgOperation::Difference => left.bounds(),
+        }
+    }
+
+    /// Keep the intersections allowed by the operation, walking the hits on the `left` and
+    /// `right` shape in order. Both must be sorted. The side of a hit is the list it comes
+    /// from, its object can be a moved copy or look just like a shape on the other side.
+    pub fn filter(&self, left: Intersections, right: Intersections) -> Intersections {
+        let mut left = left.intersections.into_iter().peekable();
+        let mut right = right.intersections.into_iter().peekable();
+        let mut inside_left = false;
+        let mut inside_right = false;
+        let mut kept: Vec<Intersection> = vec![];
+
+        loop {
+            let left_hit = match (left.peek(), right.peek()) {
+                (Some(left_next), Some(right_next)) => left_next <= right_next,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            let intersection = if left_hit { left.next() } else { right.next() }.unwrap();
+
+            if self.operation.allows(left_hit, inside_left, inside_right) {
+                kept.push(intersection);
+            }
+
+            if left_hit {
+                inside_left = !inside_left;
+            } else {
+                inside_right = !inside_right;
+            }
+        }
+
+        Intersections::new().with(kept)
+    }
+}
+
+impl Primitive for Csg {
+    fn intersect(&self, ray: &Ray) -> Intersections {
+        if !self
+            .bounds
+            .intersects(ray, f64::NEG_INFINITY, f64::INFINITY)
+        {
+            return Intersections::new();
+        }
+
+        self.filter(
+            self.left.intersect(ray).sort(),
+            self.right.intersect(ray).sort(),
+        )
+    }
+
+    /// Never hit itself, intersections point at the children.
+    fn normal(&self, _world: &Point) -> Vector3 {
+        Vector3::default()
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn transformation(&self) -> &Matrix4 {
+        &self.transformation
+    }
+
+    fn transformation_invert(&self) -> &Matrix4 {
+        &self.transformation_inverse
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+}
+
+impl Transformable for Csg {
+    fn transform(self, transformation: &Matrix4) -> Self {
+        let delta = *transformation * self.transformation;
+        let mut delta_inverse = delta.invert();
+        let left = self.left.transform(transformation);
+        let right = self.right.transform(transformation);
+
+        Self {
+            bounds: Csg::bounds_of(self.operation, &left, &right),
+            left: Box::new(left),
+            right: Box::new(right),
+            transformation: delta,
+            transformation_inverse: delta_inverse,
+            transformation_inverse_transpose: delta_inverse.transpose(),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod csg_tests {
+    use crate::intersections::{Intersection, IntersectionDetails, Intersections};
+    use crate::material::Material;
+    use crate::matrices::{Matrix, Matrix4};
+    use crate::point::Point;
+    use crate::primitives::PrimitiveShape::{CsgShape, CubeShape, MovingShape, SphereShape};
+    use crate::primitives::{Csg, CsgOperation, Cube, Moving, Primitive, Sphere};
+    use crate::ray::Ray;
+    use crate::transformations::{Transform, Transformable};
+    use crate::vector::Vector3;
+
+    #[test]
+    fn csg_operation_rules() {
+        let cases = [
+            (
+                CsgOperation::Union,
+                [false, true, false, true, false, false, true, true],
+            ),
+            (
+                CsgOperation::Intersection,
+                [true, false, true, false, true, true, false, false],
+            ),
+            (
+                CsgOperation::Difference,
+                [false, true, false, true, true, true, false, false],
+            ),
+        ];
+
+        for (operation, expected) in cases.iter() {
+            let mut index = 0;
+            for left_hit in [true, false] {
+                for inside_left in [true, false] {
+                    for inside_right in [true, false] {
+                        assert_eq!(
+                            expected[index],
+                            operation.allows(left_hit, inside_left, inside_right),
+                            "{:?} {} {} {}",
+                            operation,
+                            left_hit,
+                            inside_left,
+                            inside_right
+                        );
+                        index += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn csg_filter_intersections() {
+        let sphere = SphereShape(Sphere::default());
+        let cube = CubeShape(Cube::default());
+        let cases = [
+            (CsgOperation::Union, 0, 3),
+            (CsgOperation::Intersection, 1, 2),
+            (CsgOperation::Difference, 0, 1),
+        ];
+
+        for (operation, first, second) in cases.iter() {
+            let csg = Csg::new(*operation, sphere.clone(), cube.clone());
+            let intersections = [
+                Intersection::new(1.0, sphere.clone()),
+                Intersection::new(2.0, cube.clone()),
+                Intersection::new(3.0, sphere.clone()),
+                Intersection::new(4.0, cube.clone()),
+            ];
+            let expected_intersections = Intersections::new().with(vec![
+                intersections[*first].clone(),
+                intersections[*second].clone(),
+            ]);
+            let left = vec![intersections[0].clone(), intersections[2].clone()];
+            let right = vec![intersections[1].clone(), intersections[3].clone()];
+
+            let filtered = csg.filter(
+                Intersections::new().with(left),
+                Intersections::new().with(right),
+            );
+
+            assert_eq!(expected_intersections, filtered);
+        }
+    }
+
+    #[test]
+    fn csg_ray_miss() {
+        let csg = Csg::union(SphereShape(Sphere::default()), CubeShape(Cube::default()));
+        let ray = Ray::new(Point::new(0.0, 2.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert!(csg.intersect(&ray).is_empty());
+    }
+
+    #[test]
+    fn csg_ray_hit() {
+        let sphere_a = SphereShape(Sphere::default());
+        let sphere_b = SphereShape(Sphere::default().translate(0.0, 0.0, 0.5).transform());
+        let csg = Csg::union(sphere_a.clone(), sphere_b.clone());
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        let intersections = csg.intersect(&ray);
+
+        assert_eq!(2, intersections.intersections.len());
+        assert_eq!(4.0, intersections[0].time);
+        assert_eq!(sphere_a, intersections[0].object);
+        assert_eq!(6.5, intersections[1].time);
+        assert_eq!(sphere_b, intersections[1].object);
+    }
+
+    #[test]
+    fn csg_moving_child() {
+        let moving = MovingShape(Moving::new(
+            SphereShape(Sphere::default()),
+            Matrix4::identity(),
+            Transform::Translate(0.0, 3.0, 0.0).transformation(),
+        ));
+        let hole = SphereShape(Sphere::default().scale(0.5, 0.5, 0.5).transform());
+        let csg = Csg::difference(moving, hole);
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        let intersections = csg.intersect(&ray);
+        let times = intersections
+            .intersections
+            .iter()
+            .map(|intersection| intersection.time)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![4.0, 4.5, 5.5, 6.0], times);
+    }
+
+    #[test]
+    fn csg_identical_children() {
+        let sphere = SphereShape(Sphere::default());
+        let csg = Csg::union(sphere.clone(), sphere);
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        let intersections = csg.intersect(&ray);
+
+        assert_eq!(2, intersections.intersections.len());
+        assert_eq!(4.0, intersections[0].time);
+        assert_eq!(6.0, intersections[1].time);
+    }
+
+    #[test]
+    fn csg_transformation_applies_to_children() {
+        let csg = CsgShape(
+            Csg::difference(
+                CubeShape(Cube::default()),
+                SphereShape(Sphere::default().scale(1.2, 1.2, 1.2).transform()),
+            )
+            .translate(0.0, 0.0, 5.0)
+            .transform(),
+        );
+        let ray = Ray::new(Point::new(0.9, 0.9, 0.0), Vector3::new(0.0, 0.0, 1.0));
+
+        let intersections = csg.intersect(&ray);
+
+        assert_eq!(2, intersections.intersections.len());
+        assert_eq!(4.0, intersections[0].time);
+        assert_eq!(6.0, intersections[1].time);
+        assert!(csg
+            .intersect(&Ray::new(Point::default(), Vector3::new(0.0, 0.0, 1.0)))
+            .is_empty());
+    }
+
+    #[test]
+    fn csg_refraction_through_difference() {
+        let glass = Material::default().transparency(1.0).refractive_index(1.5);
+        let cube = CubeShape(Cube::default().apply_material(glass));
+        let sphere = SphereShape(Sphere::default().scale(0.5, 0.5, 0.5).transform());
+        let csg = Csg::difference(cube, sphere);
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+        let intersections = csg.intersect(&ray);
+        let expected_indices = [(1.0, 1.5), (1.5, 1.0), (1.0, 1.5), (1.5, 1.0)];
+
+        assert_eq!(4, intersections.intersections.len());
+        for (index, (n1, n2)) in expected_indices.iter().enumerate() {
+            let details =
+                IntersectionDetails::from_many(&intersections[index], &intersections, &ray);
+
+            assert_eq!((*n1, *n2), (details.n1, details.n2));
+        }
+    }
+}
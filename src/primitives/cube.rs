@@ -1,3 +1,4 @@
+use crate::acceleration::BoundingBox;
 use crate::float_eq::ApproxEq;
 use crate::intersections::{Intersection, Intersections};
 use crate::material::Material;
@@ -9,7 +10,7 @@ use crate::ray::Ray;
 use crate::transformations::Transformable;
 use crate::vector::Vector3;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Cube {
     pub transformation: Matrix4,
     pub transformation_inverse: Matrix4,
@@ -78,17 +79,17 @@ impl Primitive for Cube {
             .reduce(f64::max)
             .unwrap_or_default();
 
-        return if maxc.approx_eq_low(&world.x.abs()) {
+        if maxc.approx_eq_low(&world.x.abs()) {
             Vector3::new(world.x, 0.0, 0.0)
         } else if maxc.approx_eq_low(&world.y.abs()) {
             Vector3::new(0.0, world.y, 0.0)
         } else {
             Vector3::new(0.0, 0.0, world.z)
-        };
+        }
     }
 
-    fn material(&self) -> Material {
-        self.material.clone()
+    fn material(&self) -> &Material {
+        &self.material
     }
 
     fn transformation(&self) -> &Matrix4 {
@@ -98,6 +99,10 @@ impl Primitive for Cube {
     fn transformation_invert(&self) -> &Matrix4 {
         &self.transformation_inverse
     }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0))
+    }
 }
 
 impl Transformable for Cube {
@@ -126,6 +131,7 @@ impl Default for Cube {
 
 #[cfg(test)]
 mod cube_tests {
+    use crate::acceleration::BoundingBox;
     use crate::intersections::Intersections;
     use crate::material::Material;
     use crate::matrices::{Matrix, Matrix4};
@@ -322,4 +328,13 @@ mod cube_tests {
 
         assert_eq!(expected_vector, vector);
     }
+
+    #[test]
+    fn cube_bounds() {
+        let cube = Cube::default();
+        let expected_bounds =
+            BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0));
+
+        assert_eq!(expected_bounds, cube.bounds());
+    }
 }
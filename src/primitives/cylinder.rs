@@ -0,0 +1,315 @@
+use crate::acceleration::BoundingBox;
+use crate::float_eq::{EPSILON, LOW_EPSILON};
+use crate::intersections::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrices::{Matrix, Matrix4};
+use crate::point::Point;
+use crate::primitives::Primitive;
+use crate::primitives::PrimitiveShape::CylinderShape;
+use crate::ray::Ray;
+use crate::transformations::Transformable;
+use crate::vector::Vector3;
+
+/// Cylinder of radius one around the y axis, cut between `minimum` and `maximum`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cylinder {
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+    pub transformation: Matrix4,
+    pub transformation_inverse: Matrix4,
+    pub transformation_inverse_transpose: Matrix4,
+    pub material: Material,
+}
+
+impl Cylinder {
+    pub fn new(material: Material) -> Self {
+        Self {
+            material,
+            ..Self::default()
+        }
+    }
+
+    pub fn apply_material(mut self, material: Material) -> Self {
+        self.material = material;
+        self
+    }
+
+    pub fn apply_limits(mut self, minimum: f64, maximum: f64) -> Self {
+        self.minimum = minimum;
+        self.maximum = maximum;
+        self
+    }
+
+    pub fn apply_closed(mut self, closed: bool) -> Self {
+        self.closed = closed;
+        self
+    }
+
+    /// True when the ray at `time` is inside the unit circle of a cap.
+    fn check_cap(ray: &Ray, time: f64) -> bool {
+        let x = ray.origin.x + time * ray.direction.x;
+        let z = ray.origin.z + time * ray.direction.z;
+
+        x.powi(2) + z.powi(2) <= 1.0
+    }
+
+    fn intersect_caps(&self, ray: &Ray, intersections: &mut Vec<Intersection>) {
+        if !self.closed || ray.direction.y.abs() < EPSILON {
+            return;
+        }
+
+        for limit in [self.minimum, self.maximum] {
+            let time = (limit - ray.origin.y) / ray.direction.y;
+            if Cylinder::check_cap(ray, time) {
+                intersections.push(Intersection::new(time, CylinderShape(self.clone())));
+            }
+        }
+    }
+}
+
+impl Primitive for Cylinder {
+    fn intersect(&self, ray: &Ray) -> Intersections {
+        let mut intersections = vec![];
+        let a = ray.direction.x.powi(2) + ray.direction.z.powi(2);
+
+        if a.abs() >= EPSILON {
+            let b = 2.0 * ray.origin.x * ray.direction.x + 2.0 * ray.origin.z * ray.direction.z;
+            let c = ray.origin.x.powi(2) + ray.origin.z.powi(2) - 1.0;
+            let discriminant = b.powi(2) - 4.0 * a * c;
+
+            if discriminant < 0.0 {
+                return Intersections::new();
+            }
+
+            let t0 = (-b - discriminant.sqrt()) / (2.0 * a);
+            let t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+
+            for time in [t0.min(t1), t0.max(t1)] {
+                let y = ray.origin.y + time * ray.direction.y;
+                if self.minimum < y && y < self.maximum {
+                    intersections.push(Intersection::new(time, CylinderShape(self.clone())));
+                }
+            }
+        }
+
+        self.intersect_caps(ray, &mut intersections);
+
+        Intersections::new().with(intersections)
+    }
+
+    fn normal(&self, local: &Point) -> Vector3 {
+        let distance = local.x.powi(2) + local.z.powi(2);
+
+        if distance < 1.0 && local.y >= self.maximum - LOW_EPSILON {
+            Vector3::new(0.0, 1.0, 0.0)
+        } else if distance < 1.0 && local.y <= self.minimum + LOW_EPSILON {
+            Vector3::new(0.0, -1.0, 0.0)
+        } else {
+            Vector3::new(local.x, 0.0, local.z)
+        }
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn transformation(&self) -> &Matrix4 {
+        &self.transformation
+    }
+
+    fn transformation_invert(&self) -> &Matrix4 {
+        &self.transformation_inverse
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(
+            Point::new(-1.0, self.minimum, -1.0),
+            Point::new(1.0, self.maximum, 1.0),
+        )
+    }
+}
+
+impl Transformable for Cylinder {
+    fn transform(self, transformation: &Matrix4) -> Self {
+        let delta = *transformation * self.transformation;
+        let mut delta_inverse = delta.invert();
+        Self {
+            transformation: delta,
+            transformation_inverse: delta_inverse,
+            transformation_inverse_transpose: delta_inverse.transpose(),
+            ..self
+        }
+    }
+}
+
+impl Default for Cylinder {
+    fn default() -> Self {
+        Self {
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+            material: Material::default(),
+            transformation: Matrix4::identity(),
+            transformation_inverse: Matrix4::identity(),
+            transformation_inverse_transpose: Matrix4::identity(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod cylinder_tests {
+    use crate::acceleration::BoundingBox;
+    use crate::point::Point;
+    use crate::primitives::{Cylinder, Primitive};
+    use crate::ray::Ray;
+    use crate::vector::Vector3;
+
+    #[test]
+    fn cylinder_creation() {
+        let cylinder = Cylinder::default();
+
+        assert_eq!(f64::NEG_INFINITY, cylinder.minimum);
+        assert_eq!(f64::INFINITY, cylinder.maximum);
+        assert!(!cylinder.closed);
+    }
+
+    #[test]
+    fn cylinder_ray_miss() {
+        let cylinder = Cylinder::default();
+        let rays = [
+            Ray::new(Point::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+            Ray::new(Point::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+            Ray::new(
+                Point::new(0.0, 0.0, -5.0),
+                Vector3::new(1.0, 1.0, 1.0).normalize(),
+            ),
+        ];
+
+        for ray in rays.iter() {
+            assert!(cylinder.intersect(ray).is_empty());
+        }
+    }
+
+    #[test]
+    fn cylinder_ray_hit() {
+        let cylinder = Cylinder::default();
+        let cases = [
+            (
+                Point::new(1.0, 0.0, -5.0),
+                Vector3::new(0.0, 0.0, 1.0),
+                5.0,
+                5.0,
+            ),
+            (
+                Point::new(0.0, 0.0, -5.0),
+                Vector3::new(0.0, 0.0, 1.0),
+                4.0,
+                6.0,
+            ),
+            (
+                Point::new(0.5, 0.0, -5.0),
+                Vector3::new(0.1, 1.0, 1.0).normalize(),
+                6.80798,
+                7.08872,
+            ),
+        ];
+
+        for (origin, direction, expected_t0, expected_t1) in cases.iter() {
+            let intersections = cylinder.intersect(&Ray::new(*origin, *direction));
+
+            assert_eq!(2, intersections.intersections.len());
+            assert!((expected_t0 - intersections[0].time).abs() < 1e-4);
+            assert!((expected_t1 - intersections[1].time).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn cylinder_normal() {
+        let cylinder = Cylinder::default();
+        let cases = [
+            (Point::new(1.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0)),
+            (Point::new(0.0, 5.0, -1.0), Vector3::new(0.0, 0.0, -1.0)),
+            (Point::new(0.0, -2.0, 1.0), Vector3::new(0.0, 0.0, 1.0)),
+            (Point::new(-1.0, 1.0, 0.0), Vector3::new(-1.0, 0.0, 0.0)),
+        ];
+
+        for (point, expected_normal) in cases.iter() {
+            assert_eq!(*expected_normal, cylinder.normal(point));
+        }
+    }
+
+    #[test]
+    fn cylinder_truncated_intersection() {
+        let cylinder = Cylinder::default().apply_limits(1.0, 2.0);
+        let cases = [
+            (Point::new(0.0, 1.5, 0.0), Vector3::new(0.1, 1.0, 0.0), 0),
+            (Point::new(0.0, 3.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 0),
+            (Point::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 0),
+            (Point::new(0.0, 2.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 0),
+            (Point::new(0.0, 1.0, -5.0), Vector3::new(0.0, 0.0, 1.0), 0),
+            (Point::new(0.0, 1.5, -2.0), Vector3::new(0.0, 0.0, 1.0), 2),
+        ];
+
+        for (origin, direction, expected_count) in cases.iter() {
+            let ray = Ray::new(*origin, direction.normalize());
+
+            assert_eq!(
+                *expected_count,
+                cylinder.intersect(&ray).intersections.len()
+            );
+        }
+    }
+
+    #[test]
+    fn cylinder_closed_intersection() {
+        let cylinder = Cylinder::default()
+            .apply_limits(1.0, 2.0)
+            .apply_closed(true);
+        let cases = [
+            (Point::new(0.0, 3.0, 0.0), Vector3::new(0.0, -1.0, 0.0), 2),
+            (Point::new(0.0, 3.0, -2.0), Vector3::new(0.0, -1.0, 2.0), 2),
+            (Point::new(0.0, 4.0, -2.0), Vector3::new(0.0, -1.0, 1.0), 2),
+            (Point::new(0.0, 0.0, -2.0), Vector3::new(0.0, 1.0, 2.0), 2),
+            (Point::new(0.0, -1.0, -2.0), Vector3::new(0.0, 1.0, 1.0), 2),
+        ];
+
+        for (origin, direction, expected_count) in cases.iter() {
+            let ray = Ray::new(*origin, direction.normalize());
+
+            assert_eq!(
+                *expected_count,
+                cylinder.intersect(&ray).intersections.len()
+            );
+        }
+    }
+
+    #[test]
+    fn cylinder_cap_normal() {
+        let cylinder = Cylinder::default()
+            .apply_limits(1.0, 2.0)
+            .apply_closed(true);
+        let cases = [
+            (Point::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+            (Point::new(0.5, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+            (Point::new(0.0, 1.0, 0.5), Vector3::new(0.0, -1.0, 0.0)),
+            (Point::new(0.0, 2.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+            (Point::new(0.5, 2.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+            (Point::new(0.0, 2.0, 0.5), Vector3::new(0.0, 1.0, 0.0)),
+        ];
+
+        for (point, expected_normal) in cases.iter() {
+            assert_eq!(*expected_normal, cylinder.normal(point));
+        }
+    }
+
+    #[test]
+    fn cylinder_bounds() {
+        let cylinder = Cylinder::default().apply_limits(-2.0, 3.0);
+        let expected_bounds =
+            BoundingBox::new(Point::new(-1.0, -2.0, -1.0), Point::new(1.0, 3.0, 1.0));
+
+        assert_eq!(expected_bounds, cylinder.bounds());
+        assert!(Cylinder::default().bounds().is_infinite());
+    }
+}
@@ -0,0 +1,262 @@
+use crate::acceleration::BoundingBox;
+use crate::intersections::Intersections;
+use crate::material::Material;
+use crate::matrices::{Matrix, Matrix4};
+use crate::point::Point;
+use crate::primitives::{Primitive, PrimitiveShape};
+use crate::ray::Ray;
+use crate::transformations::Transformable;
+use crate::vector::Vector3;
+
+/// Assembly of primitives moved together. The group transformation is composed into every
+/// child when it is added, so hits, normals and patterns of a child already account for all
+/// of its parents and a group works in the space of its own parent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    pub children: Vec<PrimitiveShape>,
+    pub transformation: Matrix4,
+    pub transformation_inverse: Matrix4,
+    pub transformation_inverse_transpose: Matrix4,
+    bounds: BoundingBox,
+    // never shaded, only there so `material` has something to lend
+    material: Material,
+}
+
+impl Group {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_child(mut self, child: PrimitiveShape) -> Self {
+        let child = child.transform(&self.transformation);
+        self.bounds = self.bounds.merge(&child.bounds());
+        self.children.push(child);
+        self
+    }
+
+    pub fn with_children(self, children: Vec<PrimitiveShape>) -> Self {
+        children
+            .into_iter()
+            .fold(self, |group, child| group.add_child(child))
+    }
+}
+
+impl Primitive for Group {
+    fn intersect(&self, ray: &Ray) -> Intersections {
+        let mut intersections = Intersections::new();
+
+        if !self
+            .bounds
+            .intersects(ray, f64::NEG_INFINITY, f64::INFINITY)
+        {
+            return intersections;
+        }
+
+        for child in self.children.iter() {
+            intersections.merge(child.intersect(ray));
+        }
+
+        intersections.sort()
+    }
+
+    /// Never hit itself, intersections point at the children.
+    fn normal(&self, _world: &Point) -> Vector3 {
+        Vector3::default()
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
+    }
+
+    fn transformation(&self) -> &Matrix4 {
+        &self.transformation
+    }
+
+    fn transformation_invert(&self) -> &Matrix4 {
+        &self.transformation_inverse
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+}
+
+impl Transformable for Group {
+    fn transform(self, transformation: &Matrix4) -> Self {
+        let delta = *transformation * self.transformation;
+        let mut delta_inverse = delta.invert();
+        let children = self
+            .children
+            .into_iter()
+            .map(|child| child.transform(transformation))
+            .collect::<Vec<_>>();
+        let bounds = children.iter().fold(BoundingBox::empty(), |bounds, child| {
+            bounds.merge(&child.bounds())
+        });
+
+        Self {
+            children,
+            transformation: delta,
+            transformation_inverse: delta_inverse,
+            transformation_inverse_transpose: delta_inverse.transpose(),
+            bounds,
+            material: self.material,
+        }
+    }
+}
+
+impl Default for Group {
+    fn default() -> Self {
+        Self {
+            children: vec![],
+            transformation: Matrix4::identity(),
+            transformation_inverse: Matrix4::identity(),
+            transformation_inverse_transpose: Matrix4::identity(),
+            bounds: BoundingBox::empty(),
+            material: Material::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod group_tests {
+    use crate::acceleration::BoundingBox;
+    use crate::color::Color;
+    use crate::intersections::IntersectionDetails;
+    use crate::material::Material;
+    use crate::patterns::Pattern;
+    use crate::point::Point;
+    use crate::primitives::PrimitiveShape::{GroupShape, SphereShape};
+    use crate::primitives::{Group, Primitive, Sphere};
+    use crate::ray::Ray;
+    use crate::transformations::{Over, Transformable};
+    use crate::vector::Vector3;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn group_empty_intersection() {
+        let group = Group::new();
+        let ray = Ray::new(Point::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert!(group.intersect(&ray).is_empty());
+    }
+
+    #[test]
+    fn group_intersects_children() {
+        let sphere_a = SphereShape(Sphere::default());
+        let sphere_b = SphereShape(Sphere::default().translate(0.0, 0.0, -3.0).transform());
+        let sphere_c = SphereShape(Sphere::default().translate(5.0, 0.0, 0.0).transform());
+        let group = Group::new().with_children(vec![sphere_a.clone(), sphere_b.clone(), sphere_c]);
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        let intersections = group.intersect(&ray);
+
+        assert_eq!(4, intersections.intersections.len());
+        assert_eq!(sphere_b, intersections[0].object);
+        assert_eq!(sphere_b, intersections[1].object);
+        assert_eq!(sphere_a, intersections[2].object);
+        assert_eq!(sphere_a, intersections[3].object);
+    }
+
+    #[test]
+    fn group_transformation_applies_to_children() {
+        let sphere = SphereShape(Sphere::default().translate(5.0, 0.0, 0.0).transform());
+        let group = GroupShape(
+            Group::new()
+                .scale(2.0, 2.0, 2.0)
+                .transform()
+                .add_child(sphere),
+        );
+        let ray = Ray::new(Point::new(10.0, 0.0, -10.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert_eq!(2, group.intersect(&ray).intersections.len());
+    }
+
+    #[test]
+    fn group_nested_normal() {
+        let sphere = SphereShape(Sphere::default().translate(5.0, 0.0, 0.0).transform());
+        let inner = GroupShape(
+            Group::new()
+                .scale(1.0, 2.0, 3.0)
+                .transform()
+                .add_child(sphere),
+        );
+        let outer = Group::new()
+            .rotate(Over::Y, PI / 2.0)
+            .transform()
+            .add_child(inner);
+        let child = match &outer.children[0] {
+            GroupShape(inner) => inner.children[0].clone(),
+            _ => unreachable!(),
+        };
+        let expected_normal = Vector3::new(0.2857, 0.4286, -0.8571);
+
+        let normal = child.normal(&Point::new(1.7321, 1.1547, -5.5774));
+
+        assert_eq!(expected_normal, normal);
+    }
+
+    #[test]
+    fn group_details_use_child_normal() {
+        let group = GroupShape(
+            Group::new()
+                .translate(0.0, 0.0, 5.0)
+                .transform()
+                .add_child(SphereShape(Sphere::default())),
+        );
+        let ray = Ray::new(Point::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+        let intersections = group.intersect(&ray);
+        let expected_normal = Vector3::new(0.0, 0.0, -1.0);
+
+        let details = IntersectionDetails::from_many(&intersections[0], &intersections, &ray);
+
+        assert_eq!(4.0, details.time);
+        assert_eq!(expected_normal, details.normal_vector);
+    }
+
+    #[test]
+    fn group_pattern_uses_parent_transformation() {
+        let material =
+            Material::default().apply_pattern(Pattern::new_stripe(Color::white(), Color::black()));
+        let group = Group::new()
+            .scale(2.0, 2.0, 2.0)
+            .transform()
+            .add_child(SphereShape(
+                Sphere::default().apply_material(material.clone()),
+            ));
+        let child = &group.children[0];
+
+        let color = material
+            .pattern
+            .unwrap()
+            .pattern_at_local(child, &Point::new(1.5, 0.0, 0.0));
+
+        assert_eq!(Color::white(), color);
+    }
+
+    #[test]
+    fn group_bounds_cover_children() {
+        let group = Group::new()
+            .translate(1.0, 0.0, 0.0)
+            .transform()
+            .with_children(vec![
+                SphereShape(Sphere::default()),
+                SphereShape(Sphere::default().translate(0.0, 4.0, 0.0).transform()),
+            ]);
+        let expected_bounds =
+            BoundingBox::new(Point::new(0.0, -1.0, -1.0), Point::new(2.0, 5.0, 1.0));
+
+        assert_eq!(expected_bounds, group.bounds());
+    }
+
+    #[test]
+    fn group_ray_missing_bounds_skips_children() {
+        let group = Group::new().add_child(SphereShape(Sphere::default()));
+        let ray = Ray::new(Point::new(0.0, 3.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert!(!group
+            .bounds()
+            .intersects(&ray, f64::NEG_INFINITY, f64::INFINITY));
+        assert!(group.intersect(&ray).is_empty());
+    }
+}
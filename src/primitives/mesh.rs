@@ -0,0 +1,432 @@
+use crate::acceleration::{BoundingBox, Bvh};
+use crate::float_eq::{EPSILON, LOW_EPSILON};
+use crate::intersections::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrices::{Matrix, Matrix4};
+use crate::point::Point;
+use crate::primitives::Primitive;
+use crate::primitives::PrimitiveShape::MeshShape;
+use crate::ray::Ray;
+use crate::transformations::Transformable;
+use crate::vector::Vector3;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Triangle of a mesh, every field is an index into the vertex data of the mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Face {
+    pub vertices: [usize; 3],
+    pub normals: Option<[usize; 3]>,
+    pub texture: Option<[usize; 3]>,
+    pub group: Option<usize>,
+    pub material: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FaceGeometry {
+    e_hit_a: Vector3,
+    e_hit_b: Vector3,
+    normal: Vector3,
+}
+
+/// Vertex data of a mesh, shared between every copy of the mesh primitive.
+#[derive(Debug, PartialEq)]
+pub struct MeshData {
+    pub vertices: Vec<Point>,
+    pub normals: Vec<Vector3>,
+    pub texture_coordinates: Vec<(f64, f64)>,
+    pub faces: Vec<Face>,
+    pub groups: Vec<String>,
+    pub materials: Vec<String>,
+    geometry: Vec<FaceGeometry>,
+    bounds: BoundingBox,
+    bvh: Bvh,
+}
+
+impl MeshData {
+    pub fn new(
+        vertices: Vec<Point>,
+        normals: Vec<Vector3>,
+        texture_coordinates: Vec<(f64, f64)>,
+        faces: Vec<Face>,
+        groups: Vec<String>,
+        materials: Vec<String>,
+    ) -> Self {
+        let geometry = faces
+            .iter()
+            .map(|face| {
+                let [a, b, c] = face.vertices.map(|index| vertices[index]);
+                let e_hit_a: Vector3 = (b - a).into();
+                let e_hit_b: Vector3 = (c - a).into();
+
+                FaceGeometry {
+                    e_hit_a,
+                    e_hit_b,
+                    normal: e_hit_b.cross(&e_hit_a).normalize(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let face_bounds = faces
+            .iter()
+            .map(|face| {
+                face.vertices
+                    .iter()
+                    .fold(BoundingBox::empty(), |bounds, &index| {
+                        bounds.add_point(&vertices[index])
+                    })
+                    .padded(LOW_EPSILON)
+            })
+            .collect::<Vec<_>>();
+
+        let bounds = face_bounds
+            .iter()
+            .fold(BoundingBox::empty(), |acc, bounds| acc.merge(bounds));
+
+        Self {
+            bvh: Bvh::build(&face_bounds),
+            vertices,
+            normals,
+            texture_coordinates,
+            faces,
+            groups,
+            materials,
+            geometry,
+            bounds,
+        }
+    }
+
+    /// Möller–Trumbore test of a single face, gives back the time and the barycentric
+    /// `u`/`v` of the hit.
+    fn intersect_face(&self, index: usize, ray: &Ray) -> Option<(f64, f64, f64)> {
+        let geometry = &self.geometry[index];
+        let point_a = self.vertices[self.faces[index].vertices[0]];
+
+        let dir_cross_b_hit = ray.direction.cross(&geometry.e_hit_b);
+        let determinant = geometry.e_hit_a.dot(&dir_cross_b_hit);
+
+        if determinant.abs() < EPSILON {
+            return None;
+        }
+
+        let f = 1.0 / determinant;
+        let point_a_to_origin: Vector3 = (ray.origin - point_a).into();
+        let u = f * point_a_to_origin.dot(&dir_cross_b_hit);
+
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let origin_cross_hit_a = point_a_to_origin.cross(&geometry.e_hit_a);
+        let v = f * ray.direction.dot(&origin_cross_hit_a);
+
+        if v < 0.0 || (u + v) > 1.0 {
+            return None;
+        }
+
+        Some((f * geometry.e_hit_b.dot(&origin_cross_hit_a), u, v))
+    }
+}
+
+impl Default for MeshData {
+    fn default() -> Self {
+        Self::new(vec![], vec![], vec![], vec![], vec![], vec![])
+    }
+}
+
+/// Mesh primitive, vertex data is shared and only the transformation and materials belong
+/// to the primitive itself. Intersections point at the hit face through `face`.
+#[derive(Debug, Clone)]
+pub struct Mesh {
+    pub data: Arc<MeshData>,
+    pub face: Option<usize>,
+    pub transformation: Matrix4,
+    pub transformation_inverse: Matrix4,
+    pub transformation_inverse_transpose: Matrix4,
+    pub material: Material,
+    // materials assigned to the `usemtl` names of the data, in the same order
+    pub materials: Arc<Vec<Option<Material>>>,
+}
+
+impl Mesh {
+    pub fn new(data: Arc<MeshData>) -> Self {
+        Self {
+            data,
+            face: None,
+            transformation: Matrix4::identity(),
+            transformation_inverse: Matrix4::identity(),
+            transformation_inverse_transpose: Matrix4::identity(),
+            material: Material::default(),
+            materials: Arc::new(vec![]),
+        }
+    }
+
+    pub fn apply_material(mut self, material: Material) -> Self {
+        self.material = material;
+        self
+    }
+
+    /// Use the given materials for faces that were declared with a matching `usemtl`.
+    pub fn apply_materials(mut self, materials: &HashMap<String, Material>) -> Self {
+        let materials = self
+            .data
+            .materials
+            .iter()
+            .map(|name| materials.get(name).cloned())
+            .collect();
+
+        self.materials = Arc::new(materials);
+        self
+    }
+
+    fn with_face(&self, face: usize) -> Self {
+        Self {
+            face: Some(face),
+            ..self.clone()
+        }
+    }
+}
+
+impl Primitive for Mesh {
+    fn intersect(&self, ray: &Ray) -> Intersections {
+        let mut hits = vec![];
+
+        self.data
+            .bvh
+            .traverse(ray, f64::NEG_INFINITY, f64::INFINITY, |index| {
+                if let Some((time, u, v)) = self.data.intersect_face(index, ray) {
+                    hits.push((index, time, u, v));
+                }
+                false
+            });
+
+        // faces come from the hierarchy in any order, keep ties stable between renders
+        hits.sort_unstable_by_key(|&(index, ..)| index);
+
+        Intersections::new().with(
+            hits.into_iter()
+                .map(|(index, time, u, v)| {
+                    Intersection::with_uv(time, MeshShape(self.with_face(index)), u, v)
+                })
+                .collect(),
+        )
+    }
+
+    fn normal(&self, _local: &Point) -> Vector3 {
+        match self.face {
+            Some(face) => self.data.geometry[face].normal,
+            None => Vector3::default(),
+        }
+    }
+
+    fn normal_at(&self, local: &Point, hit: &Intersection) -> Vector3 {
+        let normals = self
+            .face
+            .and_then(|face| self.data.faces[face].normals)
+            .map(|indices| indices.map(|index| self.data.normals[index]));
+
+        match normals {
+            Some([na, nb, nc]) => nb * hit.u + nc * hit.v + na * (1.0 - hit.u - hit.v),
+            None => self.normal(local),
+        }
+    }
+
+    fn material(&self) -> &Material {
+        self.face
+            .and_then(|face| self.data.faces[face].material)
+            .and_then(|slot| self.materials.get(slot))
+            .and_then(Option::as_ref)
+            .unwrap_or(&self.material)
+    }
+
+    fn transformation(&self) -> &Matrix4 {
+        &self.transformation
+    }
+
+    fn transformation_invert(&self) -> &Matrix4 {
+        &self.transformation_inverse
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.data.bounds
+    }
+}
+
+impl Transformable for Mesh {
+    fn transform(self, transformation: &Matrix4) -> Self {
+        let delta = *transformation * self.transformation;
+        let mut delta_inverse = delta.invert();
+        Self {
+            transformation: delta,
+            transformation_inverse: delta_inverse,
+            transformation_inverse_transpose: delta_inverse.transpose(),
+            ..self
+        }
+    }
+}
+
+/// Copies of the same mesh are equal no matter which face they were hit at, so a closed
+/// mesh counts as a single object when refraction indices are tracked.
+impl PartialEq for Mesh {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.data, &other.data)
+            && self.transformation == other.transformation
+            && self.material == other.material
+            && self.materials == other.materials
+    }
+}
+
+#[cfg(test)]
+mod mesh_tests {
+    use crate::color::Color;
+    use crate::material::Material;
+    use crate::point::Point;
+    use crate::primitives::mesh::{Face, Mesh, MeshData};
+    use crate::primitives::Primitive;
+    use crate::primitives::PrimitiveShape::MeshShape;
+    use crate::ray::Ray;
+    use crate::transformations::Transformable;
+    use crate::vector::Vector3;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn square() -> MeshData {
+        let vertices = vec![
+            Point::new(-1.0, -1.0, 0.0),
+            Point::new(1.0, -1.0, 0.0),
+            Point::new(1.0, 1.0, 0.0),
+            Point::new(-1.0, 1.0, 0.0),
+        ];
+        let faces = vec![
+            Face {
+                vertices: [0, 1, 2],
+                material: Some(0),
+                ..Face::default()
+            },
+            Face {
+                vertices: [0, 2, 3],
+                ..Face::default()
+            },
+        ];
+
+        MeshData::new(
+            vertices,
+            vec![],
+            vec![],
+            faces,
+            vec![],
+            vec!["red".to_string()],
+        )
+    }
+
+    #[test]
+    fn mesh_intersect_hits_one_face() {
+        let mesh = Mesh::new(Arc::new(square()));
+        let ray = Ray::new(Point::new(0.5, -0.5, -2.0), Vector3::new(0.0, 0.0, 1.0));
+
+        let intersections = mesh.intersect(&ray);
+
+        assert_eq!(1, intersections.intersections.len());
+        assert_eq!(2.0, intersections[0].time);
+        assert_eq!(MeshShape(mesh), intersections[0].object);
+    }
+
+    #[test]
+    fn mesh_intersect_miss() {
+        let mesh = Mesh::new(Arc::new(square()));
+        let ray = Ray::new(Point::new(1.5, 0.0, -2.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert!(mesh.intersect(&ray).is_empty());
+    }
+
+    #[test]
+    fn mesh_transformed_intersection() {
+        let mesh = MeshShape(
+            Mesh::new(Arc::new(square()))
+                .translate(0.0, 0.0, 3.0)
+                .transform(),
+        );
+        let ray = Ray::new(Point::new(-0.5, 0.5, -2.0), Vector3::new(0.0, 0.0, 1.0));
+
+        let intersections = mesh.intersect(&ray);
+
+        assert_eq!(1, intersections.intersections.len());
+        assert_eq!(5.0, intersections[0].time);
+    }
+
+    #[test]
+    fn mesh_face_normal() {
+        let mesh = Mesh::new(Arc::new(square()));
+        let ray = Ray::new(Point::new(0.5, -0.5, -2.0), Vector3::new(0.0, 0.0, 1.0));
+
+        let intersections = mesh.intersect(&ray);
+        let expected_normal = Vector3::new(0.0, 0.0, -1.0);
+
+        assert_eq!(
+            expected_normal,
+            intersections[0].object.normal(&Point::new(0.5, -0.5, 0.0))
+        );
+    }
+
+    #[test]
+    fn mesh_smooth_normal() {
+        let square = square();
+        let data = MeshData::new(
+            square.vertices.clone(),
+            vec![
+                Vector3::new(0.0, 0.0, -1.0),
+                Vector3::new(1.0, 0.0, 0.0),
+                Vector3::new(0.0, 1.0, 0.0),
+            ],
+            vec![],
+            vec![Face {
+                vertices: [0, 1, 2],
+                normals: Some([0, 1, 2]),
+                ..Face::default()
+            }],
+            vec![],
+            vec![],
+        );
+        let mesh = MeshShape(Mesh::new(Arc::new(data)));
+        let ray = Ray::new(Point::new(0.5, -0.5, -2.0), Vector3::new(0.0, 0.0, 1.0));
+
+        let intersections = mesh.intersect(&ray);
+        let point = ray.position(intersections[0].time);
+        let expected_normal = Vector3::new(0.8165, 0.40825, -0.40825);
+
+        assert_eq!(0.5, intersections[0].u);
+        assert_eq!(0.25, intersections[0].v);
+        assert_eq!(
+            expected_normal,
+            intersections[0].object.normal_at(&point, &intersections[0])
+        );
+    }
+
+    #[test]
+    fn mesh_face_material() {
+        let red = Material::default().color(Color::red());
+        let mesh = Mesh::new(Arc::new(square()))
+            .apply_materials(&HashMap::from([("red".to_string(), red.clone())]));
+
+        let hit_red = mesh.intersect(&Ray::new(
+            Point::new(0.5, -0.5, -2.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        ));
+        let hit_default = mesh.intersect(&Ray::new(
+            Point::new(-0.5, 0.5, -2.0),
+            Vector3::new(0.0, 0.0, 1.0),
+        ));
+
+        assert_eq!(&red, hit_red[0].object.material());
+        assert_eq!(&Material::default(), hit_default[0].object.material());
+    }
+
+    #[test]
+    fn mesh_copies_share_data() {
+        let mesh = Mesh::new(Arc::new(square()));
+        let moved = mesh.clone().translate(5.0, 0.0, 0.0).transform();
+
+        assert!(Arc::ptr_eq(&mesh.data, &moved.data));
+        assert_ne!(mesh, moved);
+    }
+}
@@ -0,0 +1,467 @@
+use crate::acceleration::BoundingBox;
+use crate::intersections::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrices::{Matrix, Matrix4};
+use crate::point::Point;
+use crate::primitives::{Primitive, PrimitiveShape};
+use crate::ray::Ray;
+use crate::transformations::Transformable;
+use crate::vector::Vector3;
+
+/// Positions the bounds are sampled at along the motion.
+const MOTION_STEPS: usize = 16;
+
+/// Primitive moving while the shutter is open. The child is placed by `start` at time 0.0
+/// and by `end` at time 1.0, translation, rotation and scale are interpolated in between
+/// and held outside. A ray is taken into the space of the unmoved child at the ray time and
+/// only the parts it hits are moved back out, so normals and patterns need nothing special.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Moving {
+    pub child: Box<PrimitiveShape>,
+    pub start: Matrix4,
+    pub end: Matrix4,
+    pub transformation: Matrix4,
+    pub transformation_inverse: Matrix4,
+    bounds: BoundingBox,
+}
+
+impl Moving {
+    pub fn new(child: PrimitiveShape, start: Matrix4, end: Matrix4) -> Self {
+        let bounds = Self::swept_bounds(&child, &start, &end);
+
+        Self {
+            child: Box::new(child),
+            start,
+            end,
+            transformation: Matrix4::identity(),
+            transformation_inverse: Matrix4::identity(),
+            bounds,
+        }
+    }
+
+    /// Transformation applied to the child at `time`.
+    pub fn transformation_at(&self, time: f64) -> Matrix4 {
+        interpolate(&self.start, &self.end, time)
+    }
+
+    /// Bounds at the sampled positions, padded by how far a rotation carries the child away
+    /// from them in between.
+    fn swept_bounds(child: &PrimitiveShape, start: &Matrix4, end: &Matrix4) -> BoundingBox {
+        let bounds = child.bounds();
+
+        (0..=MOTION_STEPS)
+            .fold(BoundingBox::empty(), |swept, step| {
+                let time = step as f64 / MOTION_STEPS as f64;
+                swept.merge(&bounds.transform(&interpolate(start, end, time)))
+            })
+            .padded(motion_padding(&bounds, start, end))
+    }
+}
+
+impl Primitive for Moving {
+    fn intersect(&self, ray: &Ray) -> Intersections {
+        if !self
+            .bounds
+            .intersects(ray, f64::NEG_INFINITY, f64::INFINITY)
+        {
+            return Intersections::new();
+        }
+
+        let transformation = self.transformation_at(ray.time);
+        let local_ray = ray.transform(&transformation.invert());
+        let moved = self
+            .child
+            .intersect(&local_ray)
+            .intersections
+            .into_iter()
+            .map(|intersection| Intersection {
+                object: intersection.object.transform(&transformation),
+                ..intersection
+            })
+            .collect();
+
+        Intersections::new().with(moved)
+    }
+
+    /// Never hit itself, intersections point at the moved parts of the child.
+    fn normal(&self, _world: &Point) -> Vector3 {
+        Vector3::default()
+    }
+
+    fn material(&self) -> &Material {
+        self.child.material()
+    }
+
+    fn transformation(&self) -> &Matrix4 {
+        &self.transformation
+    }
+
+    fn transformation_invert(&self) -> &Matrix4 {
+        &self.transformation_inverse
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+}
+
+impl Transformable for Moving {
+    fn transform(self, transformation: &Matrix4) -> Self {
+        Self::new(
+            *self.child,
+            *transformation * self.start,
+            *transformation * self.end,
+        )
+    }
+}
+
+/// Affine transformation split into translation, rotation (as a unit quaternion `w, x, y, z`)
+/// and scale, the parts that can be interpolated on their own.
+struct Decomposition {
+    translation: [f64; 3],
+    rotation: [f64; 4],
+    scale: [f64; 3],
+}
+
+impl Decomposition {
+    /// Shear is not kept, a sheared matrix comes back as its closest rotation and scale.
+    fn of(matrix: &Matrix4) -> Self {
+        let mut scale = [0.0; 3];
+        for (column, scale) in scale.iter_mut().enumerate() {
+            *scale = (0..3)
+                .map(|row| matrix[(row, column)].powi(2))
+                .sum::<f64>()
+                .sqrt();
+        }
+
+        let mut linear = [[0.0; 3]; 3];
+        for (row, linear) in linear.iter_mut().enumerate() {
+            for (column, value) in linear.iter_mut().enumerate() {
+                *value = matrix[(row, column)];
+            }
+        }
+
+        if determinant(&linear) < 0.0 {
+            scale[0] = -scale[0];
+        }
+
+        let mut rotation = [[0.0; 3]; 3];
+        for (row, rotation) in rotation.iter_mut().enumerate() {
+            for (column, value) in rotation.iter_mut().enumerate() {
+                *value = if scale[column] == 0.0 {
+                    if row == column {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                } else {
+                    linear[row][column] / scale[column]
+                };
+            }
+        }
+
+        Self {
+            translation: [matrix[(0, 3)], matrix[(1, 3)], matrix[(2, 3)]],
+            rotation: quaternion(&rotation),
+            scale,
+        }
+    }
+
+    fn matrix(&self) -> Matrix4 {
+        let [w, x, y, z] = self.rotation;
+        let rotation = [
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - z * w),
+                2.0 * (x * z + y * w),
+            ],
+            [
+                2.0 * (x * y + z * w),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - x * w),
+            ],
+            [
+                2.0 * (x * z - y * w),
+                2.0 * (y * z + x * w),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ];
+
+        let mut matrix = Matrix4::identity();
+        for (row, rotation) in rotation.iter().enumerate() {
+            for (column, value) in rotation.iter().enumerate() {
+                matrix = matrix.set(row, column, value * self.scale[column]);
+            }
+            matrix = matrix.set(row, 3, self.translation[row]);
+        }
+
+        matrix
+    }
+}
+
+/// Farthest a point of `bounds` strays between two sampled positions from the line joining
+/// them. Translation and scale alone move it along that line, a rotation bends its path by
+/// at most its acceleration times the square of the step over 8.
+fn motion_padding(bounds: &BoundingBox, start: &Matrix4, end: &Matrix4) -> f64 {
+    if bounds.is_empty() || bounds.is_infinite() {
+        return 0.0;
+    }
+
+    let (start, end) = (Decomposition::of(start), Decomposition::of(end));
+    let dot = (0..4)
+        .map(|i| start.rotation[i] * end.rotation[i])
+        .sum::<f64>()
+        .abs();
+    let angle = 2.0 * dot.min(1.0).acos();
+
+    let scale = start
+        .scale
+        .iter()
+        .chain(end.scale.iter())
+        .fold(0.0, |scale: f64, axis| scale.max(axis.abs()));
+    let scale_change = (0..3).fold(0.0, |change: f64, axis| {
+        change.max((end.scale[axis] - start.scale[axis]).abs())
+    });
+    let radius = [
+        bounds.min.x.abs().max(bounds.max.x.abs()),
+        bounds.min.y.abs().max(bounds.max.y.abs()),
+        bounds.min.z.abs().max(bounds.max.z.abs()),
+    ]
+    .iter()
+    .map(|axis| axis * axis)
+    .sum::<f64>()
+    .sqrt();
+
+    let acceleration = (angle * angle * scale + 2.0 * angle * scale_change) * radius;
+    let step = 1.0 / MOTION_STEPS as f64;
+
+    acceleration * step * step / 8.0
+}
+
+/// Transformation `time` of the way from `start` to `end`, clamped to both ends.
+fn interpolate(start: &Matrix4, end: &Matrix4, time: f64) -> Matrix4 {
+    let time = time.clamp(0.0, 1.0);
+    if time == 0.0 {
+        return *start;
+    }
+    if time == 1.0 {
+        return *end;
+    }
+
+    let (start, end) = (Decomposition::of(start), Decomposition::of(end));
+    let lerp = |a: &[f64; 3], b: &[f64; 3]| {
+        let mut res = [0.0; 3];
+        for (axis, res) in res.iter_mut().enumerate() {
+            *res = a[axis] + (b[axis] - a[axis]) * time;
+        }
+        res
+    };
+
+    Decomposition {
+        translation: lerp(&start.translation, &end.translation),
+        rotation: slerp(&start.rotation, &end.rotation, time),
+        scale: lerp(&start.scale, &end.scale),
+    }
+    .matrix()
+}
+
+fn determinant(m: &[[f64; 3]; 3]) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+fn quaternion(r: &[[f64; 3]; 3]) -> [f64; 4] {
+    let trace = r[0][0] + r[1][1] + r[2][2];
+
+    let q = if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        [
+            s / 4.0,
+            (r[2][1] - r[1][2]) / s,
+            (r[0][2] - r[2][0]) / s,
+            (r[1][0] - r[0][1]) / s,
+        ]
+    } else if r[0][0] > r[1][1] && r[0][0] > r[2][2] {
+        let s = (1.0 + r[0][0] - r[1][1] - r[2][2]).sqrt() * 2.0;
+        [
+            (r[2][1] - r[1][2]) / s,
+            s / 4.0,
+            (r[0][1] + r[1][0]) / s,
+            (r[0][2] + r[2][0]) / s,
+        ]
+    } else if r[1][1] > r[2][2] {
+        let s = (1.0 + r[1][1] - r[0][0] - r[2][2]).sqrt() * 2.0;
+        [
+            (r[0][2] - r[2][0]) / s,
+            (r[0][1] + r[1][0]) / s,
+            s / 4.0,
+            (r[1][2] + r[2][1]) / s,
+        ]
+    } else {
+        let s = (1.0 + r[2][2] - r[0][0] - r[1][1]).sqrt() * 2.0;
+        [
+            (r[1][0] - r[0][1]) / s,
+            (r[0][2] + r[2][0]) / s,
+            (r[1][2] + r[2][1]) / s,
+            s / 4.0,
+        ]
+    };
+
+    normalize(q)
+}
+
+fn normalize(q: [f64; 4]) -> [f64; 4] {
+    let length = q.iter().map(|value| value * value).sum::<f64>().sqrt();
+    [q[0] / length, q[1] / length, q[2] / length, q[3] / length]
+}
+
+/// Rotation `time` of the way from `a` to `b` along the shortest arc.
+fn slerp(a: &[f64; 4], b: &[f64; 4], time: f64) -> [f64; 4] {
+    let mut dot = (0..4).map(|i| a[i] * b[i]).sum::<f64>();
+    let mut b = *b;
+    if dot < 0.0 {
+        b = [-b[0], -b[1], -b[2], -b[3]];
+        dot = -dot;
+    }
+
+    let (weight_a, weight_b) = if dot > 0.9995 {
+        (1.0 - time, time)
+    } else {
+        let theta = dot.acos();
+        let sin = theta.sin();
+        (
+            ((1.0 - time) * theta).sin() / sin,
+            (time * theta).sin() / sin,
+        )
+    };
+
+    normalize([
+        a[0] * weight_a + b[0] * weight_b,
+        a[1] * weight_a + b[1] * weight_b,
+        a[2] * weight_a + b[2] * weight_b,
+        a[3] * weight_a + b[3] * weight_b,
+    ])
+}
+
+#[cfg(test)]
+mod moving_tests {
+    use crate::matrices::{Matrix, Matrix4};
+    use crate::point::Point;
+    use crate::primitives::PrimitiveShape::{CubeShape, GroupShape, MovingShape, SphereShape};
+    use crate::primitives::{Cube, Group, Moving, Primitive, Sphere};
+    use crate::ray::Ray;
+    use crate::transformations::{Over, Transform, Transformable};
+    use crate::vector::Vector3;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    fn sliding_sphere() -> Moving {
+        Moving::new(
+            SphereShape(Sphere::default()),
+            Matrix4::identity(),
+            Transform::Translate(4.0, 0.0, 0.0).transformation(),
+        )
+    }
+
+    #[test]
+    fn moving_interpolates_translation() {
+        let moving = sliding_sphere();
+        let expected_transformation = Transform::Translate(1.0, 0.0, 0.0).transformation();
+
+        assert_eq!(expected_transformation, moving.transformation_at(0.25));
+        assert_eq!(moving.start, moving.transformation_at(-1.0));
+        assert_eq!(moving.end, moving.transformation_at(2.0));
+    }
+
+    #[test]
+    fn moving_interpolates_rotation_and_scale() {
+        let moving = Moving::new(
+            SphereShape(Sphere::default()),
+            Matrix4::identity(),
+            Transform::Scale(3.0, 3.0, 3.0).transformation()
+                * Transform::Rotate(Over::Y, FRAC_PI_2).transformation(),
+        );
+        let expected_transformation = Transform::Scale(2.0, 2.0, 2.0).transformation()
+            * Transform::Rotate(Over::Y, FRAC_PI_4).transformation();
+
+        assert_eq!(expected_transformation, moving.transformation_at(0.5));
+    }
+
+    #[test]
+    fn moving_sphere_is_hit_where_it_is_at_the_ray_time() {
+        let moving = MovingShape(sliding_sphere());
+        let ray = Ray::new(Point::new(4.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        assert!(moving.intersect(&ray).is_empty());
+
+        let intersections = moving.intersect(&ray.with_time(1.0));
+        let expected_normal = Vector3::new(0.0, 0.0, -1.0);
+
+        assert_eq!(2, intersections.intersections.len());
+        assert_eq!(4.0, intersections[0].time);
+        assert_eq!(
+            expected_normal,
+            intersections[0].object.normal(&Point::new(4.0, 0.0, -1.0))
+        );
+    }
+
+    #[test]
+    fn moving_bounds_cover_the_whole_motion() {
+        let bounds = sliding_sphere().bounds();
+
+        assert_eq!(Point::new(-1.0, -1.0, -1.0), bounds.min);
+        assert_eq!(Point::new(5.0, 1.0, 1.0), bounds.max);
+    }
+
+    #[test]
+    fn moving_group_hit_points_at_the_moved_member() {
+        let group = Group::new().add_child(SphereShape(Sphere::default()));
+        let moving = MovingShape(Moving::new(
+            GroupShape(group),
+            Matrix4::identity(),
+            Transform::Translate(4.0, 0.0, 0.0).transformation(),
+        ));
+        let ray = Ray::new(Point::new(3.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
+
+        let intersections = moving.intersect(&ray.with_time(0.75));
+        let expected_normal = Vector3::new(0.0, 0.0, -1.0);
+
+        assert_eq!(2, intersections.intersections.len());
+        assert_eq!(4.0, intersections[0].time);
+        assert_eq!(
+            expected_normal,
+            intersections[0].object.normal(&Point::new(3.0, 0.0, -1.0))
+        );
+    }
+
+    #[test]
+    fn moving_bounds_cover_every_pose_of_a_rotation() {
+        let rod = CubeShape(Cube::default()).scale(4.0, 0.1, 0.1).transform();
+        let moving = Moving::new(
+            rod.clone(),
+            Matrix4::identity(),
+            Transform::Rotate(Over::Y, 3.0 * FRAC_PI_4).transformation(),
+        );
+        let bounds = moving.bounds();
+
+        for step in 0..=1000 {
+            let pose = rod
+                .bounds()
+                .transform(&moving.transformation_at(step as f64 / 1000.0));
+
+            assert!(pose.min.x >= bounds.min.x && pose.max.x <= bounds.max.x);
+            assert!(pose.min.y >= bounds.min.y && pose.max.y <= bounds.max.y);
+            assert!(pose.min.z >= bounds.min.z && pose.max.z <= bounds.max.z);
+        }
+    }
+
+    #[test]
+    fn moving_transform_moves_both_ends() {
+        let moving = sliding_sphere().translate(0.0, 2.0, 0.0).transform();
+        let expected_start = Transform::Translate(0.0, 2.0, 0.0).transformation();
+        let expected_end = Transform::Translate(4.0, 2.0, 0.0).transformation();
+
+        assert_eq!(expected_start, moving.start);
+        assert_eq!(expected_end, moving.end);
+    }
+}
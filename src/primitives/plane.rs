@@ -1,3 +1,4 @@
+use crate::acceleration::BoundingBox;
 use crate::float_eq::EPSILON;
 use crate::intersections::{Intersection, Intersections};
 use crate::material::Material;
@@ -10,7 +11,7 @@ use crate::transformations::Transformable;
 use crate::vector::Vector3;
 use std::default::Default;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Plane {
     pub transformation: Matrix4,
     pub transformation_inverse: Matrix4,
@@ -52,8 +53,8 @@ impl Primitive for Plane {
         Vector3::new(0.0, 1.0, 0.0)
     }
 
-    fn material(&self) -> Material {
-        self.material.clone()
+    fn material(&self) -> &Material {
+        &self.material
     }
 
     fn transformation(&self) -> &Matrix4 {
@@ -63,6 +64,10 @@ impl Primitive for Plane {
     fn transformation_invert(&self) -> &Matrix4 {
         &self.transformation_inverse
     }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::infinite()
+    }
 }
 
 impl Default for Plane {
@@ -158,4 +163,11 @@ mod plane_tests {
 
         assert_eq!(expected_intersections, intersections);
     }
+
+    #[test]
+    fn plane_bounds_are_infinite() {
+        let plane = PlaneShape(Plane::default());
+
+        assert!(plane.bounds().is_infinite());
+    }
 }
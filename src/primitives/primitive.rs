@@ -1,9 +1,13 @@
-use crate::intersections::Intersections;
+use crate::acceleration::BoundingBox;
+use crate::intersections::{Intersection, Intersections};
 use crate::material::Material;
 use crate::matrices::Matrix4;
 use crate::point::Point;
-use crate::primitives::PrimitiveShape::{CubeShape, PlaneShape, SphereShape, TriangleShape};
-use crate::primitives::{Cube, Plane, Sphere, Triangle};
+use crate::primitives::PrimitiveShape::{
+    ConeShape, CsgShape, CubeShape, CylinderShape, GroupShape, MeshShape, MovingShape, PlaneShape,
+    SphereShape, TriangleShape,
+};
+use crate::primitives::{Cone, Csg, Cube, Cylinder, Group, Mesh, Moving, Plane, Sphere, Triangle};
 use crate::ray::Ray;
 use crate::transformations::Transformable;
 use crate::vector::Vector3;
@@ -11,17 +15,30 @@ use crate::vector::Vector3;
 pub trait Primitive {
     fn intersect(&self, ray: &Ray) -> Intersections;
     fn normal(&self, world: &Point) -> Vector3;
-    fn material(&self) -> Material;
+    /// Normal at the point of `hit`, shapes with interpolated normals use its `u`/`v`.
+    fn normal_at(&self, world: &Point, _hit: &Intersection) -> Vector3 {
+        self.normal(world)
+    }
+    fn material(&self) -> &Material;
     fn transformation(&self) -> &Matrix4;
     fn transformation_invert(&self) -> &Matrix4;
+    /// Box around the primitive, in object space for shapes and in world space for
+    /// `PrimitiveShape`.
+    fn bounds(&self) -> BoundingBox;
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum PrimitiveShape {
     SphereShape(Sphere),
     PlaneShape(Plane),
     CubeShape(Cube),
     TriangleShape(Triangle),
+    MeshShape(Mesh),
+    CylinderShape(Cylinder),
+    ConeShape(Cone),
+    GroupShape(Group),
+    CsgShape(Csg),
+    MovingShape(Moving),
 }
 
 impl Primitive for PrimitiveShape {
@@ -43,6 +60,22 @@ impl Primitive for PrimitiveShape {
                 let ray = &ray.transform(&triangle.transformation_inverse);
                 triangle.intersect(ray)
             }
+            MeshShape(mesh) => {
+                let ray = &ray.transform(&mesh.transformation_inverse);
+                mesh.intersect(ray)
+            }
+            CylinderShape(cylinder) => {
+                let ray = &ray.transform(&cylinder.transformation_inverse);
+                cylinder.intersect(ray)
+            }
+            ConeShape(cone) => {
+                let ray = &ray.transform(&cone.transformation_inverse);
+                cone.intersect(ray)
+            }
+            // children already carry the group transformation
+            GroupShape(group) => group.intersect(ray),
+            CsgShape(csg) => csg.intersect(ray),
+            MovingShape(moving) => moving.intersect(ray),
         }
     }
 
@@ -81,15 +114,68 @@ impl Primitive for PrimitiveShape {
 
                 world_normal.normalize()
             }
+            MeshShape(mesh) => {
+                let transformation_inverted = mesh.transformation_inverse;
+                let local_point = transformation_inverted * *world;
+                let local_normal = mesh.normal(&local_point);
+                let world_normal = mesh.transformation_inverse_transpose * local_normal;
+
+                world_normal.normalize()
+            }
+            CylinderShape(cylinder) => {
+                let transformation_inverted = cylinder.transformation_inverse;
+                let local_point = transformation_inverted * *world;
+                let local_normal = cylinder.normal(&local_point);
+                let world_normal = cylinder.transformation_inverse_transpose * local_normal;
+
+                world_normal.normalize()
+            }
+            ConeShape(cone) => {
+                let transformation_inverted = cone.transformation_inverse;
+                let local_point = transformation_inverted * *world;
+                let local_normal = cone.normal(&local_point);
+                let world_normal = cone.transformation_inverse_transpose * local_normal;
+
+                world_normal.normalize()
+            }
+            GroupShape(group) => group.normal(world),
+            CsgShape(csg) => csg.normal(world),
+            MovingShape(moving) => moving.normal(world),
         }
     }
 
-    fn material(&self) -> Material {
+    fn normal_at(&self, world: &Point, hit: &Intersection) -> Vector3 {
         match self {
-            SphereShape(sphere) => sphere.material.clone(),
-            PlaneShape(plane) => plane.material.clone(),
-            CubeShape(cube) => cube.material.clone(),
-            TriangleShape(triangle) => triangle.material.clone(),
+            TriangleShape(triangle) => {
+                let local_point = triangle.transformation_inverse * *world;
+                let local_normal = triangle.normal_at(&local_point, hit);
+                let world_normal = triangle.transformation_inverse_transpose * local_normal;
+
+                world_normal.normalize()
+            }
+            MeshShape(mesh) => {
+                let local_point = mesh.transformation_inverse * *world;
+                let local_normal = mesh.normal_at(&local_point, hit);
+                let world_normal = mesh.transformation_inverse_transpose * local_normal;
+
+                world_normal.normalize()
+            }
+            _ => self.normal(world),
+        }
+    }
+
+    fn material(&self) -> &Material {
+        match self {
+            SphereShape(sphere) => &sphere.material,
+            PlaneShape(plane) => &plane.material,
+            CubeShape(cube) => &cube.material,
+            TriangleShape(triangle) => &triangle.material,
+            MeshShape(mesh) => mesh.material(),
+            CylinderShape(cylinder) => &cylinder.material,
+            ConeShape(cone) => &cone.material,
+            GroupShape(group) => group.material(),
+            CsgShape(csg) => csg.material(),
+            MovingShape(moving) => moving.material(),
         }
     }
 
@@ -99,6 +185,12 @@ impl Primitive for PrimitiveShape {
             PlaneShape(plane) => plane.transformation(),
             CubeShape(cube) => cube.transformation(),
             TriangleShape(triangle) => triangle.transformation(),
+            MeshShape(mesh) => mesh.transformation(),
+            CylinderShape(cylinder) => cylinder.transformation(),
+            ConeShape(cone) => cone.transformation(),
+            GroupShape(group) => group.transformation(),
+            CsgShape(csg) => csg.transformation(),
+            MovingShape(moving) => moving.transformation(),
         }
     }
 
@@ -108,6 +200,27 @@ impl Primitive for PrimitiveShape {
             PlaneShape(plane) => plane.transformation_invert(),
             CubeShape(cube) => cube.transformation_invert(),
             TriangleShape(triangle) => triangle.transformation_invert(),
+            MeshShape(mesh) => mesh.transformation_invert(),
+            CylinderShape(cylinder) => cylinder.transformation_invert(),
+            ConeShape(cone) => cone.transformation_invert(),
+            GroupShape(group) => group.transformation_invert(),
+            CsgShape(csg) => csg.transformation_invert(),
+            MovingShape(moving) => moving.transformation_invert(),
+        }
+    }
+
+    fn bounds(&self) -> BoundingBox {
+        match self {
+            SphereShape(sphere) => sphere.bounds().transform(&sphere.transformation),
+            PlaneShape(plane) => plane.bounds().transform(&plane.transformation),
+            CubeShape(cube) => cube.bounds().transform(&cube.transformation),
+            TriangleShape(triangle) => triangle.bounds().transform(&triangle.transformation),
+            MeshShape(mesh) => mesh.bounds().transform(&mesh.transformation),
+            CylinderShape(cylinder) => cylinder.bounds().transform(&cylinder.transformation),
+            ConeShape(cone) => cone.bounds().transform(&cone.transformation),
+            GroupShape(group) => group.bounds(),
+            CsgShape(csg) => csg.bounds(),
+            MovingShape(moving) => moving.bounds(),
         }
     }
 }
@@ -119,7 +232,30 @@ impl PartialEq for PrimitiveShape {
             (PlaneShape(plane_a), PlaneShape(plane_b)) => plane_a == plane_b,
             (CubeShape(cube_a), CubeShape(cube_b)) => cube_a == cube_b,
             (TriangleShape(triangle_a), TriangleShape(triangle_b)) => triangle_a == triangle_b,
+            (MeshShape(mesh_a), MeshShape(mesh_b)) => mesh_a == mesh_b,
+            (CylinderShape(cylinder_a), CylinderShape(cylinder_b)) => cylinder_a == cylinder_b,
+            (ConeShape(cone_a), ConeShape(cone_b)) => cone_a == cone_b,
+            (GroupShape(group_a), GroupShape(group_b)) => group_a == group_b,
+            (CsgShape(csg_a), CsgShape(csg_b)) => csg_a == csg_b,
+            (MovingShape(moving_a), MovingShape(moving_b)) => moving_a == moving_b,
             _ => false,
         }
     }
 }
+
+impl Transformable for PrimitiveShape {
+    fn transform(self, transformation: &Matrix4) -> Self {
+        match self {
+            SphereShape(sphere) => SphereShape(sphere.transform(transformation)),
+            PlaneShape(plane) => PlaneShape(plane.transform(transformation)),
+            CubeShape(cube) => CubeShape(cube.transform(transformation)),
+            TriangleShape(triangle) => TriangleShape(triangle.transform(transformation)),
+            MeshShape(mesh) => MeshShape(mesh.transform(transformation)),
+            CylinderShape(cylinder) => CylinderShape(cylinder.transform(transformation)),
+            ConeShape(cone) => ConeShape(cone.transform(transformation)),
+            GroupShape(group) => GroupShape(group.transform(transformation)),
+            CsgShape(csg) => CsgShape(csg.transform(transformation)),
+            MovingShape(moving) => MovingShape(moving.transform(transformation)),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::acceleration::BoundingBox;
 use crate::intersections::{Intersection, Intersections};
 use crate::material::Material;
 use crate::matrices::{Matrix, Matrix4};
@@ -8,7 +9,7 @@ use crate::ray::Ray;
 use crate::transformations::Transformable;
 use crate::vector::Vector3;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Sphere {
     pub origin: Point,
     pub radius: f64,
@@ -63,8 +64,8 @@ impl Primitive for Sphere {
         Vector3::new(delta_local.x, delta_local.y, delta_local.z)
     }
 
-    fn material(&self) -> Material {
-        self.material.clone()
+    fn material(&self) -> &Material {
+        &self.material
     }
 
     fn transformation(&self) -> &Matrix4 {
@@ -74,6 +75,10 @@ impl Primitive for Sphere {
     fn transformation_invert(&self) -> &Matrix4 {
         &self.transformation_inverse
     }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0))
+    }
 }
 
 impl Transformable for Sphere {
@@ -104,6 +109,7 @@ impl Default for Sphere {
 
 #[cfg(test)]
 mod sphere_tests {
+    use crate::acceleration::BoundingBox;
     use crate::color::Color;
     use crate::intersections::{Intersection, Intersections};
     use crate::material::Material;
@@ -143,7 +149,7 @@ mod sphere_tests {
         let sphere = Sphere::default();
         let intersects = sphere.intersect(&ray);
         let expected_intersects = Intersections::new().with(vec![
-            Intersection::new(4.0, SphereShape(sphere)),
+            Intersection::new(4.0, SphereShape(sphere.clone())),
             Intersection::new(6.0, SphereShape(sphere)),
         ]);
 
@@ -156,7 +162,7 @@ mod sphere_tests {
         let sphere = Sphere::default();
         let intersects = sphere.intersect(&ray);
         let expected_intersects = Intersections::new().with(vec![
-            Intersection::new(5.0, SphereShape(sphere)),
+            Intersection::new(5.0, SphereShape(sphere.clone())),
             Intersection::new(5.0, SphereShape(sphere)),
         ]);
 
@@ -169,7 +175,7 @@ mod sphere_tests {
         let sphere = Sphere::default();
         let intersects = sphere.intersect(&ray);
         let expected_intersects = Intersections::new().with(vec![
-            Intersection::new(-1.0, SphereShape(sphere)),
+            Intersection::new(-1.0, SphereShape(sphere.clone())),
             Intersection::new(1.0, SphereShape(sphere)),
         ]);
 
@@ -182,7 +188,7 @@ mod sphere_tests {
         let sphere = Sphere::default();
         let intersects = sphere.intersect(&ray);
         let expected_intersects = Intersections::new().with(vec![
-            Intersection::new(-6.0, SphereShape(sphere)),
+            Intersection::new(-6.0, SphereShape(sphere.clone())),
             Intersection::new(-4.0, SphereShape(sphere)),
         ]);
 
@@ -210,7 +216,7 @@ mod sphere_tests {
         let sphere = SphereShape(sphere_default);
         let intersects = sphere.intersect(&ray);
         let expected_intersects = Intersections::new().with(vec![
-            Intersection::new(3.0, sphere),
+            Intersection::new(3.0, sphere.clone()),
             Intersection::new(7.0, sphere),
         ]);
 
@@ -333,4 +339,26 @@ mod sphere_tests {
 
         assert_eq!(expected_material, sphere.material);
     }
+
+    #[test]
+    fn sphere_bounds() {
+        let sphere = Sphere::default();
+        let expected_bounds =
+            BoundingBox::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0));
+
+        assert_eq!(expected_bounds, sphere.bounds());
+    }
+
+    #[test]
+    fn sphere_transformed_bounds() {
+        let sphere_default = Sphere::default()
+            .scale(0.5, 2.0, 4.0)
+            .translate(1.0, -3.0, 5.0)
+            .transform();
+        let sphere = SphereShape(sphere_default);
+        let expected_bounds =
+            BoundingBox::new(Point::new(0.5, -5.0, 1.0), Point::new(1.5, -1.0, 9.0));
+
+        assert_eq!(expected_bounds, sphere.bounds());
+    }
 }
@@ -1,3 +1,4 @@
+use crate::acceleration::BoundingBox;
 use crate::float_eq::EPSILON;
 use crate::intersections::{Intersection, Intersections};
 use crate::material::Material;
@@ -6,9 +7,10 @@ use crate::point::Point;
 use crate::primitives::Primitive;
 use crate::primitives::PrimitiveShape::TriangleShape;
 use crate::ray::Ray;
+use crate::transformations::Transformable;
 use crate::vector::Vector3;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Triangle {
     pub point_a: Point,
     pub point_b: Point,
@@ -16,6 +18,8 @@ pub struct Triangle {
     pub e_hit_a: Vector3,
     pub e_hit_b: Vector3,
     pub normal: Vector3,
+    /// Normals at `point_a`, `point_b` and `point_c`, smooth triangles interpolate them.
+    pub vertex_normals: Option<[Vector3; 3]>,
     pub transformation: Matrix4,
     pub transformation_inverse: Matrix4,
     pub transformation_inverse_transpose: Matrix4,
@@ -41,12 +45,20 @@ impl Triangle {
             e_hit_a: e1,
             e_hit_b: e2,
             normal,
+            vertex_normals: None,
             transformation: Matrix4::identity(),
             transformation_inverse: Matrix4::identity(),
             transformation_inverse_transpose: Matrix4::identity(),
             material: Default::default(),
         }
     }
+
+    pub fn smooth(pa: Point, pb: Point, pc: Point, na: Vector3, nb: Vector3, nc: Vector3) -> Self {
+        Self {
+            vertex_normals: Some([na, nb, nc]),
+            ..Self::from(pa, pb, pc)
+        }
+    }
 }
 
 impl Primitive for Triangle {
@@ -64,7 +76,7 @@ impl Primitive for Triangle {
 
         let u = f * point_a_to_origin.dot(&dir_cross_b_hit);
 
-        if u < 0.0 || u > 1.0 {
+        if !(0.0..=1.0).contains(&u) {
             return Intersections::new();
         }
 
@@ -76,16 +88,27 @@ impl Primitive for Triangle {
 
         let time = f * self.e_hit_b.dot(&origin_cross_hit_a);
 
-        return Intersections::new()
-            .with(vec![Intersection::new(time, TriangleShape(self.clone()))]);
+        Intersections::new().with(vec![Intersection::with_uv(
+            time,
+            TriangleShape(self.clone()),
+            u,
+            v,
+        )])
     }
 
     fn normal(&self, _world: &Point) -> Vector3 {
         self.normal
     }
 
-    fn material(&self) -> Material {
-        self.material.clone()
+    fn normal_at(&self, world: &Point, hit: &Intersection) -> Vector3 {
+        match self.vertex_normals {
+            Some([na, nb, nc]) => nb * hit.u + nc * hit.v + na * (1.0 - hit.u - hit.v),
+            None => self.normal(world),
+        }
+    }
+
+    fn material(&self) -> &Material {
+        &self.material
     }
 
     fn transformation(&self) -> &Matrix4 {
@@ -95,6 +118,26 @@ impl Primitive for Triangle {
     fn transformation_invert(&self) -> &Matrix4 {
         &self.transformation_inverse
     }
+
+    fn bounds(&self) -> BoundingBox {
+        BoundingBox::empty()
+            .add_point(&self.point_a)
+            .add_point(&self.point_b)
+            .add_point(&self.point_c)
+    }
+}
+
+impl Transformable for Triangle {
+    fn transform(self, transformation: &Matrix4) -> Self {
+        let delta = *transformation * self.transformation;
+        let mut delta_inverse = delta.invert();
+        Self {
+            transformation: delta,
+            transformation_inverse: delta_inverse,
+            transformation_inverse_transpose: delta_inverse.transpose(),
+            ..self
+        }
+    }
 }
 
 impl Default for Triangle {
@@ -106,6 +149,7 @@ impl Default for Triangle {
             e_hit_a: Default::default(),
             e_hit_b: Default::default(),
             normal: Default::default(),
+            vertex_normals: None,
             transformation: Matrix4::identity(),
             transformation_inverse: Matrix4::identity(),
             transformation_inverse_transpose: Matrix4::identity(),
@@ -116,7 +160,9 @@ impl Default for Triangle {
 
 #[cfg(test)]
 mod triangle_tests {
-    use crate::intersections::{Intersection, Intersections};
+    use crate::acceleration::BoundingBox;
+    use crate::float_eq::EPSILON;
+    use crate::intersections::{Intersection, IntersectionDetails, Intersections};
     use crate::point::Point;
     use crate::primitives::triangle::Triangle;
     use crate::primitives::Primitive;
@@ -245,13 +291,74 @@ mod triangle_tests {
         );
 
         let ray = Ray::new(Point::new(0.0, 0.5, -2.0), Vector3::new(0.0, 0.0, 1.0));
-        let expected_intersections = Intersections::new().with(vec![Intersection::new(
+        let expected_intersections = Intersections::new().with(vec![Intersection::with_uv(
             2.0,
             TriangleShape(triangle.clone()),
+            0.25,
+            0.25,
         )]);
 
         let intersections = triangle.intersect(&ray);
 
         assert_eq!(expected_intersections, intersections);
     }
+
+    #[test]
+    fn triangle_smooth_intersection_stores_uv() {
+        let triangle = smooth_triangle();
+        let ray = Ray::new(Point::new(-0.2, 0.3, -2.0), Vector3::new(0.0, 0.0, 1.0));
+
+        let intersections = triangle.intersect(&ray);
+
+        assert!((intersections[0].u - 0.45).abs() < EPSILON);
+        assert!((intersections[0].v - 0.25).abs() < EPSILON);
+    }
+
+    #[test]
+    fn triangle_smooth_normal_interpolation() {
+        let triangle = smooth_triangle();
+        let hit = Intersection::with_uv(1.0, TriangleShape(triangle.clone()), 0.45, 0.25);
+        let expected_normal = Vector3::new(-0.5547, 0.83205, 0.0);
+
+        let normal = TriangleShape(triangle).normal_at(&Point::default(), &hit);
+
+        assert_eq!(expected_normal, normal);
+    }
+
+    #[test]
+    fn triangle_smooth_details_use_interpolated_normal() {
+        let triangle = smooth_triangle();
+        let hit = Intersection::with_uv(2.0, TriangleShape(triangle), 0.45, 0.25);
+        let ray = Ray::new(Point::new(-0.2, 0.3, -2.0), Vector3::new(0.0, 0.0, 1.0));
+        let intersections = Intersections::new().with(vec![hit.clone()]);
+        let expected_normal = Vector3::new(-0.5547, 0.83205, 0.0);
+
+        let details = IntersectionDetails::from_many(&hit, &intersections, &ray);
+
+        assert_eq!(expected_normal, details.normal_vector);
+    }
+
+    fn smooth_triangle() -> Triangle {
+        Triangle::smooth(
+            Point::new(0.0, 1.0, 0.0),
+            Point::new(-1.0, 0.0, 0.0),
+            Point::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(-1.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn triangle_bounds() {
+        let triangle = Triangle::from(
+            Point::new(-3.0, 7.0, 2.0),
+            Point::new(6.0, 2.0, -4.0),
+            Point::new(2.0, -1.0, -1.0),
+        );
+        let expected_bounds =
+            BoundingBox::new(Point::new(-3.0, -1.0, -4.0), Point::new(6.0, 7.0, 2.0));
+
+        assert_eq!(expected_bounds, triangle.bounds());
+    }
 }
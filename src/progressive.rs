@@ -0,0 +1,219 @@
+use crate::canvas::Canvas;
+use crate::color::Color;
+use crate::float_eq::EPSILON;
+use std::time::Duration;
+
+/// Progressive rendering settings. Every pass adds the samples of the render `Sampling` to
+/// each pixel, taken with another seed so they land somewhere new.
+/// Passes stop after `max_passes`, once `time_budget` is spent or once the noise estimate
+/// drops to `noise_threshold`, whichever comes first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progressive {
+    pub max_passes: usize,
+    pub time_budget: Option<Duration>,
+    pub noise_threshold: Option<f64>,
+}
+
+impl Progressive {
+    pub fn new(max_passes: usize) -> Self {
+        Self {
+            max_passes: max_passes.max(1),
+            time_budget: None,
+            noise_threshold: None,
+        }
+    }
+
+    pub fn with_time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = Some(time_budget);
+        self
+    }
+
+    pub fn with_noise_threshold(mut self, noise_threshold: f64) -> Self {
+        self.noise_threshold = Some(noise_threshold);
+        self
+    }
+
+    /// True when no pass should follow `pass`.
+    pub fn is_done(&self, pass: &Pass) -> bool {
+        let out_of_time = self
+            .time_budget
+            .is_some_and(|budget| pass.elapsed >= budget);
+        let smooth = match (self.noise_threshold, pass.noise) {
+            (Some(threshold), Some(noise)) => noise <= threshold,
+            _ => false,
+        };
+
+        pass.number >= self.max_passes || out_of_time || smooth
+    }
+}
+
+impl Default for Progressive {
+    fn default() -> Self {
+        Self::new(16)
+    }
+}
+
+/// Progress of a progressive render after one of its passes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pass {
+    /// Passes done so far, starting at 1.
+    pub number: usize,
+    pub samples_per_pixel: usize,
+    /// Time since the render started.
+    pub elapsed: Duration,
+    /// Average standard error of the pixel brightness, unknown after a single pass.
+    pub noise: Option<f64>,
+}
+
+/// Running sums of a progressive render, kept in floating point so passes can be added
+/// without losing precision. Besides the weighted color of every pixel, it follows the
+/// spread of the brightness each pass gives the pixel to estimate the noise left.
+#[derive(Debug)]
+pub struct Accumulator {
+    width: usize,
+    height: usize,
+    color: Vec<Color>,
+    weight: Vec<f64>,
+    mean: Vec<f64>,
+    squares: Vec<f64>,
+    passes: usize,
+}
+
+impl Accumulator {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            color: vec![Color::black(); width * height],
+            weight: vec![0.0; width * height],
+            mean: vec![0.0; width * height],
+            squares: vec![0.0; width * height],
+            passes: 0,
+        }
+    }
+
+    /// Add a pass, `pass` holds the weighted color sum and total weight of every pixel, row
+    /// after row.
+    pub fn add_pass(&mut self, pass: &[(Color, f64)]) {
+        self.passes += 1;
+        let count = self.passes as f64;
+
+        for (i, (color, weight)) in pass.iter().enumerate() {
+            self.color[i] = self.color[i] + *color;
+            self.weight[i] += weight;
+
+            // Welford's running variance of the brightness of the pass estimates
+            let brightness = brightness(&resolve(color, *weight));
+            let delta = brightness - self.mean[i];
+            self.mean[i] += delta / count;
+            self.squares[i] += delta * (brightness - self.mean[i]);
+        }
+    }
+
+    pub fn passes(&self) -> usize {
+        self.passes
+    }
+
+    /// Average standard error of the pixel brightness over the passes so far, `None` until
+    /// there are two of them.
+    pub fn noise(&self) -> Option<f64> {
+        if self.passes < 2 || self.squares.is_empty() {
+            return None;
+        }
+
+        let count = self.passes as f64;
+        let total = self
+            .squares
+            .iter()
+            .map(|squares| (squares / (count - 1.0) / count).sqrt())
+            .sum::<f64>();
+
+        Some(total / self.squares.len() as f64)
+    }
+
+    /// Image of all the passes so far.
+    pub fn canvas(&self) -> Canvas {
+        let mut canvas = Canvas::new(self.width, self.height, Color::black());
+
+        for (pixel, (color, weight)) in canvas
+            .pixels()
+            .iter_mut()
+            .zip(self.color.iter().zip(self.weight.iter()))
+        {
+            *pixel = resolve(color, *weight);
+        }
+
+        canvas
+    }
+}
+
+fn resolve(color: &Color, weight: f64) -> Color {
+    if weight.abs() < EPSILON {
+        Color::black()
+    } else {
+        *color / weight
+    }
+}
+
+fn brightness(color: &Color) -> f64 {
+    (color.r + color.g + color.b) / 3.0
+}
+
+#[cfg(test)]
+mod progressive_tests {
+    use crate::color::Color;
+    use crate::progressive::{Accumulator, Pass, Progressive};
+    use std::time::Duration;
+
+    fn pass(number: usize, elapsed: u64, noise: Option<f64>) -> Pass {
+        Pass {
+            number,
+            samples_per_pixel: number,
+            elapsed: Duration::from_millis(elapsed),
+            noise,
+        }
+    }
+
+    #[test]
+    fn accumulator_averages_passes() {
+        let mut accumulator = Accumulator::new(2, 1);
+        let expected_color = Color::new(0.5, 0.25, 0.0);
+
+        accumulator.add_pass(&[(Color::new(2.0, 1.0, 0.0), 2.0), (Color::black(), 0.0)]);
+        accumulator.add_pass(&[(Color::black(), 2.0), (Color::black(), 0.0)]);
+        let canvas = accumulator.canvas();
+
+        assert_eq!(2, accumulator.passes());
+        assert_eq!(expected_color, canvas[0][0]);
+        assert_eq!(Color::black(), canvas[0][1]);
+    }
+
+    #[test]
+    fn accumulator_noise() {
+        let mut accumulator = Accumulator::new(1, 1);
+
+        accumulator.add_pass(&[(Color::white(), 1.0)]);
+        assert_eq!(None, accumulator.noise());
+
+        accumulator.add_pass(&[(Color::white(), 1.0)]);
+        assert_eq!(Some(0.0), accumulator.noise());
+
+        // brightness 1.0, 1.0 and 0.0 have a standard error of 1/3
+        accumulator.add_pass(&[(Color::black(), 1.0)]);
+        assert!((accumulator.noise().unwrap() - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn progressive_stops_on_any_limit() {
+        let progressive = Progressive::new(4)
+            .with_time_budget(Duration::from_millis(100))
+            .with_noise_threshold(0.01);
+
+        assert!(!progressive.is_done(&pass(1, 10, None)));
+        assert!(!progressive.is_done(&pass(2, 10, Some(0.5))));
+        assert!(progressive.is_done(&pass(4, 10, Some(0.5))));
+        assert!(progressive.is_done(&pass(2, 100, Some(0.5))));
+        assert!(progressive.is_done(&pass(2, 10, Some(0.005))));
+        assert!(!Progressive::default().is_done(&pass(2, 1_000_000, Some(0.0))));
+    }
+}
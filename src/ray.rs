@@ -4,15 +4,27 @@ use crate::transformations::Transformable;
 use crate::vector::Vector3;
 
 // TODO: reference and lifetimes
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
 pub struct Ray {
     pub origin: Point,
     pub direction: Vector3,
+    /// Moment within the shutter interval the ray is cast at, moving primitives are hit
+    /// where they are at this time.
+    pub time: f64,
 }
 
 impl Ray {
     pub fn new(origin: Point, direction: Vector3) -> Self {
-        Self { origin, direction }
+        Self {
+            origin,
+            direction,
+            time: 0.0,
+        }
+    }
+
+    pub fn with_time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
     }
 
     pub fn position(&self, time: f64) -> Point {
@@ -26,15 +38,7 @@ impl Transformable for Ray {
             // TODO: Replace with {*transformation} when matrix is slice and has Copy trait, instead of vector
             origin: *transformation * self.origin,
             direction: *transformation * self.direction,
-        }
-    }
-}
-
-impl Default for Ray {
-    fn default() -> Self {
-        Self {
-            origin: Point::default(),
-            direction: Vector3::default(),
+            time: self.time,
         }
     }
 }
@@ -83,6 +87,16 @@ mod ray_tests {
         assert_eq!(expected_ray.direction, translated_ray.direction);
     }
 
+    #[test]
+    fn ray_transform_keeps_time() {
+        let ray = Ray::new(Point::new(1.0, 2.0, 3.0), Vector3::new(0.0, 1.0, 0.0)).with_time(0.5);
+
+        let translated_ray = ray.translate(3.0, 4.0, 5.0).transform();
+
+        assert_eq!(0.0, Ray::default().time);
+        assert_eq!(0.5, translated_ray.time);
+    }
+
     #[test]
     fn ray_scale() {
         let ray = Ray::new(Point::new(1.0, 2.0, 3.0), Vector3::new(0.0, 1.0, 0.0));
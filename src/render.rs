@@ -1,16 +1,27 @@
+use crate::sampling::{Adaptive, Sampling};
+use crate::tiling::Tiling;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Rendering {
     Parallel,
     Sequential,
+    /// Refine only the pixels that stand out from a neighbor, ignores the sampling setting.
+    Adaptive(Adaptive),
 }
 
 pub struct Render {
     pub render_mode: Rendering,
+    pub sampling: Sampling,
+    /// How a parallel render splits the image.
+    pub tiling: Tiling,
 }
 
 impl Render {
     pub fn new() -> Self {
         Self {
             render_mode: Rendering::Parallel,
+            sampling: Sampling::default(),
+            tiling: Tiling::default(),
         }
     }
 }
@@ -0,0 +1,339 @@
+use crate::point::Point;
+
+/// Small deterministic generator (splitmix64). Every sampled quantity derives its own
+/// generator from a seed and what is being sampled, so results don't depend on the order
+/// in which threads get to render pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Generator for the samples of the pixel at `x`, `y`.
+    pub fn at_pixel(seed: u64, x: usize, y: usize) -> Self {
+        let mut rng = Self::new(seed);
+        for coordinate in [x as u64, y as u64] {
+            rng.state ^= coordinate;
+            rng.next_u64();
+        }
+        rng
+    }
+
+    /// Generator for samples taken at `point`.
+    pub fn at_point(seed: u64, point: &Point) -> Self {
+        let mut rng = Self::new(seed);
+        for coordinate in [point.x, point.y, point.z] {
+            rng.state ^= coordinate.to_bits();
+            rng.next_u64();
+        }
+        rng
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform number in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// How the samples of a pixel are spread over it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sampler {
+    /// Centers of a regular grid, the sample count is rounded to a square number.
+    Grid,
+    /// One random point per cell of the grid, the sample count is rounded the same way.
+    Jittered,
+    /// Halton sequence in bases 2 and 3, shifted by a random offset per pixel.
+    LowDiscrepancy,
+}
+
+impl Sampler {
+    /// Points in the unit square for one pixel, `rng` only moves the random ones.
+    pub fn offsets(&self, samples: usize, rng: &mut Rng) -> Vec<(f64, f64)> {
+        match self {
+            Sampler::Grid | Sampler::Jittered => {
+                let side = ((samples as f64).sqrt().round() as usize).max(1);
+                let mut offsets = Vec::with_capacity(side * side);
+
+                for row in 0..side {
+                    for col in 0..side {
+                        let (dx, dy) = match self {
+                            Sampler::Jittered => (rng.next_f64(), rng.next_f64()),
+                            _ => (0.5, 0.5),
+                        };
+                        offsets.push((
+                            (col as f64 + dx) / side as f64,
+                            (row as f64 + dy) / side as f64,
+                        ));
+                    }
+                }
+
+                offsets
+            }
+            Sampler::LowDiscrepancy => {
+                let (shift_x, shift_y) = (rng.next_f64(), rng.next_f64());
+
+                (0..samples.max(1))
+                    .map(|index| {
+                        let x = (radical_inverse(index, 2) + shift_x).fract();
+                        let y = (radical_inverse(index, 3) + shift_y).fract();
+                        (x, y)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Digits of `index` in `base` mirrored behind the decimal point.
+fn radical_inverse(mut index: usize, base: usize) -> f64 {
+    let mut result = 0.0;
+    let mut fraction = 1.0 / base as f64;
+
+    while index > 0 {
+        result += (index % base) as f64 * fraction;
+        index /= base;
+        fraction /= base as f64;
+    }
+
+    result
+}
+
+/// Reconstruction filter weighting the samples of a pixel by their distance to its center.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    Box,
+    Tent,
+    Gaussian,
+    /// Mitchell-Netravali with B = C = 1/3.
+    Mitchell,
+}
+
+impl Filter {
+    /// Half width of the filter in pixels, samples are spread over that much of the image.
+    pub fn radius(&self) -> f64 {
+        match self {
+            Filter::Box => 0.5,
+            Filter::Tent => 1.0,
+            Filter::Gaussian => 1.5,
+            Filter::Mitchell => 2.0,
+        }
+    }
+
+    /// Weight of a sample `x`, `y` pixels away from the center.
+    pub fn weight(&self, x: f64, y: f64) -> f64 {
+        self.weight_1d(x) * self.weight_1d(y)
+    }
+
+    fn weight_1d(&self, x: f64) -> f64 {
+        let x = x.abs();
+        let radius = self.radius();
+
+        if x > radius {
+            return 0.0;
+        }
+
+        match self {
+            Filter::Box => 1.0,
+            Filter::Tent => 1.0 - x,
+            Filter::Gaussian => {
+                let alpha = 2.0;
+                (-alpha * x * x).exp() - (-alpha * radius * radius).exp()
+            }
+            Filter::Mitchell => {
+                let (b, c) = (1.0 / 3.0, 1.0 / 3.0);
+
+                if x < 1.0 {
+                    ((12.0 - 9.0 * b - 6.0 * c) * x.powi(3)
+                        + (-18.0 + 12.0 * b + 6.0 * c) * x.powi(2)
+                        + (6.0 - 2.0 * b))
+                        / 6.0
+                } else {
+                    ((-b - 6.0 * c) * x.powi(3)
+                        + (6.0 * b + 30.0 * c) * x.powi(2)
+                        + (-12.0 * b - 48.0 * c) * x
+                        + (8.0 * b + 24.0 * c))
+                        / 6.0
+                }
+            }
+        }
+    }
+}
+
+/// Antialiasing settings of a render. The default is a single sample through the center of
+/// every pixel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sampling {
+    pub samples: usize,
+    pub sampler: Sampler,
+    pub filter: Filter,
+    pub seed: u64,
+}
+
+impl Sampling {
+    pub fn new(samples: usize, sampler: Sampler, filter: Filter) -> Self {
+        Self {
+            samples: samples.max(1),
+            sampler,
+            filter,
+            seed: 0,
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sample positions of the pixel at `x`, `y` relative to its center, in pixels.
+    pub fn pixel_offsets(&self, x: usize, y: usize) -> Vec<(f64, f64)> {
+        let mut rng = Rng::at_pixel(self.seed, x, y);
+        let footprint = 2.0 * self.filter.radius();
+
+        self.sampler
+            .offsets(self.samples, &mut rng)
+            .into_iter()
+            .map(|(dx, dy)| ((dx - 0.5) * footprint, (dy - 0.5) * footprint))
+            .collect()
+    }
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Self::new(1, Sampler::Grid, Filter::Box)
+    }
+}
+
+/// Adaptive antialiasing settings. A pixel gets more samples when its color differs from a
+/// neighbor by more than `threshold` in any channel, up to `max_samples` for the pixel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Adaptive {
+    pub threshold: f64,
+    pub max_samples: usize,
+}
+
+impl Adaptive {
+    pub fn new(threshold: f64, max_samples: usize) -> Self {
+        Self {
+            threshold,
+            max_samples: max_samples.max(1),
+        }
+    }
+}
+
+impl Default for Adaptive {
+    fn default() -> Self {
+        Self::new(0.1, 17)
+    }
+}
+
+#[cfg(test)]
+mod sampling_tests {
+    use crate::point::Point;
+    use crate::sampling::{Filter, Rng, Sampler, Sampling};
+
+    #[test]
+    fn rng_same_seed_same_sequence() {
+        let mut rng_a = Rng::new(42);
+        let mut rng_b = Rng::new(42);
+
+        for _ in 0..16 {
+            assert_eq!(rng_a.next_u64(), rng_b.next_u64());
+        }
+    }
+
+    #[test]
+    fn rng_values_in_unit_interval() {
+        let mut rng = Rng::at_point(7, &Point::new(1.0, -2.0, 3.5));
+
+        for _ in 0..1000 {
+            let value = rng.next_f64();
+            assert!((0.0..1.0).contains(&value));
+        }
+    }
+
+    #[test]
+    fn rng_differs_between_points() {
+        let mut rng_a = Rng::at_point(7, &Point::new(1.0, 2.0, 3.0));
+        let mut rng_b = Rng::at_point(7, &Point::new(1.0, 2.0, 3.0001));
+
+        assert_ne!(rng_a.next_u64(), rng_b.next_u64());
+    }
+
+    #[test]
+    fn sampler_grid_offsets() {
+        let offsets = Sampler::Grid.offsets(4, &mut Rng::new(0));
+        let expected_offsets = vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)];
+
+        assert_eq!(expected_offsets, offsets);
+        assert_eq!(9, Sampler::Grid.offsets(10, &mut Rng::new(0)).len());
+    }
+
+    #[test]
+    fn sampler_jittered_offsets_stay_in_their_cell() {
+        let offsets = Sampler::Jittered.offsets(9, &mut Rng::new(3));
+
+        assert_eq!(9, offsets.len());
+        for (index, (x, y)) in offsets.iter().enumerate() {
+            let (col, row) = ((index % 3) as f64, (index / 3) as f64);
+
+            assert!(*x >= col / 3.0 && *x < (col + 1.0) / 3.0);
+            assert!(*y >= row / 3.0 && *y < (row + 1.0) / 3.0);
+        }
+    }
+
+    #[test]
+    fn sampler_low_discrepancy_covers_the_square() {
+        let offsets = Sampler::LowDiscrepancy.offsets(16, &mut Rng::new(5));
+
+        assert_eq!(16, offsets.len());
+        for half in [(0.0, 0.5), (0.5, 1.0)] {
+            let count = offsets
+                .iter()
+                .filter(|(x, _)| *x >= half.0 && *x < half.1)
+                .count();
+            assert_eq!(8, count);
+        }
+    }
+
+    #[test]
+    fn filter_weights() {
+        assert_eq!(1.0, Filter::Box.weight(0.4, -0.4));
+        assert_eq!(0.0, Filter::Box.weight(0.6, 0.0));
+        assert_eq!(0.25, Filter::Tent.weight(0.5, 0.5));
+        assert!(Filter::Gaussian.weight(0.0, 0.0) > Filter::Gaussian.weight(1.0, 0.0));
+        assert_eq!(0.0, Filter::Gaussian.weight(1.5, 0.0));
+        assert!(Filter::Mitchell.weight(1.5, 0.0) < 0.0);
+        assert!(Filter::Mitchell.weight(0.0, 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sampling_default_is_pixel_center() {
+        let expected_offsets = vec![(0.0, 0.0)];
+
+        assert_eq!(expected_offsets, Sampling::default().pixel_offsets(3, 7));
+    }
+
+    #[test]
+    fn sampling_offsets_span_the_filter() {
+        let sampling = Sampling::new(16, Sampler::Jittered, Filter::Tent).with_seed(9);
+        let offsets = sampling.pixel_offsets(1, 2);
+
+        assert_eq!(offsets, sampling.pixel_offsets(1, 2));
+        assert_ne!(offsets, sampling.pixel_offsets(2, 1));
+        for (x, y) in offsets {
+            assert!(x.abs() <= 1.0 && y.abs() <= 1.0);
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use std::cmp::Ordering;
+use std::time::Duration;
+
+/// Order tiles are handed out to the render threads in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileOrder {
+    /// Left to right, top to bottom.
+    Rows,
+    /// From the center of the image outwards, ring after ring.
+    Spiral,
+    /// Along a Hilbert curve, neighboring tiles are rendered close together in time.
+    Hilbert,
+}
+
+impl TileOrder {
+    pub fn from_name(name: &str) -> Option<TileOrder> {
+        match name {
+            "rows" => Some(TileOrder::Rows),
+            "spiral" => Some(TileOrder::Spiral),
+            "hilbert" => Some(TileOrder::Hilbert),
+            _ => None,
+        }
+    }
+}
+
+/// Rectangle of the image rendered in one go, tiles along the right and bottom edges are
+/// cut to the image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// How a parallel render splits the image, square tiles of `tile_size` pixels in `order`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tiling {
+    pub tile_size: usize,
+    pub order: TileOrder,
+}
+
+impl Tiling {
+    pub fn new(tile_size: usize, order: TileOrder) -> Self {
+        Self {
+            tile_size: tile_size.max(1),
+            order,
+        }
+    }
+
+    /// Tiles covering an image of `width` by `height` pixels, in render order.
+    pub fn tiles(&self, width: usize, height: usize) -> Vec<Tile> {
+        let size = self.tile_size;
+        let (columns, rows) = (width.div_ceil(size), height.div_ceil(size));
+
+        let mut cells = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .collect::<Vec<_>>();
+
+        match self.order {
+            TileOrder::Rows => {}
+            TileOrder::Spiral => {
+                let center = ((columns as f64 - 1.0) / 2.0, (rows as f64 - 1.0) / 2.0);
+                let key = |&(column, row): &(usize, usize)| {
+                    let (dx, dy) = (column as f64 - center.0, row as f64 - center.1);
+                    (dx.abs().max(dy.abs()), dy.atan2(dx))
+                };
+                cells.sort_by(|a, b| {
+                    let (a, b) = (key(a), key(b));
+                    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+                });
+            }
+            TileOrder::Hilbert => {
+                let side = columns.max(rows).next_power_of_two();
+                cells.sort_by_key(|&(column, row)| hilbert_index(side, column, row));
+            }
+        }
+
+        cells
+            .into_iter()
+            .map(|(column, row)| Tile {
+                x: column * size,
+                y: row * size,
+                width: size.min(width - column * size),
+                height: size.min(height - row * size),
+            })
+            .collect()
+    }
+}
+
+impl Default for Tiling {
+    fn default() -> Self {
+        Self::new(32, TileOrder::Spiral)
+    }
+}
+
+/// Distance along the Hilbert curve filling a `side` by `side` grid, `side` being a power of
+/// two.
+fn hilbert_index(side: usize, x: usize, y: usize) -> usize {
+    let (mut x, mut y) = (x, y);
+    let mut index = 0;
+    let mut s = side / 2;
+
+    while s > 0 {
+        let rx = usize::from(x & s > 0);
+        let ry = usize::from(y & s > 0);
+        index += s * s * ((3 * rx) ^ ry);
+
+        // rotate the quadrant so the curve continues where the previous one ended
+        if ry == 0 {
+            if rx == 1 {
+                x = side - 1 - x;
+                y = side - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+
+    index
+}
+
+/// Progress of a tiled render, reported after every finished tile.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    pub tiles_done: usize,
+    pub tiles_total: usize,
+    /// Time since the render started.
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    /// Part of the tiles done, between 0.0 and 1.0.
+    pub fn fraction(&self) -> f64 {
+        if self.tiles_total == 0 {
+            1.0
+        } else {
+            self.tiles_done as f64 / self.tiles_total as f64
+        }
+    }
+
+    /// Time left when the remaining tiles take as long as the finished ones did, unknown
+    /// before the first tile is done.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.tiles_done == 0 {
+            return None;
+        }
+
+        let remaining = (self.tiles_total - self.tiles_done) as f64 / self.tiles_done as f64;
+        Some(self.elapsed.mul_f64(remaining))
+    }
+}
+
+#[cfg(test)]
+mod tiling_tests {
+    use crate::tiling::{hilbert_index, Progress, Tile, TileOrder, Tiling};
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    fn covered_pixels(tiles: &[Tile]) -> usize {
+        tiles.iter().map(|tile| tile.width * tile.height).sum()
+    }
+
+    #[test]
+    fn tiling_covers_any_image_size() {
+        for order in [TileOrder::Rows, TileOrder::Spiral, TileOrder::Hilbert] {
+            let tiles = Tiling::new(16, order).tiles(50, 37);
+            let corners = tiles
+                .iter()
+                .map(|tile| (tile.x, tile.y))
+                .collect::<HashSet<_>>();
+
+            assert_eq!(12, tiles.len());
+            assert_eq!(12, corners.len());
+            assert_eq!(50 * 37, covered_pixels(&tiles));
+        }
+    }
+
+    #[test]
+    fn tiling_rows_order() {
+        let tiles = Tiling::new(10, TileOrder::Rows).tiles(25, 15);
+        let expected_last = Tile {
+            x: 20,
+            y: 10,
+            width: 5,
+            height: 5,
+        };
+
+        assert_eq!((0, 0), (tiles[0].x, tiles[0].y));
+        assert_eq!((10, 0), (tiles[1].x, tiles[1].y));
+        assert_eq!(expected_last, tiles[5]);
+    }
+
+    #[test]
+    fn tiling_spiral_starts_in_the_center() {
+        let tiles = Tiling::new(10, TileOrder::Spiral).tiles(50, 50);
+
+        assert_eq!((20, 20), (tiles[0].x, tiles[0].y));
+        assert!(tiles[1..9]
+            .iter()
+            .all(|tile| (10..=30).contains(&tile.x) && (10..=30).contains(&tile.y)));
+    }
+
+    #[test]
+    fn tiling_hilbert_steps_to_neighbors() {
+        let tiles = Tiling::new(1, TileOrder::Hilbert).tiles(8, 8);
+
+        for pair in tiles.windows(2) {
+            let distance = pair[0].x.abs_diff(pair[1].x) + pair[0].y.abs_diff(pair[1].y);
+            assert_eq!(1, distance);
+        }
+        assert_eq!(0, hilbert_index(4, 0, 0));
+        assert_eq!(15, hilbert_index(4, 3, 0));
+    }
+
+    #[test]
+    fn tiling_order_from_name() {
+        assert_eq!(Some(TileOrder::Hilbert), TileOrder::from_name("hilbert"));
+        assert_eq!(None, TileOrder::from_name("random"));
+    }
+
+    #[test]
+    fn progress_eta() {
+        let progress = Progress {
+            tiles_done: 4,
+            tiles_total: 10,
+            elapsed: Duration::from_secs(2),
+        };
+
+        assert_eq!(0.4, progress.fraction());
+        assert_eq!(Some(Duration::from_secs(3)), progress.eta());
+        assert_eq!(
+            None,
+            Progress {
+                tiles_done: 0,
+                ..progress
+            }
+            .eta()
+        );
+    }
+}
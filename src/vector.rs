@@ -58,9 +58,7 @@ impl Vector3 {
     pub fn near_zero(&self) -> bool {
         let almost_zero = 1e-8;
 
-        return self.x.abs() < almost_zero
-            && self.y.abs() < almost_zero
-            && self.z.abs() < almost_zero;
+        self.x.abs() < almost_zero && self.y.abs() < almost_zero && self.z.abs() < almost_zero
     }
 
     /// Reflects a 3D vector off a surface defined by a normal vector.
@@ -89,7 +87,7 @@ impl Transformable for Vector3 {
 
 impl PartialEq for Vector3 {
     fn eq(&self, other: &Self) -> bool {
-        self.approx_eq_low(&other)
+        self.approx_eq_low(other)
     }
 }
 
@@ -1,17 +1,27 @@
+use crate::acceleration::{Acceleration, Bvh};
+use crate::background::Background;
 use crate::color::Color;
-use crate::float_eq::ApproxEq;
+use crate::float_eq::{ApproxEq, LOW_EPSILON};
 use crate::intersections::{IntersectionDetails, Intersections};
-use crate::lights::PointLight;
+use crate::lights::Light;
 use crate::point::Point;
 use crate::primitives::{Primitive, PrimitiveShape};
 use crate::ray::Ray;
 use crate::vector::Vector3;
+use std::sync::OnceLock;
 
 #[derive(Debug, PartialEq)]
 pub struct World {
     objects: Vec<PrimitiveShape>,
-    light_sources: Vec<PointLight>,
+    light_sources: Vec<Light>,
     recursive_depth: usize,
+    acceleration: Acceleration,
+    background: Background,
+    // part of the average background color lighting every surface as ambient light
+    background_ambient: f64,
+    background_average: Color,
+    // built on the first traced ray, so that adding objects one by one stays cheap
+    bvh: OnceLock<Bvh>,
 }
 
 impl World {
@@ -20,89 +30,232 @@ impl World {
             objects: Vec::with_capacity(8),
             light_sources: Vec::with_capacity(4),
             recursive_depth: 10,
+            acceleration: Acceleration::default(),
+            background: Background::default(),
+            background_ambient: 0.0,
+            background_average: Color::black(),
+            bvh: OnceLock::new(),
         }
     }
 
     pub fn add_object(mut self, object: PrimitiveShape) -> Self {
         self.objects.push(object);
+        self.bvh = OnceLock::new();
         self
     }
 
-    pub fn add_light_source(mut self, source: PointLight) -> Self {
-        self.light_sources.push(source);
+    pub fn add_light_source(mut self, source: impl Into<Light>) -> Self {
+        self.light_sources.push(source.into());
         self
     }
 
     pub fn with_objects(mut self, objects: Vec<PrimitiveShape>) -> Self {
         self.objects = objects;
+        self.bvh = OnceLock::new();
         self
     }
 
-    pub fn with_light_sources(mut self, sources: Vec<PointLight>) -> Self {
-        self.light_sources = sources;
+    pub fn with_light_sources<T: Into<Light>>(mut self, sources: Vec<T>) -> Self {
+        self.light_sources = sources.into_iter().map(Into::into).collect();
         self
     }
 
+    pub fn with_recursive_depth(mut self, depth: usize) -> Self {
+        self.recursive_depth = depth;
+        self
+    }
+
+    pub fn with_acceleration(mut self, acceleration: Acceleration) -> Self {
+        self.acceleration = acceleration;
+        self
+    }
+
+    /// What rays that miss every object see, black by default.
+    pub fn with_background(mut self, background: Background) -> Self {
+        self.background_average = background.average();
+        self.background = background;
+        self
+    }
+
+    /// Light the surfaces with `strength` times the average color of the background, on top
+    /// of the ambient term of each light. The material `ambient` scales it like it does for
+    /// lights.
+    pub fn with_background_ambient(mut self, strength: f64) -> Self {
+        self.background_ambient = strength;
+        self
+    }
+
+    pub fn background(&self) -> &Background {
+        &self.background
+    }
+
     pub fn get_primitive(self, index: usize) -> PrimitiveShape {
-        self.objects[index]
+        self.objects[index].clone()
+    }
+
+    pub fn objects(&self) -> &[PrimitiveShape] {
+        &self.objects
+    }
+
+    pub fn light_sources(&self) -> &[Light] {
+        &self.light_sources
+    }
+
+    fn bvh(&self) -> &Bvh {
+        self.bvh.get_or_init(|| {
+            let bounds = self
+                .objects
+                .iter()
+                .map(|object| object.bounds().padded(LOW_EPSILON))
+                .collect::<Vec<_>>();
+
+            Bvh::build(&bounds)
+        })
     }
 
     pub fn intersect_objects(&self, ray: &Ray) -> Intersections {
         let mut intersections = Intersections::new();
 
-        self.objects
-            .iter()
-            .for_each(|object| intersections.merge(object.intersect(ray)));
+        match self.acceleration {
+            Acceleration::BruteForce => self
+                .objects
+                .iter()
+                .for_each(|object| intersections.merge(object.intersect(ray))),
+            // candidates come in the order of `objects`, so ties sort the same as brute force
+            Acceleration::Bvh => self
+                .bvh()
+                .candidates(ray)
+                .into_iter()
+                .for_each(|index| intersections.merge(self.objects[index].intersect(ray))),
+        }
 
         intersections.sort()
     }
 
+    /// Part of the light that gets through everything the ray hits before `distance`. Each
+    /// transparent surface crossed tints it by its color where it is crossed and its
+    /// transparency, opaque ones block it and materials that don't cast shadows are skipped.
+    fn transmittance(&self, ray: &Ray, distance: f64) -> Color {
+        let mut tints: Vec<(f64, Color)> = vec![];
+        let mut blocked = false;
+        let mut absorb = |object: &PrimitiveShape| {
+            for intersection in object.intersect(ray).intersections {
+                let material = intersection.object.material();
+
+                if intersection.time < 0.0
+                    || intersection.time >= distance
+                    || !material.casts_shadow
+                {
+                    continue;
+                }
+
+                let position = ray.position(intersection.time);
+                let tint =
+                    material.color_at(&intersection.object, &position) * material.transparency;
+
+                if tint == Color::black() {
+                    blocked = true;
+                    return true;
+                }
+                tints.push((intersection.time, tint));
+            }
+            false
+        };
+
+        match self.acceleration {
+            Acceleration::BruteForce => {
+                self.objects.iter().any(absorb);
+            }
+            Acceleration::Bvh => {
+                self.bvh()
+                    .traverse(ray, 0.0, distance, |index| absorb(&self.objects[index]));
+            }
+        }
+
+        if blocked {
+            return Color::black();
+        }
+
+        // multiplied along the ray rather than in the order the objects were visited, so the
+        // brute force and bvh paths round the same way
+        tints.sort_by(|(time_a, tint_a), (time_b, tint_b)| {
+            time_a
+                .total_cmp(time_b)
+                .then(tint_a.r.total_cmp(&tint_b.r))
+                .then(tint_a.g.total_cmp(&tint_b.g))
+                .then(tint_a.b.total_cmp(&tint_b.b))
+        });
+        tints
+            .iter()
+            .fold(Color::white(), |transmittance, (_, tint)| {
+                transmittance * *tint
+            })
+    }
+
     pub fn shade_hit(&self, details: &IntersectionDetails, recursive_depth: usize) -> Color {
-        self.light_sources
+        // every light is shaded with its own shadow test, so a point hidden from one light
+        // can still be lit by another
+        let material = details.object.material();
+        let surface_color = self
+            .light_sources
             .iter()
             .fold(Color::default(), |acc, light| {
-                let is_shadowed = self.shadow_cast(&details.over_point);
+                let light_intensity =
+                    self.light_reaching(light, &details.over_point, details.ray_time);
 
-                let surface_color = details.object.material().phong_reflection(
+                acc + material.phong_reflection(
                     light,
                     &details.object,
                     &details.over_point,
                     &details.eye_vector,
                     &details.normal_vector,
-                    is_shadowed,
-                );
+                    light_intensity,
+                )
+            });
+        let surface_color = surface_color + self.background_light(details);
 
-                let reflected_color = self.reflect_color(details, recursive_depth);
-                let refracted_color = self.refracted_color(details, recursive_depth);
+        let reflected_color = self.reflect_color(details, recursive_depth);
+        let refracted_color = self.refracted_color(details, recursive_depth);
 
-                if details.object.material().reflective > 0.0
-                    && details.object.material().transparency > 0.0
-                {
-                    let reflectance = details.schlick();
+        if material.reflective > 0.0 && material.transparency > 0.0 {
+            let reflectance = details.schlick();
 
-                    acc + surface_color
-                        + reflected_color * reflectance
-                        + refracted_color * (1.0 - reflectance)
-                } else {
-                    acc + surface_color + reflected_color + refracted_color
-                }
-            })
+            surface_color + reflected_color * reflectance + refracted_color * (1.0 - reflectance)
+        } else {
+            surface_color + reflected_color + refracted_color
+        }
+    }
+
+    fn background_light(&self, details: &IntersectionDetails) -> Color {
+        if self.background_ambient <= 0.0 {
+            return Color::black();
+        }
+
+        let material = details.object.material();
+        let color = material.color_at(&details.object, &details.over_point);
+
+        color * self.background_average * material.ambient * self.background_ambient
     }
 
     pub fn reflect_color(&self, details: &IntersectionDetails, recursive_depth: usize) -> Color {
-        if details.object.material().reflective.approx_eq_low(&0.0) || recursive_depth == 0 {
+        let reflective = details.object.material().reflective;
+
+        if reflective.approx_eq_low(&0.0) || recursive_depth == 0 {
             Color::black()
         } else {
-            let reflected_ray = Ray::new(details.over_point, details.reflection_vector);
+            let reflected_ray =
+                Ray::new(details.over_point, details.reflection_vector).with_time(details.ray_time);
 
             let color = self.color_at_recursive(&reflected_ray, recursive_depth - 1);
 
-            color * details.object.material().reflective
+            color * reflective
         }
     }
 
     pub fn refracted_color(&self, details: &IntersectionDetails, recursive_depth: usize) -> Color {
-        if details.object.material().transparency.approx_eq_low(&0.0) || recursive_depth == 0 {
+        let transparency = details.object.material().transparency;
+
+        if transparency.approx_eq_low(&0.0) || recursive_depth == 0 {
             Color::black()
         } else {
             let (n1, n2) = (details.n1, details.n2);
@@ -121,10 +274,10 @@ impl World {
                 let refract_ray = Ray {
                     origin: details.under_point,
                     direction: direction_refracted,
+                    time: details.ray_time,
                 };
 
-                self.color_at_recursive(&refract_ray, recursive_depth - 1)
-                    * details.object.material().transparency
+                self.color_at_recursive(&refract_ray, recursive_depth - 1) * transparency
             }
         }
     }
@@ -134,34 +287,48 @@ impl World {
     }
 
     fn color_at_recursive(&self, ray: &Ray, recursive_depth: usize) -> Color {
-        let intersections = self.intersect_objects(&ray);
+        let intersections = self.intersect_objects(ray);
 
         match intersections.hit() {
             Some(hit) => {
-                let details = IntersectionDetails::from_many(hit, &intersections, &ray);
+                let details = IntersectionDetails::from_many(hit, &intersections, ray);
                 self.shade_hit(&details, recursive_depth)
             }
-            None => Color::black(),
+            None => self.background.color_at(&ray.direction),
         }
     }
 
     pub fn shadow_cast(&self, point: &Point) -> bool {
-        for light in &self.light_sources {
-            let v = light.position - *point;
-            let distance = v.magnitude();
-            let direction = v.normalize();
+        self.light_sources.iter().any(|light| {
+            self.is_shadowed(
+                point,
+                &light.direction_from(point),
+                light.distance_from(point),
+            )
+        })
+    }
 
-            let ray = Ray::new(*point, Vector3::new(direction.x, direction.y, direction.z));
-            let intersections = self.intersect_objects(&ray);
+    /// True when nothing of a light `distance` away in `direction` gets through to `point`.
+    pub fn is_shadowed(&self, point: &Point, direction: &Vector3, distance: f64) -> bool {
+        self.transmittance(&Ray::new(*point, *direction), distance) == Color::black()
+    }
 
-            if let Some(hit) = intersections.hit() {
-                if hit.time < distance {
-                    return true;
-                }
-            }
-        }
+    /// Part of `light` reaching `point` per channel, the transmittance averaged over its
+    /// samples.
+    pub fn light_intensity_at(&self, light: &Light, point: &Point) -> Color {
+        self.light_reaching(light, point, 0.0)
+    }
 
-        false
+    /// `light_intensity_at` with shadow rays cast at `time`, so moving primitives block the
+    /// light where they are at that moment.
+    fn light_reaching(&self, light: &Light, point: &Point, time: f64) -> Color {
+        let samples = light.samples(point);
+        let transmitted = samples.iter().fold(Color::black(), |acc, sample| {
+            let ray = Ray::new(*point, sample.direction).with_time(time);
+            acc + self.transmittance(&ray, sample.distance)
+        });
+
+        transmitted / samples.len() as f64
     }
 }
 
@@ -173,18 +340,26 @@ impl Default for World {
 
 #[cfg(test)]
 mod world_tests {
+    use crate::acceleration::Acceleration;
+    use crate::background::Background;
+    use crate::camera::Camera;
+    use crate::canvas::Canvas;
     use crate::color::Color;
     use crate::intersections::{Intersection, IntersectionDetails, Intersections};
-    use crate::lights::PointLight;
+    use crate::lights::{AreaLight, DirectionalLight, Light, PointLight, SpotLight};
     use crate::material::Material;
     use crate::patterns::Pattern;
     use crate::point::Point;
-    use crate::primitives::PrimitiveShape::{PlaneShape, SphereShape};
-    use crate::primitives::{Plane, Sphere};
+    use crate::primitives::PrimitiveShape::{
+        CubeShape, MovingShape, PlaneShape, SphereShape, TriangleShape,
+    };
+    use crate::primitives::{Cube, Moving, Plane, Sphere, Triangle};
     use crate::ray::Ray;
-    use crate::transformations::Transformable;
+    use crate::transformations::Transform::{Orientation, Translate};
+    use crate::transformations::{Over, Transformable};
     use crate::vector::Vector3;
     use crate::world::World;
+    use std::f64::consts::PI;
 
     #[test]
     fn world_creation() {
@@ -235,15 +410,15 @@ mod world_tests {
             PointLight::new(Color::new(1.0, 1.0, 1.0), Point::new(-10.0, 10.0, -10.0));
 
         let world = World::default()
-            .add_object(SphereShape(sphere_a))
-            .add_object(SphereShape(sphere_b))
+            .add_object(SphereShape(sphere_a.clone()))
+            .add_object(SphereShape(sphere_b.clone()))
             .add_light_source(light_source);
 
         let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector3::new(0.0, 0.0, 1.0));
 
         let expected_intersections = Intersections::new().with(vec![
-            Intersection::new(4.0, SphereShape(sphere_b)),
-            Intersection::new(4.5, SphereShape(sphere_a)),
+            Intersection::new(4.0, SphereShape(sphere_b.clone())),
+            Intersection::new(4.5, SphereShape(sphere_a.clone())),
             Intersection::new(5.5, SphereShape(sphere_a)),
             Intersection::new(6.0, SphereShape(sphere_b)),
         ]);
@@ -334,6 +509,53 @@ mod world_tests {
         assert_eq!(expected_color, world.color_at_recursive(&ray, 0));
     }
 
+    #[test]
+    fn world_color_at_ray_miss_sees_background() {
+        let world = World::default().with_background(Background::Gradient {
+            bottom: Color::black(),
+            top: Color::new(0.0, 0.0, 1.0),
+        });
+        let ray = Ray::new(Point::new(0.0, 0.0, -5.0), Vector3::new(0.0, 1.0, 0.0));
+        let expected_color = Color::new(0.0, 0.0, 1.0);
+
+        assert_eq!(expected_color, world.color_at(&ray));
+    }
+
+    #[test]
+    fn world_reflects_background() {
+        let mirror = Plane::default().apply_material(
+            Material::default()
+                .reflective(1.0)
+                .ambient(0.0)
+                .diffuse(0.0)
+                .specular(0.0),
+        );
+        let world = World::default()
+            .add_object(PlaneShape(mirror))
+            .with_background(Background::Solid(Color::new(0.2, 0.4, 0.6)));
+        let ray = Ray::new(Point::new(0.0, 1.0, -1.0), Vector3::new(0.0, -1.0, 1.0));
+        let expected_color = Color::new(0.2, 0.4, 0.6);
+
+        assert_eq!(expected_color, world.color_at(&ray));
+    }
+
+    #[test]
+    fn world_background_ambient_lights_surfaces() {
+        let plane = Plane::default()
+            .apply_material(Material::default().ambient(0.5).diffuse(0.0).specular(0.0));
+        let world = World::default()
+            .add_object(PlaneShape(plane))
+            .with_background(Background::Solid(Color::new(0.2, 0.4, 0.6)));
+        let ray = Ray::new(Point::new(0.0, 1.0, -1.0), Vector3::new(0.0, -1.0, 1.0));
+        let expected_color = Color::new(0.1, 0.2, 0.3);
+
+        assert_eq!(Color::black(), world.color_at(&ray));
+        assert_eq!(
+            expected_color,
+            world.with_background_ambient(1.0).color_at(&ray)
+        );
+    }
+
     #[test]
     fn world_color_at_ray_hits() {
         let sphere_a = Sphere::default().scale(0.5, 0.5, 0.5).transform();
@@ -382,6 +604,325 @@ mod world_tests {
         assert_eq!(expected_color, world.color_at_recursive(&ray, 0));
     }
 
+    #[test]
+    fn world_light_intensity_of_point_light() {
+        let sphere_a = Sphere::default().scale(0.5, 0.5, 0.5).transform();
+        let sphere_b = Sphere::default();
+        let light = Light::Point(PointLight::new(
+            Color::new(1.0, 1.0, 1.0),
+            Point::new(-10.0, 10.0, -10.0),
+        ));
+        let world = World::default()
+            .add_object(SphereShape(sphere_a))
+            .add_object(SphereShape(sphere_b))
+            .add_light_source(light);
+        let cases = [
+            (Point::new(0.0, 1.0001, 0.0), 1.0),
+            (Point::new(-1.0001, 0.0, 0.0), 1.0),
+            (Point::new(0.0, 0.0, -1.0001), 1.0),
+            (Point::new(0.0, 0.0, 1.0001), 0.0),
+            (Point::new(1.0001, 0.0, 0.0), 0.0),
+            (Point::new(0.0, -1.0001, 0.0), 0.0),
+            (Point::new(0.0, 0.0, 0.0), 0.0),
+        ];
+
+        for (point, expected_intensity) in cases.iter() {
+            assert_eq!(
+                Color::white() * *expected_intensity,
+                world.light_intensity_at(&light, point)
+            );
+        }
+    }
+
+    #[test]
+    fn world_area_light_soft_shadow() {
+        // the slab hides the half of the light with positive x from the origin
+        let slab = Cube::default()
+            .scale(1.25, 0.1, 1.0)
+            .translate(1.25, 5.0, 0.0)
+            .transform();
+        let light = Light::Area(AreaLight::rectangle(
+            Color::new(1.0, 1.0, 1.0),
+            Point::new(-2.0, 10.0, 0.0),
+            Vector3::new(4.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 0.0),
+            2,
+            1,
+        ));
+        let world = World::default()
+            .add_object(CubeShape(slab))
+            .add_light_source(light);
+
+        assert_eq!(
+            Color::new(0.5, 0.5, 0.5),
+            world.light_intensity_at(&light, &Point::default())
+        );
+        assert_eq!(
+            Color::white(),
+            world.light_intensity_at(&light, &Point::new(-5.0, 0.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn world_area_light_penumbra_is_deterministic() {
+        let ball = Sphere::default().translate(0.0, 1.0, 0.0).transform();
+        let light = AreaLight::rectangle(
+            Color::new(1.0, 1.0, 1.0),
+            Point::new(-1.0, 5.0, -1.0),
+            Vector3::new(2.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 2.0),
+            4,
+            4,
+        )
+        .with_seed(3);
+        let world = World::default()
+            .add_object(SphereShape(ball))
+            .add_light_source(light);
+        let point = Point::new(1.1, 0.0, 0.0);
+
+        let intensity = world.light_intensity_at(&world.light_sources[0], &point);
+
+        assert!(intensity.r > 0.0 && intensity.r < 1.0);
+        assert_eq!(
+            intensity,
+            world.light_intensity_at(&world.light_sources[0], &point)
+        );
+    }
+
+    #[test]
+    fn world_second_light_lights_shadow_of_the_first() {
+        let floor = Plane::default();
+        let wall = Cube::default()
+            .scale(0.1, 5.0, 5.0)
+            .translate(-2.0, 0.0, 0.0)
+            .transform();
+        let left = PointLight::new(Color::new(1.0, 0.0, 0.0), Point::new(-10.0, 10.0, 0.0));
+        let right = PointLight::new(Color::new(0.0, 1.0, 0.0), Point::new(10.0, 10.0, 0.0));
+        let world = World::default()
+            .add_object(PlaneShape(floor))
+            .add_object(CubeShape(wall))
+            .add_light_source(left)
+            .add_light_source(right);
+        let point = Point::new(0.0, 0.01, 0.0);
+
+        assert!(world.shadow_cast(&point));
+        assert_eq!(
+            Color::black(),
+            world.light_intensity_at(&world.light_sources[0], &point)
+        );
+        assert_eq!(
+            Color::white(),
+            world.light_intensity_at(&world.light_sources[1], &point)
+        );
+
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0));
+        let color = world.color_at(&ray);
+
+        assert!(color.r < 0.2);
+        assert!(color.g > 0.2);
+    }
+
+    #[test]
+    fn world_two_lights_add_up() {
+        let floor = Plane::default();
+        let light = PointLight::new(Color::new(0.5, 0.5, 0.5), Point::new(0.0, 10.0, 0.0));
+        let one_light = World::default()
+            .add_object(PlaneShape(floor.clone()))
+            .add_light_source(light);
+        let two_lights = World::default()
+            .add_object(PlaneShape(floor))
+            .add_light_source(light)
+            .add_light_source(light);
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0));
+
+        assert_eq!(one_light.color_at(&ray) * 2.0, two_lights.color_at(&ray));
+    }
+
+    #[test]
+    fn world_reflection_added_once_with_many_lights() {
+        let mirror = Plane::default().apply_material(Material::default().reflective(0.5));
+        let light = PointLight::new(Color::new(1.0, 1.0, 1.0), Point::new(-10.0, 10.0, -10.0));
+        let world = World::default()
+            .add_object(PlaneShape(mirror))
+            .add_light_source(light)
+            .add_light_source(light);
+        let ray = Ray::new(Point::new(0.0, 1.0, -1.0), Vector3::new(0.0, -1.0, 1.0));
+        let intersection = Intersection::new(1.0, world.objects()[0].clone());
+        let details = IntersectionDetails::from(&intersection, &ray);
+
+        let reflected = world.reflect_color(&details, 4);
+        let surface = world.shade_hit(&details, 0);
+
+        assert_eq!(surface + reflected, world.shade_hit(&details, 4));
+    }
+
+    #[test]
+    fn world_refraction_added_once_with_many_lights() {
+        let glass = Plane::default().apply_material(Material::default().transparency(0.5));
+        let floor = Plane::default()
+            .translate(0.0, -1.0, 0.0)
+            .transform()
+            .apply_material(Material::default().ambient(0.5).color(Color::red()));
+        let light = PointLight::new(Color::new(1.0, 1.0, 1.0), Point::new(-10.0, 10.0, -10.0));
+        let world = World::default()
+            .add_object(PlaneShape(glass))
+            .add_object(PlaneShape(floor))
+            .add_light_source(light)
+            .add_light_source(light);
+        let ray = Ray::new(Point::new(0.0, 1.0, -1.0), Vector3::new(0.0, -1.0, 1.0));
+        let intersection = Intersection::new(1.0, world.objects()[0].clone());
+        let details = IntersectionDetails::from(&intersection, &ray);
+
+        let refracted = world.refracted_color(&details, 4);
+        let surface = world.shade_hit(&details, 0);
+
+        assert_ne!(Color::black(), refracted);
+        assert_eq!(surface + refracted, world.shade_hit(&details, 4));
+    }
+
+    #[test]
+    fn world_point_light_attenuation_darkens_far_points() {
+        let floor = Plane::default();
+        let mut light = PointLight::new(Color::new(1.0, 1.0, 1.0), Point::new(0.0, 1.0, 0.0));
+        light.with_attenuation(1.0, 0.0, 1.0);
+        let world = World::default()
+            .add_object(PlaneShape(floor))
+            .add_light_source(light);
+        let near = Ray::new(Point::new(0.0, 0.5, 0.0), Vector3::new(0.0, -1.0, 0.0));
+        let far = Ray::new(Point::new(3.0, 0.5, 0.0), Vector3::new(0.0, -1.0, 0.0));
+
+        assert!(world.color_at(&near).r > world.color_at(&far).r);
+        assert_eq!(
+            0.5,
+            world.light_sources()[0].intensity_at(&Point::default()).r
+        );
+    }
+
+    #[test]
+    fn world_transparent_shadow_is_tinted() {
+        let glass = Material::default()
+            .color(Color::new(1.0, 0.5, 0.0))
+            .transparency(0.8);
+        let pane = Cube::default()
+            .apply_material(glass)
+            .scale(2.0, 0.1, 2.0)
+            .translate(0.0, 5.0, 0.0)
+            .transform();
+        let light = Light::Point(PointLight::new(Color::white(), Point::new(0.0, 10.0, 0.0)));
+        let world = World::default()
+            .add_object(CubeShape(pane))
+            .add_light_source(light);
+        let point = Point::default();
+        // the pane is crossed twice, entering and leaving it
+        let expected_intensity = Color::new(0.64, 0.16, 0.0);
+
+        assert_eq!(expected_intensity, world.light_intensity_at(&light, &point));
+        assert!(!world.shadow_cast(&point));
+    }
+
+    #[test]
+    fn world_patterned_glass_shadow_follows_the_pattern() {
+        let glass = Material::default()
+            .apply_pattern(Pattern::new_plain(Color::new(0.0, 0.5, 1.0)))
+            .transparency(1.0);
+        let pane = Plane::default()
+            .apply_material(glass)
+            .translate(0.0, 5.0, 0.0)
+            .transform();
+        let light = Light::Point(PointLight::new(Color::white(), Point::new(0.0, 10.0, 0.0)));
+        let world = World::default()
+            .add_object(PlaneShape(pane))
+            .add_light_source(light);
+        let expected_intensity = Color::new(0.0, 0.5, 1.0);
+
+        assert_eq!(
+            expected_intensity,
+            world.light_intensity_at(&light, &Point::default())
+        );
+    }
+
+    #[test]
+    fn world_transparent_shadows_same_as_brute_force() {
+        // added from the top down, the bvh visits them from the bottom up
+        let world = |acceleration| {
+            (0..12)
+                .fold(World::default(), |world, i| {
+                    let i = i as f64;
+                    let glass = Material::default()
+                        .color(Color::new(0.9 - i * 0.05, 0.3 + i * 0.05, 0.7))
+                        .transparency(0.9 - i * 0.03);
+                    world.add_object(SphereShape(
+                        Sphere::default()
+                            .apply_material(glass)
+                            .scale(0.6, 0.6, 0.6)
+                            .translate((i % 3.0) * 0.3, 12.0 - i, (i % 4.0) * 0.2)
+                            .transform(),
+                    ))
+                })
+                .with_acceleration(acceleration)
+        };
+        let (brute_force, bvh) = (world(Acceleration::BruteForce), world(Acceleration::Bvh));
+        let light = Light::Point(PointLight::new(Color::white(), Point::new(0.3, 20.0, 0.3)));
+        let bits = |color: Color| [color.r.to_bits(), color.g.to_bits(), color.b.to_bits()];
+
+        for x in -4..4 {
+            let point = Point::new(x as f64 * 0.1, -1.0, 0.2);
+            let through = brute_force.light_intensity_at(&light, &point);
+
+            assert!(through.r > 0.0);
+            assert_eq!(bits(through), bits(bvh.light_intensity_at(&light, &point)));
+        }
+    }
+
+    #[test]
+    fn world_material_without_shadow_lets_light_through() {
+        let water = Plane::default()
+            .apply_material(Material::default().casts_shadow(false))
+            .translate(0.0, 1.0, 0.0)
+            .transform();
+        let light = Light::Point(PointLight::new(Color::white(), Point::new(0.0, 10.0, 0.0)));
+        let world = World::default()
+            .add_object(PlaneShape(water))
+            .add_light_source(light);
+
+        assert_eq!(
+            Color::white(),
+            world.light_intensity_at(&light, &Point::default())
+        );
+        assert!(!world.shadow_cast(&Point::default()));
+    }
+
+    #[test]
+    fn world_directional_light_shadow_rays_are_parallel() {
+        let far_away = Sphere::default().translate(5.0, 1000.0, 0.0).transform();
+        let sun = DirectionalLight::new(Color::white(), Vector3::new(0.0, -1.0, 0.0));
+        let world = World::default()
+            .add_object(SphereShape(far_away))
+            .add_light_source(sun);
+
+        assert!(world.shadow_cast(&Point::new(5.0, 0.0, 0.0)));
+        assert!(!world.shadow_cast(&Point::new(0.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn world_spot_light_only_lights_its_cone() {
+        let floor = Plane::default();
+        let spot = SpotLight::new(
+            Color::white(),
+            Point::new(0.0, 10.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            PI / 8.0,
+        );
+        let world = World::default()
+            .add_object(PlaneShape(floor))
+            .add_light_source(spot);
+        let ray_at = |x: f64| Ray::new(Point::new(x, 1.0, 0.0), Vector3::new(0.0, -1.0, 0.0));
+        let expected_ambient = Color::new(0.1, 0.1, 0.1);
+
+        assert_eq!(Color::new(1.9, 1.9, 1.9), world.color_at(&ray_at(0.0)));
+        assert_eq!(expected_ambient, world.color_at(&ray_at(5.0)));
+    }
+
     #[test]
     fn world_there_are_no_shadow() {
         let sphere_a = Sphere::default().scale(0.5, 0.5, 0.5).transform();
@@ -495,11 +1036,11 @@ mod world_tests {
         let light_point = PointLight::new(Color::new(1.0, 1.0, 1.0), Point::new(0.0, 0.0, -10.0));
 
         let world = World::default()
-            .with_objects(vec![sphere_a.clone(), sphere_b.clone()])
+            .with_objects(vec![sphere_a, sphere_b.clone()])
             .with_light_sources(vec![light_point]);
 
         let ray = Ray::new(Point::new(0.0, 0.0, 5.0), Vector3::new(0.0, 0.0, 1.0));
-        let intersection = Intersection::new(4.0, sphere_b.clone());
+        let intersection = Intersection::new(4.0, sphere_b);
         let intersection_details = IntersectionDetails::from(&intersection, &ray);
         let expected_color = Color::new(0.1, 0.1, 0.1);
 
@@ -760,7 +1301,8 @@ mod world_tests {
         let intersections = Intersections::new().with(vec![intersection.clone()]);
         let intersection_details =
             IntersectionDetails::from_many(&intersection, &intersections, &ray);
-        let expected_color = Color::new(0.93642, 0.68642, 0.68642);
+        // the glass floor lets half of the light through to the ball below it
+        let expected_color = Color::new(1.12547, 0.68643, 0.68643);
 
         assert_eq!(expected_color, world.shade_hit(&intersection_details, 5));
     }
@@ -809,8 +1351,172 @@ mod world_tests {
         let intersections = Intersections::new().with(vec![intersection.clone()]);
         let intersection_details =
             IntersectionDetails::from_many(&intersection, &intersections, &ray);
-        let expected_color = Color::new(0.93391, 0.69643, 0.69243);
+        // the glass floor lets half of the light through to the ball below it
+        let expected_color = Color::new(1.11500, 0.69643, 0.69243);
 
         assert_eq!(expected_color, world.shade_hit(&intersection_details, 5));
     }
+
+    fn crowded_world() -> World {
+        let floor = Plane::default().translate(0.0, -1.0, 0.0).transform();
+        let glass = Sphere::default()
+            .translate(0.0, 0.5, -1.0)
+            .transform()
+            .apply_material(
+                Material::default()
+                    .transparency(0.9)
+                    .refractive_index(1.5)
+                    .reflective(0.9),
+            );
+
+        let mut objects = vec![PlaneShape(floor), SphereShape(glass)];
+
+        for x in -4..4 {
+            for z in 0..6 {
+                let (x, z) = (x as f64, z as f64);
+                let material = Material::default()
+                    .color(Color::new(0.1 * z, 0.5, 0.3))
+                    .reflective(0.2);
+
+                objects.push(if (x + z) as i64 % 3 == 0 {
+                    CubeShape(
+                        Cube::default()
+                            .scale(0.3, 0.3, 0.3)
+                            .rotate(Over::Y, x)
+                            .translate(x, -0.7, z)
+                            .transform()
+                            .apply_material(material),
+                    )
+                } else if (x + z) as i64 % 3 == 1 {
+                    TriangleShape(Triangle {
+                        material,
+                        ..Triangle::from(
+                            Point::new(x - 0.3, -1.0, z),
+                            Point::new(x + 0.3, -1.0, z),
+                            Point::new(x, -0.2, z + 0.2),
+                        )
+                    })
+                } else {
+                    SphereShape(
+                        Sphere::default()
+                            .scale(0.3, 0.3, 0.3)
+                            .translate(x, -0.7, z)
+                            .transform()
+                            .apply_material(material),
+                    )
+                });
+            }
+        }
+
+        World::default()
+            .with_objects(objects)
+            .add_light_source(PointLight::new(
+                Color::white(),
+                Point::new(-10.0, 10.0, -10.0),
+            ))
+    }
+
+    #[test]
+    fn world_bvh_renders_same_image_as_brute_force() {
+        let orientation = Orientation(
+            Vector3::new(0.0, 2.0, -6.0),
+            Vector3::new(0.0, 0.0, 2.0),
+            Vector3::new(0.0, 1.0, 0.0),
+        );
+        let camera = Camera::new(40, 30, PI / 3.0).transform(&orientation.transformation());
+
+        let mut brute_force =
+            camera.sequential_render(&crowded_world().with_acceleration(Acceleration::BruteForce));
+        let mut bvh =
+            camera.sequential_render(&crowded_world().with_acceleration(Acceleration::Bvh));
+
+        let bits = |canvas: &mut Canvas| {
+            canvas
+                .pixels()
+                .iter()
+                .map(|color| [color.r.to_bits(), color.g.to_bits(), color.b.to_bits()])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(bits(&mut brute_force), bits(&mut bvh));
+    }
+
+    #[test]
+    fn world_bvh_shadows_same_as_brute_force() {
+        let brute_force = crowded_world().with_acceleration(Acceleration::BruteForce);
+        let bvh = crowded_world().with_acceleration(Acceleration::Bvh);
+
+        for x in -20..20 {
+            for z in -5..30 {
+                let point = Point::new(x as f64 * 0.25, -0.99, z as f64 * 0.25);
+
+                assert_eq!(brute_force.shadow_cast(&point), bvh.shadow_cast(&point));
+            }
+        }
+    }
+
+    #[test]
+    fn world_bvh_hits_far_along_a_nearly_axis_aligned_ray() {
+        // the ray starts beside the cubes and only drifts into the far one, far beyond the
+        // padding of the bounds
+        let cubes = (0..4)
+            .map(|i| {
+                CubeShape(
+                    Cube::default()
+                        .translate(0.0, 3.0 * i as f64, -100_000.0)
+                        .transform(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let world = |acceleration| {
+            World::default()
+                .with_objects(cubes.clone())
+                .with_acceleration(acceleration)
+        };
+        let (brute_force, bvh) = (world(Acceleration::BruteForce), world(Acceleration::Bvh));
+        let ray = Ray::new(Point::new(1.0005, 0.0, 0.0), Vector3::new(-1e-8, 0.0, -1.0));
+
+        let times = |world: &World| {
+            world
+                .intersect_objects(&ray)
+                .intersections
+                .iter()
+                .map(|intersection| intersection.time.to_bits())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(2, times(&brute_force).len());
+        assert_eq!(times(&brute_force), times(&bvh));
+    }
+
+    #[test]
+    fn world_moving_primitive_shadows_at_the_ray_time() {
+        // a ball leaves the spot right above the floor point between time 0.0 and 1.0
+        let ball = Moving::new(
+            SphereShape(Sphere::default().scale(0.3, 0.3, 0.3).transform()),
+            Translate(0.0, 0.0, 0.0).transformation(),
+            Translate(5.0, 0.0, 0.0).transformation(),
+        );
+        let world = |acceleration| {
+            World::default()
+                .add_object(PlaneShape(
+                    Plane::default().translate(0.0, -1.0, 0.0).transform(),
+                ))
+                .add_object(MovingShape(ball.clone()))
+                .add_light_source(PointLight::new(Color::white(), Point::new(0.0, 10.0, 0.0)))
+                .with_acceleration(acceleration)
+        };
+        let (brute_force, bvh) = (world(Acceleration::BruteForce), world(Acceleration::Bvh));
+        let ray = Ray::new(Point::new(0.0, -0.5, 0.0), Vector3::new(0.0, -1.0, 0.0));
+
+        for time in [0.0, 0.5, 1.0] {
+            let ray = ray.with_time(time);
+            assert_eq!(brute_force.color_at(&ray), bvh.color_at(&ray));
+        }
+
+        let shadowed = bvh.color_at(&ray);
+        let lit = bvh.color_at(&ray.with_time(1.0));
+        assert_eq!(Color::new(0.1, 0.1, 0.1), shadowed);
+        assert!(lit.r > shadowed.r);
+    }
 }